@@ -1,6 +1,7 @@
 use anyhow::Context;
 use cs2::{
     EntitySystem,
+    GameSettings,
     Globals,
 };
 use valthrun_kernel_interface::MouseState;
@@ -73,7 +74,15 @@ impl Enhancement for AntiAimPunsh {
             nalgebra::Vector4::<f32>::zeros()
         };
 
-        let deg_one = settings.mouse_x_360 as f32 / 360.0;
+        let game_counts_per_degree = if settings.mouse_sensitivity_from_game {
+            ctx.states
+                .resolve::<GameSettings>(())
+                .ok()
+                .and_then(|game_settings| game_settings.mouse_counts_per_degree())
+        } else {
+            None
+        };
+        let deg_one = game_counts_per_degree.unwrap_or(settings.mouse_x_360 as f32 / 360.0);
         let target_mouse_y = (total_punch_angle.x * deg_one * -2.25).round() as i32;
         let delta_mouse_y = target_mouse_y - self.mouse_adjustment_y;
         self.mouse_adjustment_y = target_mouse_y;
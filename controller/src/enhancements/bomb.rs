@@ -9,6 +9,7 @@ use cs2_schema_generated::cs2::client::C_CSPlayerPawn;
 
 use super::Enhancement;
 use crate::{
+    localization::GameLocalization,
     settings::AppSettings,
     utils::ImguiUiEx,
     view::ViewController,
@@ -96,118 +97,215 @@ impl Enhancement for BombInfoIndicator {
         }
 
         let bomb_state = states.resolve::<C4>(())?;
+        let localization = states.resolve::<GameLocalization>(())?;
         let view = states.resolve::<ViewController>(())?;
+        render_bomb_panel(
+            ui,
+            &settings,
+            &localization,
+            &bomb_state,
+            Some(&view),
+            self.local_pos,
+        );
 
-        if let Some(state) = &bomb_state.state {
-            let group = ui.begin_group();
-            let line_count = match state {
-                C4State::Active { .. } => 3,
-                C4State::Defused => 2,
-                _ => 0,
-            };
-            let text_height = ui.text_line_height_with_spacing() * line_count as f32;
-
-            /* align to be on the right side after the players */
-            let offset_x = ui.io().display_size[0] * 1730.0 / 2560.0;
-            let offset_y = ui.io().display_size[1] * PLAYER_AVATAR_TOP_OFFSET
-                + 0_f32.max((ui.io().display_size[1] * PLAYER_AVATAR_SIZE - text_height) / 2.0);
-
-            if settings.bomb_esp_settings.bomb_site
-                && matches!(state, C4State::Active { time_detonation: _ })
-            {
-                ui.set_cursor_pos([offset_x, offset_y]);
-                ui.text(&format!(
-                    "Bomb planted {}",
-                    match bomb_state.bomb_site.unwrap_or(0) {
-                        0 => "A",
-                        1 => "B",
-                        _ => unreachable!(),
-                    }
-                ));
-            }
+        Ok(())
+    }
+}
 
-            if settings.bomb_esp_settings.bomb_status {
-                ui.set_cursor_pos_x(offset_x);
-                match state {
-                    C4State::Active { time_detonation } => {
-                        ui.text(&format!("Time: {:.3}", time_detonation));
-
-                        if let Some(defuser) = &bomb_state.defuser {
-                            let color = if defuser.time_remaining > *time_detonation {
-                                [0.79, 0.11, 0.11, 1.0]
-                            } else {
-                                [0.11, 0.79, 0.26, 1.0]
-                            };
-                            ui.set_cursor_pos_x(offset_x);
-                            ui.text_colored(
-                                color,
-                                &format!(
-                                    "Defused in {:.3} by {}",
-                                    defuser.time_remaining, defuser.player_name
-                                ),
-                            );
+/// Draw the bomb status next to the player avatars.
+/// The bombs world position will only be drawn if a view is given.
+pub fn render_bomb_panel(
+    ui: &imgui::Ui,
+    settings: &AppSettings,
+    localization: &GameLocalization,
+    bomb_state: &C4,
+    view: Option<&ViewController>,
+    local_pos: Option<nalgebra::Vector3<f32>>,
+) {
+    if let Some(state) = &bomb_state.state {
+        let group = ui.begin_group();
+        let line_count = match state {
+            C4State::Active { .. } => 3,
+            C4State::Defused => 2,
+            _ => 0,
+        };
+        let text_height = ui.text_line_height_with_spacing() * line_count as f32;
+
+        /* align to be on the right side after the players */
+        let offset_x = ui.io().display_size[0] * 1730.0 / 2560.0;
+        let offset_y = ui.io().display_size[1] * PLAYER_AVATAR_TOP_OFFSET
+            + 0_f32.max((ui.io().display_size[1] * PLAYER_AVATAR_SIZE - text_height) / 2.0);
+
+        if settings.bomb_esp_settings.bomb_site
+            && matches!(state, C4State::Active { time_detonation: _ })
+        {
+            ui.set_cursor_pos([offset_x, offset_y]);
+            ui.text(&format!(
+                "Bomb planted {}",
+                localization.bomb_site_name(bomb_state.bomb_site.unwrap_or(0))
+            ));
+        }
+
+        if settings.bomb_esp_settings.bomb_status {
+            ui.set_cursor_pos_x(offset_x);
+            match state {
+                C4State::Active { time_detonation } => {
+                    ui.text(&format!("Time: {:.3}", time_detonation));
+
+                    if let Some(defuser) = &bomb_state.defuser {
+                        let color = if defuser.time_remaining > *time_detonation {
+                            [0.79, 0.11, 0.11, 1.0]
                         } else {
-                            ui.set_cursor_pos_x(offset_x);
-                            ui.text("Not defusing");
-                        }
-                    }
-                    C4State::Defused => {
-                        ui.text("Bomb has been defused");
+                            [0.11, 0.79, 0.26, 1.0]
+                        };
+                        ui.set_cursor_pos_x(offset_x);
+                        ui.text_colored(
+                            color,
+                            &format!(
+                                "Defused in {:.3} by {}",
+                                defuser.time_remaining, defuser.player_name
+                            ),
+                        );
+                    } else {
+                        ui.set_cursor_pos_x(offset_x);
+                        ui.text("Not defusing");
                     }
-                    _ => {}
                 }
+                C4State::Defused => {
+                    ui.text("Bomb has been defused");
+                }
+                _ => {}
             }
+        }
 
-            if matches!(state, C4State::Dropped)
-                || matches!(state, C4State::Active { time_detonation: _ })
-            {
-                if let Some(pos) = bomb_state.bomb_pos {
-                    if let Some(local_pos) = self.local_pos {
-                        let time_detonation = match state {
-                            C4State::Active { time_detonation } => Some(*time_detonation),
-                            _ => None,
-                        };
-
-                        let distance = (pos - local_pos).norm() * UNITS_TO_METERS;
-                        let color = if settings.bomb_esp_settings.bomb_position {
-                            settings
-                                .bomb_esp_settings
-                                .bomb_position_color
-                                .calculate_color(distance, time_detonation.unwrap_or(40.0))
-                        } else {
-                            [1.0, 1.0, 1.0, 1.0]
-                        };
+        if matches!(state, C4State::Dropped)
+            || matches!(state, C4State::Active { time_detonation: _ })
+        {
+            if let Some(pos) = bomb_state.bomb_pos {
+                if let Some(local_pos) = local_pos {
+                    let time_detonation = match state {
+                        C4State::Active { time_detonation } => Some(*time_detonation),
+                        _ => None,
+                    };
 
-                        if settings.bomb_esp_settings.bomb_position {
-                            if let Some(pos) = view.world_to_screen(&pos, false) {
-                                let y_offset = 0.0;
-                                let draw = ui.get_window_draw_list();
-                                let text = "BOMB";
-                                let [text_width, _] = ui.calc_text_size(&text);
-                                let mut pos = pos.clone();
-                                pos.x -= text_width / 2.0;
-                                pos.y += y_offset;
-                                ui.set_cursor_pos_x(offset_x);
-                                draw.add_text(pos, color, text);
-                            }
-                        }
+                    let distance = (pos - local_pos).norm() * UNITS_TO_METERS;
+                    let color = if settings.bomb_esp_settings.bomb_position {
+                        settings
+                            .bomb_esp_settings
+                            .bomb_position_color
+                            .calculate_color(distance, time_detonation.unwrap_or(40.0))
+                    } else {
+                        [1.0, 1.0, 1.0, 1.0]
+                    };
 
-                        if settings.bomb_esp_settings.is_safe && time_detonation.is_some() {
+                    if settings.bomb_esp_settings.bomb_position {
+                        if let Some(pos) = view.and_then(|view| view.world_to_screen(&pos, false)) {
+                            let y_offset = 0.0;
+                            let draw = ui.get_window_draw_list();
+                            let text = "BOMB";
+                            let [text_width, _] = ui.calc_text_size(&text);
+                            let mut pos = pos.clone();
+                            pos.x -= text_width / 2.0;
+                            pos.y += y_offset;
                             ui.set_cursor_pos_x(offset_x);
-                            if distance > IS_SAFE {
-                                ui.text("You're safe!")
-                            } else {
-                                let test = IS_SAFE - distance;
-                                let text = format!("Back {:.0} m", test);
-                                ui.text(text)
-                            };
+                            draw.add_text(pos, color, text);
                         }
                     }
+
+                    if settings.bomb_esp_settings.is_safe && time_detonation.is_some() {
+                        ui.set_cursor_pos_x(offset_x);
+                        if distance > IS_SAFE {
+                            ui.text("You're safe!")
+                        } else {
+                            let test = IS_SAFE - distance;
+                            let text = format!("Back {:.0} m", test);
+                            ui.text(text)
+                        };
+                    }
                 }
             }
-            group.end();
         }
+        group.end();
+    }
+}
 
-        Ok(())
+#[cfg(test)]
+mod test {
+    use std::{
+        io::Cursor,
+        path::Path,
+    };
+
+    use cs2::{
+        BombDefuser,
+        C4State,
+        StateFrame,
+        StateRecorder,
+        StateReplay,
+        C4,
+    };
+    use utils_reference_renderer::{
+        assert_matches_baseline,
+        FrameHarness,
+    };
+    use utils_state::StateRegistry;
+
+    use super::render_bomb_panel;
+    use crate::{
+        localization::GameLocalization,
+        settings::AppSettings,
+    };
+
+    fn record_bomb(bomb: C4) -> Vec<u8> {
+        let mut states = StateRegistry::new(1024);
+        states.set(bomb, ()).unwrap();
+
+        let mut recorder = StateRecorder::new(Vec::new()).unwrap();
+        recorder.write_frame(&StateFrame::capture(&states)).unwrap();
+        recorder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_render_replay() {
+        let recording = record_bomb(C4 {
+            state: Some(C4State::Active {
+                time_detonation: 24.15,
+            }),
+            bomb_pos: None,
+            bomb_site: Some(1),
+            defuser: Some(BombDefuser {
+                time_remaining: 4.32,
+                player_name: "Defuser".to_string(),
+            }),
+            owner: None,
+        });
+
+        let mut states = StateRegistry::new(1024);
+        let mut replay = StateReplay::new(Cursor::new(recording)).unwrap();
+        assert!(replay.replay_frame(&mut states).unwrap().is_some());
+        assert!(replay.next_frame().unwrap().is_none());
+
+        let mut settings: AppSettings = serde_yaml::from_str("").unwrap();
+        settings.bomb_esp_settings.bomb_site = true;
+        settings.bomb_esp_settings.bomb_status = true;
+
+        let localization = GameLocalization::builtin();
+        let bomb = states.resolve::<C4>(()).unwrap();
+
+        let image = FrameHarness::new(640, 120)
+            .render(|ui| {
+                ui.window("overlay")
+                    .draw_background(false)
+                    .no_decoration()
+                    .size(ui.io().display_size, imgui::Condition::Always)
+                    .position([0.0, 0.0], imgui::Condition::Always)
+                    .build(|| render_bomb_panel(ui, &settings, &localization, &bomb, None, None));
+            })
+            .unwrap();
+
+        let baseline = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("baselines")
+            .join("bomb_panel.png");
+        assert_matches_baseline(&image, &baseline, 2).unwrap();
     }
 }
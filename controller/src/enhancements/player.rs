@@ -1,7 +1,14 @@
+use std::{
+    sync::Arc,
+    thread,
+};
+
 use cs2::{
     BoneFlags,
     CEntityIdentityEx,
+    CS2HandleState,
     CS2Model,
+    CS2Offsets,
     ClassNameCache,
     EntitySystem,
     LocalCameraControllerTarget,
@@ -9,12 +16,16 @@ use cs2::{
     PlayerPawnState,
     C4,
 };
-use cs2_schema_generated::cs2::client::C_CSPlayerPawn;
 use imgui::ImColor32;
 use obfstr::obfstr;
+use utils_state::{
+    SharedStateRegistry,
+    StateRegistry,
+};
 
 use super::Enhancement;
 use crate::{
+    localization::GameLocalization,
     settings::{
         AppSettings,
         EspBoxType,
@@ -30,10 +41,17 @@ use crate::{
     },
 };
 
+/// Max amount of threads used to resolve the player pawns
+const PAWN_WORKER_COUNT: usize = 4;
+
 pub struct PlayerESP {
     toggle: KeyToggle,
     players: Vec<PlayerPawnInfo>,
     local_team_id: u8,
+
+    /// Player pawns and the states shared by the pawn workers (entity system and models)
+    pawn_states: Arc<SharedStateRegistry>,
+    pawn_workers: Vec<StateRegistry>,
 }
 
 impl PlayerESP {
@@ -42,9 +60,30 @@ impl PlayerESP {
             toggle: KeyToggle::new(),
             players: Default::default(),
             local_team_id: 0,
+
+            pawn_states: Arc::new(SharedStateRegistry::new(256)),
+            pawn_workers: Vec::new(),
         }
     }
 
+    fn create_pawn_workers(&mut self, ctx: &crate::UpdateContext) -> anyhow::Result<()> {
+        let offsets = ctx.states.resolve::<CS2Offsets>(())?;
+        let worker_count = thread::available_parallelism()
+            .map(|count| count.get())
+            .unwrap_or(1)
+            .clamp(1, PAWN_WORKER_COUNT);
+
+        for _ in 0..worker_count {
+            let mut states = StateRegistry::new(1024);
+            states.set(CS2HandleState::new(ctx.cs2.clone()), ())?;
+            states.set(offsets.clone(), ())?;
+            states.set_shared_registry(self.pawn_states.clone());
+            self.pawn_workers.push(states);
+        }
+
+        Ok(())
+    }
+
     fn resolve_esp_player_config<'a>(
         &self,
         settings: &'a AppSettings,
@@ -154,7 +193,7 @@ impl Enhancement for PlayerESP {
         let settings = ctx.states.resolve::<AppSettings>(())?;
         if self
             .toggle
-            .update(&settings.esp_mode, ctx.input, &settings.esp_toogle)
+            .update(&settings.esp_mode, ctx.input, &settings.esp_toggle)
         {
             ctx.cs2.add_metrics_record(
                 obfstr!("feature-esp-toggle"),
@@ -186,8 +225,10 @@ impl Enhancement for PlayerESP {
             None => return Ok(()),
         };
 
+        let mut pawn_entity_indices = Vec::with_capacity(16);
         for entity_identity in entities.all_identities() {
-            if entity_identity.handle::<()>()?.get_entity_index() == target_entity_id {
+            let entity_index = entity_identity.handle::<()>()?.get_entity_index();
+            if entity_index == target_entity_id {
                 continue;
             }
 
@@ -200,22 +241,44 @@ impl Enhancement for PlayerESP {
                 continue;
             }
 
-            let player_pawn = entity_identity.entity_ptr::<C_CSPlayerPawn>()?;
-            match ctx
-                .states
-                .resolve::<PlayerPawnState>(entity_identity.handle::<()>()?.get_entity_index())
+            pawn_entity_indices.push(entity_index);
+        }
+
+        if self.pawn_workers.is_empty() {
+            self.create_pawn_workers(ctx)?;
+        }
+
+        self.pawn_states.invalidate_states();
+        for worker in self.pawn_workers.iter_mut() {
+            worker.invalidate_states();
+        }
+
+        /* the workers use the entity system of this frame instead of creating their own */
+        self.pawn_states.set(entities.clone(), ())?;
+
+        /* every pawn can be read independently, therefore resolve them in parallel */
+        let results = self.pawn_states.resolve_parallel::<PlayerPawnState>(
+            &mut self.pawn_workers,
+            pawn_entity_indices.clone(),
+        );
+
+        for (entity_index, result) in pawn_entity_indices.into_iter().zip(results) {
+            if let Err(error) = result {
+                log::warn!(
+                    "Failed to generate player pawn ESP info for {}: {:#}",
+                    entity_index,
+                    error
+                );
+                continue;
+            }
+
+            match self
+                .pawn_states
+                .get::<PlayerPawnState>(entity_index)
+                .as_deref()
             {
-                Ok(info) => match &*info {
-                    PlayerPawnState::Alive(info) => self.players.push(info.clone()),
-                    PlayerPawnState::Dead => continue,
-                },
-                Err(error) => {
-                    log::warn!(
-                        "Failed to generate player pawn ESP info for {:X}: {:#}",
-                        player_pawn.address()?,
-                        error
-                    );
-                }
+                Some(PlayerPawnState::Alive(info)) => self.players.push(info.clone()),
+                Some(PlayerPawnState::Dead) | None => continue,
             }
         }
 
@@ -226,6 +289,7 @@ impl Enhancement for PlayerESP {
         let settings = states.resolve::<AppSettings>(())?;
         let view = states.resolve::<ViewController>(())?;
         let bomb_state = states.resolve::<C4>(())?;
+        let localization = states.resolve::<GameLocalization>(())?;
 
         let draw = ui.get_window_draw_list();
         const UNITS_TO_METERS: f32 = 0.01905;
@@ -249,7 +313,9 @@ impl Enhancement for PlayerESP {
 
             let player_rel_health = (entry.player_health as f32 / 100.0).clamp(0.0, 1.0);
 
-            let entry_model = states.resolve::<CS2Model>(entry.model_address)?;
+            let entry_model = self
+                .pawn_states
+                .resolve::<CS2Model>(states, entry.model_address)?;
             let player_2d_box = view.calculate_box_2d(
                 &(entry_model.vhull_min + entry.position),
                 &(entry_model.vhull_max + entry.position),
@@ -448,7 +514,7 @@ impl Enhancement for PlayerESP {
                 }
 
                 if esp_settings.info_weapon {
-                    let text = entry.weapon.display_name();
+                    let text = localization.weapon_name(&entry.weapon);
                     player_info.add_line(
                         esp_settings
                             .info_weapon_color
@@ -30,23 +30,72 @@ impl Enhancement for SpectatorsListIndicator {
             None => return Ok(()),
         };
         let spectators = states.resolve::<SpectatorList>(target_entity_id)?;
+        render_spectators_list(ui, &spectators);
+        Ok(())
+    }
+}
 
-        let group = ui.begin_group();
+/// Draw the names of the spectators vertically centered on the left side of the screen
+pub fn render_spectators_list(ui: &imgui::Ui, spectators: &SpectatorList) {
+    let group = ui.begin_group();
 
-        let line_count = spectators.spectators.iter().count();
-        let text_height = ui.text_line_height_with_spacing() * line_count as f32;
+    let line_count = spectators.spectators.iter().count();
+    let text_height = ui.text_line_height_with_spacing() * line_count as f32;
 
-        let offset_x = ui.io().display_size[0] * 0.01;
-        let offset_y = (ui.io().display_size[1] - text_height) * 0.5;
-        let mut offset_y = offset_y;
+    let offset_x = ui.io().display_size[0] * 0.01;
+    let offset_y = (ui.io().display_size[1] - text_height) * 0.5;
+    let mut offset_y = offset_y;
 
-        for spectator in &spectators.spectators {
-            ui.set_cursor_pos([offset_x, offset_y]);
-            ui.text(&spectator.spectator_name);
-            offset_y += ui.text_line_height_with_spacing();
-        }
+    for spectator in &spectators.spectators {
+        ui.set_cursor_pos([offset_x, offset_y]);
+        ui.text(&spectator.spectator_name);
+        offset_y += ui.text_line_height_with_spacing();
+    }
 
-        group.end();
-        Ok(())
+    group.end();
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use cs2::{
+        SpectatorInfo,
+        SpectatorList,
+    };
+    use utils_reference_renderer::{
+        assert_matches_baseline,
+        FrameHarness,
+    };
+
+    use super::render_spectators_list;
+
+    #[test]
+    fn test_spectators_list_baseline() {
+        let spectators = SpectatorList {
+            target_entity_id: 1,
+            spectators: ["Alice", "Bob", "Charlie"]
+                .into_iter()
+                .map(|name| SpectatorInfo {
+                    spectator_name: name.to_string(),
+                })
+                .collect(),
+        };
+
+        let image = FrameHarness::new(320, 180)
+            .render(|ui| {
+                ui.window("overlay")
+                    .draw_background(false)
+                    .no_decoration()
+                    .size(ui.io().display_size, imgui::Condition::Always)
+                    .position([0.0, 0.0], imgui::Condition::Always)
+                    .build(|| render_spectators_list(ui, &spectators));
+            })
+            .unwrap();
+
+        let baseline = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("baselines")
+            .join("spectators_list.png");
+        assert_matches_baseline(&image, &baseline, 2).unwrap();
     }
 }
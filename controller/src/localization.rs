@@ -0,0 +1,211 @@
+use std::{
+    borrow::Cow,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use cs2::{
+    GameSettings,
+    WeaponId,
+};
+use map_tracer::{
+    KV1Value,
+    Localization,
+    LOCALIZATION_FALLBACK_LANGUAGE,
+};
+use obfstr::obfstr;
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+
+use crate::settings::AppSettings;
+
+const CS2_APP_ID: &str = "730";
+
+/// Find the games install directory using the Steam library configuration
+pub fn find_game_directory() -> Option<PathBuf> {
+    let program_files = std::env::var("ProgramFiles(x86)")
+        .unwrap_or_else(|_| obfstr!("C:\\Program Files (x86)").to_string());
+    let steam_directory = PathBuf::from(program_files).join("Steam");
+
+    let mut libraries = vec![steam_directory.clone()];
+    match fs::read_to_string(steam_directory.join("steamapps").join("libraryfolders.vdf")) {
+        Ok(library_folders) => match KV1Value::parse(&library_folders) {
+            Ok(root) => {
+                for (_, library_folders) in root.iter() {
+                    for (_, library) in library_folders.entries() {
+                        let has_game = library
+                            .get("apps")
+                            .map(|apps| apps.get(CS2_APP_ID).is_some())
+                            .unwrap_or(false);
+
+                        if let (true, Some(path)) = (has_game, library.get_str("path")) {
+                            libraries.insert(0, PathBuf::from(path));
+                        }
+                    }
+                }
+            }
+            Err(error) => log::warn!("Failed to parse the Steam library folders: {}", error),
+        },
+        Err(error) => log::debug!("Failed to read the Steam library folders: {}", error),
+    }
+
+    libraries
+        .into_iter()
+        .map(|library| {
+            library
+                .join("steamapps")
+                .join("common")
+                .join(obfstr!("Counter-Strike Global Offensive"))
+        })
+        .find(|directory| directory.is_dir())
+}
+
+/// Directory containing the localization files (*_<language>.txt)
+fn find_resource_directory(game_directory: &Path) -> Option<PathBuf> {
+    let resource_directory = game_directory.join("game").join("csgo").join("resource");
+    [resource_directory.join("localization"), resource_directory]
+        .into_iter()
+        .find(|directory| {
+            Localization::available_languages(directory)
+                .map(|languages| !languages.is_empty())
+                .unwrap_or(false)
+        })
+}
+
+/// Localized display strings of the game.
+/// Follows the language of the game unless the user selected a language.
+pub struct GameLocalization {
+    resource_directory: Option<PathBuf>,
+    available_languages: Vec<String>,
+
+    /// Game directory and language the current localization has been loaded for
+    loaded_key: Option<(Option<String>, String)>,
+    localization: Option<Localization>,
+}
+
+impl State for GameLocalization {
+    type Parameter = ();
+
+    fn create(_states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        Ok(Self::builtin())
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+
+    fn update(&mut self, states: &StateRegistry) -> anyhow::Result<()> {
+        let settings = states.resolve::<AppSettings>(())?;
+        let language = match &settings.localization_language {
+            Some(language) => language.clone(),
+            None => states
+                .resolve::<GameSettings>(())
+                .ok()
+                .and_then(|game_settings| game_settings.language.clone())
+                .unwrap_or_else(|| LOCALIZATION_FALLBACK_LANGUAGE.to_string()),
+        };
+
+        let key = (
+            settings.game_directory.clone(),
+            language.to_ascii_lowercase(),
+        );
+        if self.loaded_key.as_ref() == Some(&key) {
+            return Ok(());
+        }
+
+        self.loaded_key = Some(key);
+        self.localization = None;
+        self.available_languages.clear();
+
+        let game_directory = match &settings.game_directory {
+            Some(directory) => Some(PathBuf::from(directory)),
+            None => find_game_directory(),
+        };
+        self.resource_directory = game_directory
+            .as_ref()
+            .and_then(|directory| find_resource_directory(directory));
+
+        let Some(resource_directory) = &self.resource_directory else {
+            log::warn!("Failed to find the games resource directory. Using built in names.");
+            return Ok(());
+        };
+
+        self.available_languages =
+            Localization::available_languages(resource_directory).unwrap_or_default();
+        match Localization::load_resource_directory(resource_directory, &language) {
+            Ok(localization) => {
+                log::debug!(
+                    "Loaded {} localization tokens for {}",
+                    localization.token_count(),
+                    localization.language()
+                );
+                self.localization = Some(localization);
+            }
+            Err(error) => log::warn!("Failed to load the game localization: {}", error),
+        }
+
+        Ok(())
+    }
+}
+
+impl GameLocalization {
+    /// Localization without any game files, only providing the built in names
+    pub fn builtin() -> Self {
+        Self {
+            resource_directory: None,
+            available_languages: Vec::new(),
+
+            loaded_key: None,
+            localization: None,
+        }
+    }
+
+    pub fn language(&self) -> Option<&str> {
+        self.localization
+            .as_ref()
+            .map(|localization| localization.language())
+    }
+
+    /// Languages which can be selected
+    pub fn available_languages(&self) -> &[String] {
+        &self.available_languages
+    }
+
+    /// Lookup a localization token (e.g. #SFUI_WPNHUD_AK47)
+    pub fn lookup(&self, token: &str) -> Option<&str> {
+        self.localization.as_ref()?.lookup(token)
+    }
+
+    pub fn weapon_name(&self, weapon: &WeaponId) -> Cow<'_, str> {
+        match weapon
+            .localization_token()
+            .and_then(|token| self.lookup(token))
+        {
+            Some(name) => Cow::Borrowed(name),
+            None => weapon.display_name(),
+        }
+    }
+
+    /// Label of a bomb site (0 = A, 1 = B)
+    pub fn bomb_site_name(&self, bomb_site: u8) -> &str {
+        let (token, fallback) = match bomb_site {
+            0 => ("#SFUI_Bombsite_A", "A"),
+            1 => ("#SFUI_Bombsite_B", "B"),
+            _ => return "?",
+        };
+
+        self.lookup(token).unwrap_or(fallback)
+    }
+
+    /// Display title of a map (e.g. de_dust2 -> Dust II)
+    pub fn map_title<'a>(&'a self, map_name: &'a str) -> &'a str {
+        self.lookup(&format!("#SFUI_Map_{}", map_name))
+            .unwrap_or(map_name)
+    }
+}
@@ -11,9 +11,15 @@ use std::{
     error::Error,
     fmt::Debug,
     fs::File,
-    io::BufWriter,
+    io::{
+        BufWriter,
+        Write,
+    },
     mem,
-    path::PathBuf,
+    path::{
+        Path,
+        PathBuf,
+    },
     rc::Rc,
     sync::{
         atomic::{
@@ -34,6 +40,7 @@ use clap::{
     Args,
     Parser,
     Subcommand,
+    ValueEnum,
 };
 use cs2::{
     offsets_runtime,
@@ -41,28 +48,42 @@ use cs2::{
     CS2Handle,
     CS2HandleState,
     CS2Offsets,
+    ConVars,
+    GameSettings,
+    OffsetCache,
+    StateRecorder,
+    PAGE_CACHE_DEFAULT_CAPACITY,
+};
+use cs2_schema_generated::{
+    definition::RustOffsetMode,
+    export,
 };
 use enhancements::Enhancement;
 use imgui::{
     Condition,
     FontConfig,
-    FontId,
-    FontSource,
     Ui,
 };
 use libloading::Library;
 use obfstr::obfstr;
 use overlay::{
+    FontData,
+    FontReference,
     LoadingError,
     OverlayError,
+    OverlayFontSource,
     OverlayOptions,
     OverlayTarget,
     SystemRuntimeController,
+    DEFAULT_FONT_SIZE,
 };
 use radar::WebRadar;
 use settings::{
     load_app_settings,
+    switch_to_next_profile,
     AppSettings,
+    AppSettingsWatcher,
+    SettingsFileChange,
     SettingsUI,
 };
 use tokio::runtime;
@@ -100,6 +121,7 @@ use crate::{
 
 mod cache;
 mod enhancements;
+mod localization;
 mod map;
 mod physics;
 mod radar;
@@ -145,7 +167,7 @@ pub struct UpdateContext<'a> {
 }
 
 pub struct AppFonts {
-    valthrun: FontId,
+    valthrun: FontReference,
 }
 
 pub struct Application {
@@ -160,11 +182,15 @@ pub struct Application {
 
     pub settings_visible: bool,
     pub settings_dirty: bool,
+    pub settings_watcher: AppSettingsWatcher,
     pub settings_ui: RefCell<SettingsUI>,
     pub settings_screen_capture_changed: AtomicBool,
     pub settings_render_debug_window_changed: AtomicBool,
 
     pub web_radar: RefCell<Option<Arc<Mutex<WebRadar>>>>,
+
+    /// Records the game states of every frame (see --record)
+    pub state_recorder: Option<StateRecorder<BufWriter<File>>>,
 }
 
 impl Application {
@@ -181,7 +207,39 @@ impl Application {
     }
 
     pub fn pre_update(&mut self, controller: &mut SystemRuntimeController) -> anyhow::Result<()> {
-        if self.settings_dirty {
+        {
+            let mut settings = self
+                .app_state
+                .get_mut::<AppSettings>(())
+                .expect("app settings to be present");
+
+            /* merge external changes before saving to not overwrite them */
+            let change = if self.settings_dirty {
+                self.settings_watcher.check(&mut settings)
+            } else {
+                self.settings_watcher.poll(&mut settings)
+            };
+
+            match change {
+                Some(SettingsFileChange::Reloaded {
+                    unsaved_changes, ..
+                }) => {
+                    self.settings_dirty |= unsaved_changes;
+                    self.settings_screen_capture_changed
+                        .store(true, Ordering::Relaxed);
+                    self.settings_render_debug_window_changed
+                        .store(true, Ordering::Relaxed);
+                }
+                Some(SettingsFileChange::Removed) => {
+                    /* recreate the config file */
+                    self.settings_dirty = true;
+                }
+                _ => {}
+            }
+        }
+
+        /* do not overwrite an invalid config file, the user is probably still editing it */
+        if self.settings_dirty && self.settings_watcher.error().is_none() {
             self.settings_dirty = false;
             let mut settings = self.settings_mut();
 
@@ -196,7 +254,9 @@ impl Application {
 
             if let Err(error) = save_app_settings(&*settings) {
                 log::warn!("Failed to save user settings: {}", error);
-            };
+            } else if let Err(error) = self.settings_watcher.mark_saved(&settings) {
+                log::warn!("Failed to track saved user settings: {}", error);
+            }
         }
 
         if self
@@ -219,6 +279,23 @@ impl Application {
             controller.toggle_debug_overlay(settings.render_debug_window);
         }
 
+        {
+            let settings = self.settings();
+            let mut font_scale = settings.font_scale;
+            if settings.font_scale_from_game {
+                let hud_scale = self
+                    .app_state
+                    .resolve::<GameSettings>(())
+                    .ok()
+                    .and_then(|game_settings| game_settings.hud_scale);
+                if let Some(hud_scale) = hud_scale.filter(|scale| *scale > 0.0) {
+                    font_scale *= hud_scale;
+                }
+            }
+
+            controller.fonts.set_scale(font_scale);
+        }
+
         Ok(())
     }
 
@@ -246,6 +323,19 @@ impl Application {
             }
         }
 
+        let key_next_profile = self.settings().key_next_profile.clone();
+        if let Some(key) = key_next_profile {
+            if ui.is_key_pressed_no_repeat(key.0) {
+                match switch_to_next_profile(&mut self.settings_mut()) {
+                    Ok(Some(profile)) => log::info!("Switched to settings profile {}", profile),
+                    Ok(None) => log::debug!("No settings profiles available"),
+                    Err(error) => log::warn!("Failed to switch the settings profile: {:#}", error),
+                }
+
+                self.settings_dirty = true;
+            }
+        }
+
         self.app_state.invalidate_states();
         if let Ok(mut view_controller) = self.app_state.resolve_mut::<ViewController>(()) {
             view_controller.update_screen_bounds(mint::Vector2::from_slice(&ui.io().display_size));
@@ -263,7 +353,17 @@ impl Application {
             hack.update(&update_context)?;
         }
 
-        let read_calls = self.cs2.ke_interface.total_read_calls();
+        if let Some(recorder) = &mut self.state_recorder {
+            if let Err(error) = recorder.record_frame(&self.app_state) {
+                log::error!(
+                    "Failed to record frame, stopping the recording: {:#}",
+                    error
+                );
+                self.state_recorder = None;
+            }
+        }
+
+        let read_calls = self.cs2.total_read_calls();
         self.frame_read_calls = read_calls - self.last_total_read_calls;
         self.last_total_read_calls = read_calls;
 
@@ -324,6 +424,17 @@ impl Application {
             }
         }
 
+        if self.settings_watcher.error().is_some() {
+            let text_buf;
+            let text = obfstr!(text_buf = "config.yaml is invalid");
+
+            ui.set_cursor_pos([
+                ui.window_size()[0] - ui.calc_text_size(text)[0] - 10.0,
+                52.0,
+            ]);
+            ui.text_colored([1.0, 0.3, 0.3, 1.0], text);
+        }
+
         for hack in self.enhancements.iter() {
             let hack = hack.borrow();
             if let Err(err) = hack.render(&self.app_state, ui) {
@@ -372,7 +483,8 @@ fn main() {
     let command = args.command.as_ref().unwrap_or(&AppCommand::Overlay);
     let result = match command {
         AppCommand::DumpSchema(args) => main_schema_dump(args),
-        AppCommand::Overlay => main_overlay(),
+        AppCommand::DumpConVars(args) => main_convar_dump(args),
+        AppCommand::Overlay => main_overlay(&args),
     };
 
     if let Err(error) = result {
@@ -387,6 +499,15 @@ struct AppArgs {
     #[clap(short, long)]
     verbose: bool,
 
+    /// Ignore the offset cache and scan for all offsets again
+    #[clap(long)]
+    refresh_offsets: bool,
+
+    /// Record the game states of every frame into the given file.
+    /// The recording can be replayed with the standalone radar (--replay).
+    #[clap(long)]
+    record: Option<PathBuf>,
+
     #[clap(subcommand)]
     command: Option<AppCommand>,
 }
@@ -398,6 +519,46 @@ enum AppCommand {
 
     /// Create a schema dump
     DumpSchema(SchemaDumpArgs),
+
+    /// Dump all convars and their values
+    DumpConVars(ConVarDumpArgs),
+}
+
+#[derive(Debug, Args)]
+struct ConVarDumpArgs {
+    pub target_file: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SchemaDumpFormat {
+    /// Raw schema (input for the cs2-schema-generated build script)
+    Json,
+
+    /// C++ header with padded structs
+    Cpp,
+
+    /// Rust definitions with fixed offsets (can be used as pinned cs2_schema.rs)
+    Rust,
+
+    /// Markdown class reference
+    Markdown,
+
+    /// HTML class reference
+    Html,
+}
+
+impl SchemaDumpFormat {
+    fn from_extension(target_file: &Path) -> Option<Self> {
+        let extension = target_file.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Some(Self::Json),
+            "h" | "hpp" => Some(Self::Cpp),
+            "rs" => Some(Self::Rust),
+            "md" => Some(Self::Markdown),
+            "html" | "htm" => Some(Self::Html),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Args)]
@@ -406,6 +567,10 @@ struct SchemaDumpArgs {
 
     #[clap(long, short, default_value_t = false)]
     pub all_classes: bool,
+
+    /// Output format (defaults to the format matching the target file extension or json)
+    #[clap(long, short, value_enum)]
+    pub format: Option<SchemaDumpFormat>,
 }
 
 fn is_console_invoked() -> bool {
@@ -422,6 +587,46 @@ fn main_schema_dump(args: &SchemaDumpArgs) -> anyhow::Result<()> {
     let cs2 = CS2Handle::create(true)?;
     let schema = cs2::dump_schema(&cs2, !args.all_classes)?;
 
+    let output = File::options()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .open(&args.target_file)?;
+
+    let format = args
+        .format
+        .or_else(|| SchemaDumpFormat::from_extension(&args.target_file))
+        .unwrap_or(SchemaDumpFormat::Json);
+
+    let mut output = BufWriter::new(output);
+    match format {
+        SchemaDumpFormat::Json => serde_json::to_writer_pretty(&mut output, &schema)?,
+        SchemaDumpFormat::Cpp => export::emit_cpp_header(&schema, &mut output)?,
+        SchemaDumpFormat::Rust => {
+            let unmapped = export::emit_rust_source(&schema, &mut output, RustOffsetMode::Fixed)?;
+            if !unmapped.is_empty() {
+                log::warn!("{} schema fields could not be mapped", unmapped.len());
+            }
+        }
+        SchemaDumpFormat::Markdown => export::emit_markdown(&schema, &mut output)?,
+        SchemaDumpFormat::Html => export::emit_html(&schema, &mut output)?,
+    }
+    output.flush()?;
+
+    log::info!(
+        "Schema dumped to {} ({:?})",
+        args.target_file.to_string_lossy(),
+        format
+    );
+    Ok(())
+}
+
+fn main_convar_dump(args: &ConVarDumpArgs) -> anyhow::Result<()> {
+    log::info!("Dumping convars. Please wait...");
+
+    let cs2 = CS2Handle::create(true)?;
+    let con_vars = ConVars::new(cs2)?.read_all()?;
+
     let output = File::options()
         .create(true)
         .truncate(true)
@@ -429,8 +634,14 @@ fn main_schema_dump(args: &SchemaDumpArgs) -> anyhow::Result<()> {
         .open(&args.target_file)?;
 
     let mut output = BufWriter::new(output);
-    serde_json::to_writer_pretty(&mut output, &schema)?;
-    log::info!("Schema dumped to {}", args.target_file.to_string_lossy());
+    serde_json::to_writer_pretty(&mut output, &con_vars)?;
+    output.flush()?;
+
+    log::info!(
+        "Dumped {} convars to {}",
+        con_vars.len(),
+        args.target_file.to_string_lossy()
+    );
     Ok(())
 }
 
@@ -451,7 +662,14 @@ fn preload_vulkan_with_act_ctx() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn main_overlay() -> anyhow::Result<()> {
+fn get_offset_cache_path() -> anyhow::Result<PathBuf> {
+    let exe_file = std::env::current_exe().context("missing current exe path")?;
+    let base_dir = exe_file.parent().context("could not get exe directory")?;
+
+    Ok(base_dir.join("offsets_cache.json"))
+}
+
+fn main_overlay(args: &AppArgs) -> anyhow::Result<()> {
     let build_info = version_info()?;
     log::info!(
         "{} v{} ({}). Windows build {}.",
@@ -476,6 +694,7 @@ fn main_overlay() -> anyhow::Result<()> {
     }
 
     let settings = load_app_settings()?;
+    let settings_watcher = AppSettingsWatcher::new(&settings)?;
     let cs2 = match CS2Handle::create(settings.metrics) {
         Ok(handle) => handle,
         Err(err) => {
@@ -528,11 +747,17 @@ fn main_overlay() -> anyhow::Result<()> {
 
     cs2.add_metrics_record(obfstr!("controller-status"), "initializing");
 
+    cs2.page_cache().set_capacity(PAGE_CACHE_DEFAULT_CAPACITY);
+
     let mut app_state = StateRegistry::new(1024 * 8);
     app_state.set(CS2HandleState::new(cs2.clone()), ())?;
     app_state.set(settings, ())?;
+    app_state.add_invalidation_hook({
+        let cs2 = cs2.clone();
+        move || cs2.page_cache().invalidate()
+    });
 
-    {
+    let cs2_revision = {
         let cs2_build_info = app_state.resolve::<BuildInfo>(()).with_context(|| {
             obfstr!(
                 "Failed to load CS2 build info. CS2 version might be newer / older then expected"
@@ -550,41 +775,33 @@ fn main_overlay() -> anyhow::Result<()> {
             obfstr!("cs2-version"),
             &format!("revision: {}", cs2_build_info.revision),
         );
-    }
 
-    offsets_runtime::setup_provider(&cs2)?;
+        cs2_build_info.revision.clone()
+    };
+
+    let offset_cache_path = get_offset_cache_path()?;
+    let mut offset_cache = OffsetCache::load_or_create(
+        &cs2,
+        &cs2_revision,
+        &offset_cache_path,
+        args.refresh_offsets,
+    )?;
+
+    offsets_runtime::setup_provider_cached(&cs2, &mut offset_cache)?;
     app_state
         .resolve::<CS2Offsets>(())
         .with_context(|| obfstr!("failed to load CS2 offsets").to_string())?;
 
+    if let Err(error) = offset_cache.store(&cs2, &offset_cache_path) {
+        log::warn!("Failed to store the offset cache: {:#}", error);
+    }
+
     log::debug!("Initialize overlay");
-    let app_fonts: Rc<RefCell<Option<AppFonts>>> = Default::default();
+    #[allow(deprecated)]
     let overlay_options = OverlayOptions {
         title: obfstr!("CS2 Overlay").to_string(),
         target: OverlayTarget::WindowOfProcess(cs2.process_id() as u32),
-        font_init: Some(Box::new({
-            let app_fonts = app_fonts.clone();
-
-            move |imgui| {
-                let mut app_fonts = app_fonts.borrow_mut();
-
-                let font_size = 18.0;
-                let valthrun_font = imgui.fonts().add_font(&[FontSource::TtfData {
-                    data: include_bytes!("../resources/Valthrun-Regular.ttf"),
-                    size_pixels: font_size,
-                    config: Some(FontConfig {
-                        rasterizer_multiply: 1.5,
-                        oversample_h: 4,
-                        oversample_v: 4,
-                        ..FontConfig::default()
-                    }),
-                }]);
-
-                *app_fonts = Some(AppFonts {
-                    valthrun: valthrun_font,
-                });
-            }
-        })),
+        font_init: None,
     };
 
     let mut overlay = match overlay::init(&overlay_options) {
@@ -613,13 +830,38 @@ fn main_overlay() -> anyhow::Result<()> {
         if let Some(imgui_settings) = &settings.imgui {
             overlay.imgui.load_ini_settings(imgui_settings);
         }
+
+        overlay.fonts.set_scale(settings.font_scale);
     }
 
+    let app_fonts = AppFonts {
+        valthrun: overlay.fonts.register_font(
+            vec![OverlayFontSource::with_config(
+                FontData::Static(include_bytes!("../resources/Valthrun-Regular.ttf")),
+                FontConfig {
+                    rasterizer_multiply: 1.5,
+                    oversample_h: 4,
+                    oversample_v: 4,
+                    ..FontConfig::default()
+                },
+            )],
+            DEFAULT_FONT_SIZE,
+        ),
+    };
+
+    let state_recorder = match &args.record {
+        Some(path) => {
+            let output = File::create(path)
+                .with_context(|| format!("failed to create recording {}", path.display()))?;
+
+            log::info!("Recording game states to {}", path.display());
+            Some(StateRecorder::new(BufWriter::new(output))?)
+        }
+        None => None,
+    };
+
     let app = Application {
-        fonts: app_fonts
-            .borrow_mut()
-            .take()
-            .context("failed to initialize app fonts")?,
+        fonts: app_fonts,
 
         app_state,
 
@@ -640,10 +882,13 @@ fn main_overlay() -> anyhow::Result<()> {
 
         settings_visible: false,
         settings_dirty: false,
+        settings_watcher,
         settings_ui: RefCell::new(SettingsUI::new()),
         /* set the screen capture visibility at the beginning of the first update */
         settings_screen_capture_changed: AtomicBool::new(true),
         settings_render_debug_window_changed: AtomicBool::new(true),
+
+        state_recorder,
     };
     let app = Rc::new(RefCell::new(app));
 
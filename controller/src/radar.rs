@@ -1,7 +1,10 @@
-use std::sync::{
-    Arc,
-    Mutex,
-    Weak,
+use std::{
+    sync::{
+        Arc,
+        Mutex,
+        Weak,
+    },
+    time::Instant,
 };
 
 use cs2::{
@@ -10,6 +13,8 @@ use cs2::{
 };
 use radar_client::{
     CS2RadarGenerator,
+    PublishSessionController,
+    PublishSessionOptions,
     WebRadarPublisher,
 };
 use tokio::{
@@ -33,6 +38,10 @@ pub struct WebRadar {
     endpoint: Url,
     connection_state: WebRadarState,
 
+    session: Option<PublishSessionController>,
+    session_expires_at: Option<Instant>,
+    password_protected: bool,
+
     disconnect_tx: Option<oneshot::Sender<()>>,
 }
 
@@ -40,15 +49,17 @@ impl WebRadar {
     async fn create_connection(
         endpoint: &Url,
         cs2: Arc<CS2Handle>,
+        options: PublishSessionOptions,
     ) -> anyhow::Result<WebRadarPublisher> {
         let radar_generator = {
             let mut states = StateRegistry::new(1024 * 8);
-            states.set(CS2HandleState::new(cs2), ())?;
+            states.set(CS2HandleState::new(cs2.clone()), ())?;
+            states.add_invalidation_hook(move || cs2.page_cache().invalidate());
 
             Box::new(CS2RadarGenerator::new(states)?)
         };
 
-        WebRadarPublisher::connect(radar_generator, endpoint).await
+        WebRadarPublisher::connect(radar_generator, endpoint, options).await
     }
 
     pub fn endpoint(&self) -> &Url {
@@ -59,6 +70,29 @@ impl WebRadar {
         &self.connection_state
     }
 
+    pub fn session_expires_at(&self) -> Option<Instant> {
+        self.session_expires_at
+    }
+
+    pub fn is_password_protected(&self) -> bool {
+        self.password_protected
+    }
+
+    /// Change the password of the current session. Connected viewers stay connected.
+    pub fn set_password(&mut self, password: Option<String>) {
+        if let Some(session) = &self.session {
+            self.password_protected = password.is_some();
+            session.set_password(password);
+        }
+    }
+
+    /// Disconnect all viewers of the current session
+    pub fn kick_viewers(&self) {
+        if let Some(session) = &self.session {
+            session.kick_subscribers();
+        }
+    }
+
     pub fn close_connection(&mut self) {
         if let Some(abort) = self.disconnect_tx.take() {
             let _ = abort.send(());
@@ -66,7 +100,11 @@ impl WebRadar {
     }
 }
 
-pub fn create_web_radar(endpoint: Url, cs2: Arc<CS2Handle>) -> Arc<Mutex<WebRadar>> {
+pub fn create_web_radar(
+    endpoint: Url,
+    cs2: Arc<CS2Handle>,
+    options: PublishSessionOptions,
+) -> Arc<Mutex<WebRadar>> {
     let (disconnect_tx, disconnect_rx) = oneshot::channel();
     let instance = Arc::new_cyclic(|ref_self| {
         Mutex::new(WebRadar {
@@ -75,6 +113,10 @@ pub fn create_web_radar(endpoint: Url, cs2: Arc<CS2Handle>) -> Arc<Mutex<WebRada
             connection_state: WebRadarState::Connecting,
             endpoint: endpoint.clone(),
 
+            session: None,
+            session_expires_at: None,
+            password_protected: options.password.is_some(),
+
             disconnect_tx: Some(disconnect_tx),
         })
     });
@@ -83,13 +125,15 @@ pub fn create_web_radar(endpoint: Url, cs2: Arc<CS2Handle>) -> Arc<Mutex<WebRada
         let instance = instance.clone();
 
         async move {
-            let mut publisher = match WebRadar::create_connection(&endpoint, cs2).await {
+            let mut publisher = match WebRadar::create_connection(&endpoint, cs2, options).await {
                 Ok(publisher) => {
                     log::info!("Web radar created. Session id: {}", publisher.session_id);
                     let mut instance = instance.lock().unwrap();
                     instance.connection_state = WebRadarState::Connected {
                         session_id: publisher.session_id.clone(),
                     };
+                    instance.session = Some(publisher.session_controller());
+                    instance.session_expires_at = publisher.session_expires_at;
                     publisher
                 }
                 Err(err) => {
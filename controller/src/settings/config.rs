@@ -1,11 +1,17 @@
 use std::{
     collections::BTreeMap,
-    fs::File,
+    fs::{
+        self,
+        File,
+    },
     io::{
         BufReader,
         BufWriter,
     },
-    path::PathBuf,
+    path::{
+        Path,
+        PathBuf,
+    },
 };
 
 use anyhow::Context;
@@ -14,18 +20,26 @@ use serde::{
     Deserialize,
     Serialize,
 };
+use serde_yaml::{
+    Mapping,
+    Value,
+};
 use utils_state::{
     State,
     StateCacheType,
 };
 
 use super::{
+    migrate_settings,
     EspBombColor,
     EspBombSettings,
     EspConfig,
     EspPlayerSettings,
     EspSelector,
     HotKey,
+    SettingsMigrationReport,
+    UiTheme,
+    APP_SETTINGS_VERSION,
 };
 
 fn bool_true() -> bool {
@@ -41,6 +55,14 @@ fn default_i32<const V: i32>() -> i32 {
     V
 }
 
+fn default_settings_version() -> u32 {
+    APP_SETTINGS_VERSION
+}
+
+fn default_font_scale() -> f32 {
+    1.0
+}
+
 fn default_key_settings() -> HotKey {
     Key::Pause.into()
 }
@@ -99,6 +121,10 @@ pub enum KeyToggleMode {
 
 #[derive(Clone, Deserialize, Serialize)]
 pub struct AppSettings {
+    /// Layout version of the settings, see APP_SETTINGS_VERSION
+    #[serde(default = "default_settings_version")]
+    pub version: u32,
+
     #[serde(default = "default_key_settings")]
     pub key_settings: HotKey,
 
@@ -106,7 +132,7 @@ pub struct AppSettings {
     pub esp_mode: KeyToggleMode,
 
     #[serde(default = "default_key_none")]
-    pub esp_toogle: Option<HotKey>,
+    pub esp_toggle: Option<HotKey>,
 
     #[serde(default = "default_esp_configs")]
     pub esp_settings: BTreeMap<String, EspConfig>,
@@ -126,9 +152,21 @@ pub struct AppSettings {
     #[serde(default = "bool_true")]
     pub valthrun_watermark: bool,
 
+    /// Scale of all overlay fonts (e.g. for high resolution monitors)
+    #[serde(default = "default_font_scale")]
+    pub font_scale: f32,
+
+    /// Additionally scale the fonts by the games HUD scale
+    #[serde(default = "bool_false")]
+    pub font_scale_from_game: bool,
+
     #[serde(default = "default_i32::<16364>")]
     pub mouse_x_360: i32,
 
+    /// Use the games sensitivity instead of mouse_x_360
+    #[serde(default = "bool_false")]
+    pub mouse_sensitivity_from_game: bool,
+
     #[serde(default = "default_trigger_bot_mode")]
     pub trigger_bot_mode: KeyToggleMode,
 
@@ -167,6 +205,25 @@ pub struct AppSettings {
 
     #[serde(default)]
     pub imgui: Option<String>,
+
+    /// Style of the settings UI
+    #[serde(default)]
+    pub theme: UiTheme,
+
+    /// Name of the active settings profile
+    #[serde(default)]
+    pub active_profile: Option<String>,
+
+    #[serde(default = "default_key_none")]
+    pub key_next_profile: Option<HotKey>,
+
+    /// Language of the game strings (weapon and map names). Follows the game if not set.
+    #[serde(default)]
+    pub localization_language: Option<String>,
+
+    /// Install directory of the game. Will be detected if not set.
+    #[serde(default)]
+    pub game_directory: Option<String>,
 }
 
 impl State for AppSettings {
@@ -206,11 +263,110 @@ pub fn load_app_settings() -> anyhow::Result<AppSettings> {
     })?;
     let mut config = BufReader::new(config);
 
-    let config: AppSettings =
+    let config: Value =
         serde_yaml::from_reader(&mut config).context("failed to parse app config")?;
+    let (mut settings, report) = parse_app_settings(config)?;
+    report.log();
 
     log::info!("Loaded app config from {}", config_path.to_string_lossy());
-    Ok(config)
+    if report.source_version > APP_SETTINGS_VERSION {
+        log::warn!(
+            "App config version {} is newer than the supported version {}. Unknown options will be lost when saving.",
+            report.source_version,
+            APP_SETTINGS_VERSION
+        );
+
+        if !backup_path(&config_path, report.source_version, 0).exists() {
+            backup_app_settings(&config_path, report.source_version)?;
+        }
+    }
+
+    settings.version = APP_SETTINGS_VERSION;
+    if report.is_migrated() {
+        backup_app_settings(&config_path, report.source_version)?;
+        save_app_settings(&settings)?;
+    }
+
+    Ok(settings)
+}
+
+/// Migrate and validate the raw app config
+pub fn parse_app_settings(config: Value) -> anyhow::Result<(AppSettings, SettingsMigrationReport)> {
+    let mut config = match config {
+        Value::Mapping(config) => config,
+        Value::Null => {
+            log::info!("App config is empty. Using default config.");
+            let settings = serde_yaml::from_str("").context("failed to parse empty config")?;
+            let report = SettingsMigrationReport {
+                source_version: APP_SETTINGS_VERSION,
+                target_version: APP_SETTINGS_VERSION,
+                ..Default::default()
+            };
+            return Ok((settings, report));
+        }
+        _ => anyhow::bail!("app config must be a mapping"),
+    };
+
+    let mut report = migrate_settings(&mut config)?;
+    let settings: AppSettings = serde_yaml::from_value(Value::Mapping(config.clone()))
+        .context("failed to parse app config")?;
+    report
+        .unmigrated_keys
+        .extend(find_unknown_keys(&config, &settings)?);
+
+    Ok((settings, report))
+}
+
+/// Top level keys of the raw config which are not part of the settings
+fn find_unknown_keys(config: &Mapping, settings: &AppSettings) -> anyhow::Result<Vec<String>> {
+    let known_keys = match serde_yaml::to_value(settings).context("failed to serialize config")? {
+        Value::Mapping(mapping) => mapping,
+        _ => anyhow::bail!("app settings must serialize into a mapping"),
+    };
+
+    Ok(config
+        .keys()
+        .filter(|key| !known_keys.contains_key(*key))
+        .map(|key| match key {
+            Value::String(key) => key.clone(),
+            key => format!("{:?}", key),
+        })
+        .collect())
+}
+
+fn backup_path(config_path: &Path, version: u32, index: usize) -> PathBuf {
+    let file_name = config_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "config.yaml".to_string());
+
+    let file_name = if index == 0 {
+        format!("{}.v{}.bak", file_name, version)
+    } else {
+        format!("{}.v{}.{}.bak", file_name, version, index)
+    };
+    config_path.with_file_name(file_name)
+}
+
+/// Copy the current config file before it gets rewritten. Existing backups will not be overwritten.
+fn backup_app_settings(config_path: &Path, version: u32) -> anyhow::Result<PathBuf> {
+    let backup_path = (0..)
+        .map(|index| backup_path(config_path, version, index))
+        .find(|path| !path.exists())
+        .context("no free backup file name")?;
+
+    fs::copy(config_path, &backup_path).with_context(|| {
+        format!(
+            "failed to backup the app config to {}",
+            backup_path.to_string_lossy()
+        )
+    })?;
+
+    log::info!(
+        "Saved a backup of the previous app config to {}",
+        backup_path.to_string_lossy()
+    );
+    Ok(backup_path)
 }
 
 pub fn save_app_settings(settings: &AppSettings) -> anyhow::Result<()> {
@@ -0,0 +1,483 @@
+use anyhow::Context;
+use serde_yaml::{
+    Mapping,
+    Value,
+};
+
+use super::{
+    Color,
+    EspBoxType,
+    EspColor,
+    EspConfig,
+    EspHealthBar,
+    EspPlayerSettings,
+    EspSelector,
+};
+
+/// Current version of the app settings layout
+pub const APP_SETTINGS_VERSION: u32 = 3;
+
+/// A single step migrating the raw settings tree to the next version
+pub struct SettingsMigration {
+    /// Version of the settings after applying this migration
+    pub version: u32,
+    pub description: &'static str,
+    pub migrate: fn(&mut Mapping, &mut SettingsMigrationReport) -> anyhow::Result<()>,
+}
+
+#[derive(Debug, Default)]
+pub struct SettingsMigrationReport {
+    pub source_version: u32,
+    pub target_version: u32,
+
+    /// Descriptions of the applied migrations
+    pub applied: Vec<&'static str>,
+
+    /// Keys which could not be migrated and will be dropped when saving
+    pub unmigrated_keys: Vec<String>,
+}
+
+impl SettingsMigrationReport {
+    pub fn is_migrated(&self) -> bool {
+        !self.applied.is_empty()
+    }
+
+    pub fn log(&self) {
+        if self.is_migrated() {
+            log::info!(
+                "Migrated app config from version {} to {}",
+                self.source_version,
+                self.target_version
+            );
+            for description in self.applied.iter() {
+                log::info!(" - {}", description);
+            }
+        }
+
+        for key in self.unmigrated_keys.iter() {
+            log::warn!(
+                "App config key {} could not be migrated and will be ignored.",
+                key
+            );
+        }
+    }
+}
+
+/// Ordered chain of all migrations
+const MIGRATIONS: &[SettingsMigration] = &[
+    SettingsMigration {
+        version: 1,
+        description: "convert the legacy ESP options into per target ESP configs",
+        migrate: migrate_legacy_esp,
+    },
+    SettingsMigration {
+        version: 2,
+        description: "rename esp_toogle to esp_toggle",
+        migrate: |settings, report| {
+            rename_key(settings, "esp_toogle", "esp_toggle", report);
+            Ok(())
+        },
+    },
+    SettingsMigration {
+        version: 3,
+        description: "move the USP-S, CZ75-Auto and R8 Revolver ESP configs into the pistol group",
+        migrate: migrate_moved_pistols,
+    },
+];
+
+fn key(name: &str) -> Value {
+    Value::String(name.to_string())
+}
+
+/// Rename a top level key. Keeps the new key if both keys are present.
+fn rename_key(settings: &mut Mapping, from: &str, to: &str, report: &mut SettingsMigrationReport) {
+    let Some(value) = settings.remove(from) else {
+        return;
+    };
+
+    if settings.contains_key(to) {
+        report.unmigrated_keys.push(from.to_string());
+    } else {
+        settings.insert(key(to), value);
+    }
+}
+
+const LEGACY_ESP_KEYS: [&str; 9] = [
+    "esp_boxes",
+    "esp_box_thickness",
+    "esp_skeleton",
+    "esp_skeleton_thickness",
+    "esp_health",
+    "esp_color_team",
+    "esp_color_enemy",
+    "esp_enabled_team",
+    "esp_enabled_enemy",
+];
+
+fn migrate_legacy_esp(
+    settings: &mut Mapping,
+    report: &mut SettingsMigrationReport,
+) -> anyhow::Result<()> {
+    let legacy = LEGACY_ESP_KEYS
+        .iter()
+        .filter_map(|name| settings.remove(*name).map(|value| (*name, value)))
+        .collect::<Vec<_>>();
+    if legacy.is_empty() {
+        return Ok(());
+    }
+
+    if settings.contains_key("esp_settings") {
+        /* the new ESP configuration has already been set up */
+        report
+            .unmigrated_keys
+            .extend(legacy.iter().map(|(name, _)| name.to_string()));
+        return Ok(());
+    }
+
+    let mut esp_settings = Mapping::new();
+    let mut esp_settings_enabled = Mapping::new();
+    let mut unmigrated_keys = Vec::new();
+    for enemy in [false, true] {
+        let selector = EspSelector::PlayerTeam { enemy };
+        let mut config = EspPlayerSettings::new(&selector);
+        let mut enabled = true;
+
+        for (name, value) in legacy.iter() {
+            match (*name, value) {
+                ("esp_boxes", Value::Bool(value)) => {
+                    config.box_type = if *value {
+                        EspBoxType::Box2D
+                    } else {
+                        EspBoxType::None
+                    };
+                }
+                ("esp_box_thickness", Value::Number(value)) => {
+                    config.box_width = value.as_f64().unwrap_or_default() as f32;
+                }
+                ("esp_skeleton", Value::Bool(value)) => config.skeleton = *value,
+                ("esp_skeleton_thickness", Value::Number(value)) => {
+                    config.skeleton_width = value.as_f64().unwrap_or_default() as f32;
+                }
+                ("esp_health", Value::Bool(value)) => {
+                    config.health_bar = if *value {
+                        EspHealthBar::Left
+                    } else {
+                        EspHealthBar::None
+                    };
+                }
+
+                /* options for the other team */
+                ("esp_color_team" | "esp_enabled_team", _) if enemy => {}
+                ("esp_color_enemy" | "esp_enabled_enemy", _) if !enemy => {}
+
+                ("esp_color_team" | "esp_color_enemy", value) => {
+                    match serde_yaml::from_value::<[f32; 4]>(value.clone()) {
+                        Ok(value) => {
+                            let color = EspColor::Static {
+                                value: Color::from_f32(value),
+                            };
+                            config.box_color = color;
+                            config.skeleton_color = color;
+                        }
+                        Err(_) => unmigrated_keys.push(name.to_string()),
+                    }
+                }
+                ("esp_enabled_team" | "esp_enabled_enemy", Value::Bool(value)) => enabled = *value,
+
+                _ => unmigrated_keys.push(name.to_string()),
+            }
+        }
+
+        esp_settings.insert(
+            key(&selector.config_key()),
+            serde_yaml::to_value(EspConfig::Player(config))
+                .context("failed to serialize the ESP config")?,
+        );
+        esp_settings_enabled.insert(key(&selector.config_key()), Value::Bool(enabled));
+    }
+
+    /* keys shared by both teams are visited twice */
+    unmigrated_keys.sort();
+    unmigrated_keys.dedup();
+    report.unmigrated_keys.extend(unmigrated_keys);
+
+    settings.insert(key("esp_settings"), Value::Mapping(esp_settings));
+    settings.insert(
+        key("esp_settings_enabled"),
+        Value::Mapping(esp_settings_enabled),
+    );
+    Ok(())
+}
+
+/// Weapons which have been listed as rifles before the weapon table has been generated
+const MOVED_PISTOLS: [&str; 3] = ["USPS", "CZ75a", "Revolver"];
+
+fn migrate_moved_pistols(
+    settings: &mut Mapping,
+    report: &mut SettingsMigrationReport,
+) -> anyhow::Result<()> {
+    for config_name in ["esp_settings", "esp_settings_enabled"] {
+        let Some(Value::Mapping(configs)) = settings.get_mut(config_name) else {
+            continue;
+        };
+
+        for weapon in MOVED_PISTOLS {
+            let from = format!("weapon.rifle.{}", weapon);
+            let Some(value) = configs.remove(from.as_str()) else {
+                continue;
+            };
+
+            let to = key(&format!("weapon.pistol.{}", weapon));
+            if configs.contains_key(&to) {
+                report
+                    .unmigrated_keys
+                    .push(format!("{}.{}", config_name, from));
+            } else {
+                configs.insert(to, value);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Version of the raw settings. Settings without a version predate versioning.
+pub fn settings_version(settings: &Mapping) -> anyhow::Result<u32> {
+    let Some(version) = settings.get("version") else {
+        return Ok(0);
+    };
+
+    version
+        .as_u64()
+        .and_then(|version| u32::try_from(version).ok())
+        .with_context(|| format!("invalid settings version {:?}", version))
+}
+
+/// Apply all pending migrations to the raw settings
+pub fn migrate_settings(settings: &mut Mapping) -> anyhow::Result<SettingsMigrationReport> {
+    let source_version = settings_version(settings)?;
+    let mut report = SettingsMigrationReport {
+        source_version,
+        target_version: source_version,
+        ..Default::default()
+    };
+
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > source_version)
+    {
+        (migration.migrate)(settings, &mut report).with_context(|| {
+            format!(
+                "failed to migrate settings to version {} ({})",
+                migration.version, migration.description
+            )
+        })?;
+
+        settings.insert(key("version"), Value::from(migration.version));
+        report.target_version = migration.version;
+        report.applied.push(migration.description);
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod test {
+    use serde_yaml::{
+        Mapping,
+        Value,
+    };
+
+    use super::{
+        migrate_settings,
+        rename_key,
+        SettingsMigrationReport,
+        APP_SETTINGS_VERSION,
+    };
+    use crate::settings::{
+        Color,
+        EspBoxType,
+        EspColor,
+        EspConfig,
+        EspHealthBar,
+    };
+
+    fn parse_mapping(value: &str) -> Mapping {
+        serde_yaml::from_str(value).unwrap()
+    }
+
+    fn player_config(settings: &Mapping, key: &str) -> EspConfig {
+        serde_yaml::from_value(settings["esp_settings"][key].clone()).unwrap()
+    }
+
+    #[test]
+    fn test_migrate_legacy_config() {
+        let mut settings = parse_mapping(
+            r#"
+esp_boxes: true
+esp_box_thickness: 2.5
+esp_skeleton: false
+esp_health: true
+esp_color_team: [0.0, 1.0, 0.0, 1.0]
+esp_color_enemy: red
+esp_enabled_team: false
+esp_enabled_enemy: true
+esp_toogle: 112
+bomb_timer: true
+"#,
+        );
+
+        let report = migrate_settings(&mut settings).unwrap();
+        assert_eq!(report.source_version, 0);
+        assert_eq!(report.target_version, APP_SETTINGS_VERSION);
+        assert_eq!(report.applied.len(), 3);
+        assert!(report.is_migrated());
+        assert_eq!(report.unmigrated_keys, vec!["esp_color_enemy"]);
+
+        assert_eq!(
+            settings["version"].as_u64(),
+            Some(APP_SETTINGS_VERSION as u64)
+        );
+        assert_eq!(settings["bomb_timer"], Value::Bool(true));
+        assert!(!settings.contains_key("esp_boxes"));
+        assert!(!settings.contains_key("esp_color_enemy"));
+
+        let EspConfig::Player(friendly) = player_config(&settings, "player.friendly") else {
+            panic!("expected a player config");
+        };
+        assert!(friendly.box_type == EspBoxType::Box2D);
+        assert_eq!(friendly.box_width, 2.5);
+        assert!(!friendly.skeleton);
+        assert!(friendly.health_bar == EspHealthBar::Left);
+        assert!(
+            friendly.box_color
+                == EspColor::Static {
+                    value: Color::from_f32([0.0, 1.0, 0.0, 1.0])
+                }
+        );
+
+        let EspConfig::Player(enemy) = player_config(&settings, "player.enemy") else {
+            panic!("expected a player config");
+        };
+        assert!(enemy.box_type == EspBoxType::Box2D);
+        /* the invalid color has not been applied */
+        assert!(enemy.box_color != friendly.box_color);
+
+        let enabled = &settings["esp_settings_enabled"];
+        assert_eq!(enabled["player.friendly"], Value::Bool(false));
+        assert_eq!(enabled["player.enemy"], Value::Bool(true));
+
+        /* the hotkey has been renamed */
+        assert!(!settings.contains_key("esp_toogle"));
+        assert_eq!(settings["esp_toggle"].as_u64(), Some(112));
+    }
+
+    #[test]
+    fn test_legacy_esp_with_esp_settings() {
+        let mut settings = parse_mapping(
+            r#"
+esp_boxes: true
+esp_settings: {}
+"#,
+        );
+
+        let report = migrate_settings(&mut settings).unwrap();
+        assert_eq!(report.unmigrated_keys, vec!["esp_boxes"]);
+        assert!(!settings.contains_key("esp_boxes"));
+        assert_eq!(settings["esp_settings"], Value::Mapping(Mapping::new()));
+    }
+
+    #[test]
+    fn test_rename_key() {
+        let mut report = SettingsMigrationReport::default();
+        let mut settings = parse_mapping("esp_toogle: 1");
+        rename_key(&mut settings, "esp_toogle", "esp_toggle", &mut report);
+        assert_eq!(settings, parse_mapping("esp_toggle: 1"));
+        assert!(report.unmigrated_keys.is_empty());
+
+        /* missing keys are ignored */
+        rename_key(&mut settings, "esp_toogle", "esp_toggle", &mut report);
+        assert_eq!(settings, parse_mapping("esp_toggle: 1"));
+        assert!(report.unmigrated_keys.is_empty());
+    }
+
+    #[test]
+    fn test_rename_key_both_present() {
+        let mut settings = parse_mapping(
+            r#"
+version: 1
+esp_toogle: 1
+esp_toggle: 2
+"#,
+        );
+
+        let report = migrate_settings(&mut settings).unwrap();
+        assert_eq!(report.source_version, 1);
+        assert_eq!(report.applied.len(), 2);
+        assert_eq!(report.unmigrated_keys, vec!["esp_toogle"]);
+
+        /* the new key is kept */
+        assert!(!settings.contains_key("esp_toogle"));
+        assert_eq!(settings["esp_toggle"].as_u64(), Some(2));
+    }
+
+    #[test]
+    fn test_moved_pistols() {
+        let mut settings = parse_mapping(
+            r#"
+version: 2
+esp_settings:
+  weapon.rifle.USPS: usp
+  weapon.rifle.Revolver: revolver
+  weapon.pistol.Revolver: kept
+  weapon.rifle.Ak47: ak
+esp_settings_enabled:
+  weapon.rifle.CZ75a: true
+"#,
+        );
+
+        let report = migrate_settings(&mut settings).unwrap();
+        assert_eq!(report.applied.len(), 1);
+        assert_eq!(
+            report.unmigrated_keys,
+            vec!["esp_settings.weapon.rifle.Revolver"]
+        );
+
+        let esp_settings = &settings["esp_settings"];
+        assert_eq!(esp_settings["weapon.pistol.USPS"].as_str(), Some("usp"));
+        assert_eq!(
+            esp_settings["weapon.pistol.Revolver"].as_str(),
+            Some("kept")
+        );
+        assert_eq!(esp_settings["weapon.rifle.Ak47"].as_str(), Some("ak"));
+        assert!(esp_settings.get("weapon.rifle.USPS").is_none());
+        assert!(esp_settings.get("weapon.rifle.Revolver").is_none());
+
+        let enabled = &settings["esp_settings_enabled"];
+        assert_eq!(enabled["weapon.pistol.CZ75a"], Value::Bool(true));
+        assert!(enabled.get("weapon.rifle.CZ75a").is_none());
+    }
+
+    #[test]
+    fn test_future_version() {
+        let source = r#"
+version: 99
+esp_toogle: 1
+future_option: true
+"#;
+        let mut settings = parse_mapping(source);
+
+        let report = migrate_settings(&mut settings).unwrap();
+        assert_eq!(report.source_version, 99);
+        assert_eq!(report.target_version, 99);
+        assert!(!report.is_migrated());
+        assert!(report.unmigrated_keys.is_empty());
+        assert_eq!(settings, parse_mapping(source));
+    }
+
+    #[test]
+    fn test_invalid_version() {
+        let mut settings = parse_mapping("version: latest");
+        assert!(migrate_settings(&mut settings).is_err());
+    }
+}
@@ -7,5 +7,17 @@ pub use ui::*;
 mod config;
 pub use config::*;
 
+mod migration;
+pub use migration::*;
+
+mod profile;
+pub use profile::*;
+
+mod watcher;
+pub use watcher::*;
+
 mod esp;
 pub use esp::*;
+
+mod theme;
+pub use theme::*;
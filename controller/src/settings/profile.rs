@@ -0,0 +1,492 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{
+        Read,
+        Write,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use anyhow::Context;
+use base64::{
+    engine::general_purpose::URL_SAFE_NO_PAD,
+    Engine,
+};
+use crc::{
+    Crc,
+    CRC_32_ISO_HDLC,
+};
+use flate2::{
+    read::DeflateDecoder,
+    write::DeflateEncoder,
+    Compression,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_yaml::{
+    Mapping,
+    Value,
+};
+
+use super::{
+    AppSettings,
+    EspBombSettings,
+    EspConfig,
+    KeyToggleMode,
+    UiTheme,
+};
+
+/// Prefix of all profile share codes
+const SHARE_CODE_PREFIX: &str = "VTP";
+
+/// Version of the share code payload. Must be incremented when changing the layout of `ProfileSettings`.
+const SHARE_CODE_VERSION: u8 = 1;
+
+/// Upper limit of a decompressed share code
+const SHARE_CODE_MAX_SIZE: u64 = 1024 * 1024;
+
+const SHARE_CODE_CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+/// Visual and HUD settings which are covered by a profile
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ProfileSettings {
+    pub esp_mode: KeyToggleMode,
+    pub esp_settings: BTreeMap<String, EspConfig>,
+    pub esp_settings_enabled: BTreeMap<String, bool>,
+
+    pub bomb_esp: bool,
+    pub bomb_esp_settings: EspBombSettings,
+
+    pub spectators_list: bool,
+    pub valthrun_watermark: bool,
+
+    pub font_scale: f32,
+    pub theme: UiTheme,
+}
+
+impl ProfileSettings {
+    pub fn capture(settings: &AppSettings) -> Self {
+        Self {
+            esp_mode: settings.esp_mode,
+            esp_settings: settings.esp_settings.clone(),
+            esp_settings_enabled: settings.esp_settings_enabled.clone(),
+
+            bomb_esp: settings.bomb_esp,
+            bomb_esp_settings: settings.bomb_esp_settings,
+
+            spectators_list: settings.spectators_list,
+            valthrun_watermark: settings.valthrun_watermark,
+
+            font_scale: settings.font_scale,
+            theme: settings.theme.clone(),
+        }
+    }
+
+    pub fn apply(&self, settings: &mut AppSettings) {
+        settings.esp_mode = self.esp_mode;
+        settings.esp_settings = self.esp_settings.clone();
+        settings.esp_settings_enabled = self.esp_settings_enabled.clone();
+
+        settings.bomb_esp = self.bomb_esp;
+        settings.bomb_esp_settings = self.bomb_esp_settings;
+
+        settings.spectators_list = self.spectators_list;
+        settings.valthrun_watermark = self.valthrun_watermark;
+
+        settings.font_scale = self.font_scale;
+        settings.theme = self.theme.clone();
+    }
+}
+
+impl Default for ProfileSettings {
+    fn default() -> Self {
+        let settings: AppSettings = serde_yaml::from_value(Value::Mapping(Mapping::new()))
+            .expect("the default app settings to be valid");
+
+        Self::capture(&settings)
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct SettingsProfile {
+    pub name: String,
+    pub settings: ProfileSettings,
+}
+
+/// Share code payload header: version (u8), CRC32 of the uncompressed payload (u32, LE)
+const SHARE_CODE_HEADER_SIZE: usize = 5;
+
+impl SettingsProfile {
+    pub fn capture(name: &str, settings: &AppSettings) -> Self {
+        Self {
+            name: name.to_string(),
+            settings: ProfileSettings::capture(settings),
+        }
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let value = fs::read_to_string(path)
+            .with_context(|| format!("failed to read profile {}", path.display()))?;
+
+        serde_yaml::from_str(&value).context("failed to parse profile")
+    }
+
+    pub fn save(&self) -> anyhow::Result<PathBuf> {
+        self.save_to(&get_profiles_path()?)
+    }
+
+    /// Save the profile into the directory.
+    /// Fails if the file name is already used by a profile with a different name.
+    fn save_to(&self, directory: &Path) -> anyhow::Result<PathBuf> {
+        fs::create_dir_all(directory).context("failed to create the profiles directory")?;
+
+        let path = profile_path(directory, &self.name);
+        ensure_profile_owns_path(&path, &self.name)?;
+
+        let value = serde_yaml::to_string(self).context("failed to serialize profile")?;
+        fs::write(&path, value)
+            .with_context(|| format!("failed to write profile {}", path.display()))?;
+
+        Ok(path)
+    }
+
+    /// Encode the profile as a compact, copy pasteable text
+    pub fn to_share_code(&self) -> anyhow::Result<String> {
+        let payload = serde_json::to_vec(self).context("failed to serialize profile")?;
+
+        let mut buffer = Vec::with_capacity(payload.len() / 2);
+        buffer.push(SHARE_CODE_VERSION);
+        buffer.extend_from_slice(&SHARE_CODE_CRC32.checksum(&payload).to_le_bytes());
+
+        let mut encoder = DeflateEncoder::new(buffer, Compression::best());
+        encoder.write_all(&payload)?;
+        let buffer = encoder.finish()?;
+
+        Ok(format!(
+            "{}{}",
+            SHARE_CODE_PREFIX,
+            URL_SAFE_NO_PAD.encode(buffer)
+        ))
+    }
+
+    pub fn from_share_code(code: &str) -> anyhow::Result<Self> {
+        let code = code
+            .chars()
+            .filter(|char| !char.is_whitespace())
+            .collect::<String>();
+        let Some(code) = code.strip_prefix(SHARE_CODE_PREFIX) else {
+            anyhow::bail!("not a profile share code");
+        };
+
+        let buffer = URL_SAFE_NO_PAD
+            .decode(code)
+            .context("invalid share code encoding")?;
+        if buffer.len() < SHARE_CODE_HEADER_SIZE {
+            anyhow::bail!("share code is too short");
+        }
+
+        let version = buffer[0];
+        if version != SHARE_CODE_VERSION {
+            anyhow::bail!(
+                "unsupported share code version {} (supported: {})",
+                version,
+                SHARE_CODE_VERSION
+            );
+        }
+
+        let expected_crc = u32::from_le_bytes(buffer[1..SHARE_CODE_HEADER_SIZE].try_into()?);
+
+        let mut payload = Vec::new();
+        DeflateDecoder::new(&buffer[SHARE_CODE_HEADER_SIZE..])
+            .take(SHARE_CODE_MAX_SIZE + 1)
+            .read_to_end(&mut payload)
+            .context("invalid share code data")?;
+        if payload.len() as u64 > SHARE_CODE_MAX_SIZE {
+            anyhow::bail!("share code exceeds the max size");
+        }
+
+        let crc = SHARE_CODE_CRC32.checksum(&payload);
+        if crc != expected_crc {
+            anyhow::bail!(
+                "share code checksum mismatch (expected {:08X}, calculated {:08X})",
+                expected_crc,
+                crc
+            );
+        }
+
+        serde_json::from_slice(&payload).context("failed to parse the shared profile")
+    }
+}
+
+pub fn get_profiles_path() -> anyhow::Result<PathBuf> {
+    let exe_file = std::env::current_exe().context("missing current exe path")?;
+    let base_dir = exe_file.parent().context("could not get exe directory")?;
+
+    Ok(base_dir.join("profiles"))
+}
+
+fn profile_path(directory: &Path, name: &str) -> PathBuf {
+    let file_name = name
+        .chars()
+        .map(|char| {
+            if char.is_ascii_alphanumeric() || char == '-' {
+                char.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+
+    directory.join(format!("{}.yaml", file_name))
+}
+
+/// Different names might map to the same file (e.g. "My Profile" and "my_profile").
+/// Ensure the file at the path either does not exist or belongs to the profile.
+fn ensure_profile_owns_path(path: &Path, name: &str) -> anyhow::Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let existing = SettingsProfile::load(path)
+        .with_context(|| format!("the file for profile {} is already in use", name))?;
+    if existing.name != name {
+        anyhow::bail!(
+            "the profile name {} is too similar to the existing profile {}",
+            name,
+            existing.name
+        );
+    }
+
+    Ok(())
+}
+
+/// Load all profiles sorted by their name
+pub fn load_profiles() -> anyhow::Result<Vec<SettingsProfile>> {
+    let directory = get_profiles_path()?;
+    if !directory.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut profiles = Vec::new();
+    for entry in fs::read_dir(&directory).context("failed to read the profiles directory")? {
+        let path = entry?.path();
+        if !path
+            .extension()
+            .is_some_and(|extension| extension == "yaml")
+        {
+            continue;
+        }
+
+        match SettingsProfile::load(&path) {
+            Ok(profile) => profiles.push(profile),
+            Err(error) => log::warn!("Failed to load profile {}: {:#}", path.display(), error),
+        }
+    }
+
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(profiles)
+}
+
+pub fn delete_profile(name: &str) -> anyhow::Result<()> {
+    delete_profile_from(&get_profiles_path()?, name)
+}
+
+fn delete_profile_from(directory: &Path, name: &str) -> anyhow::Result<()> {
+    let path = profile_path(directory, name);
+    ensure_profile_owns_path(&path, name)?;
+    fs::remove_file(&path).with_context(|| format!("failed to delete profile {}", path.display()))
+}
+
+/// Apply the profile and mark it as active
+pub fn activate_profile(settings: &mut AppSettings, profile: &SettingsProfile) {
+    profile.settings.apply(settings);
+    settings.active_profile = Some(profile.name.clone());
+}
+
+/// Store the current settings into the active profile (if any)
+pub fn save_active_profile(settings: &AppSettings) -> anyhow::Result<()> {
+    if let Some(name) = &settings.active_profile {
+        SettingsProfile::capture(name, settings).save()?;
+    }
+
+    Ok(())
+}
+
+/// Save the active profile and switch to the next one.
+/// Returns the name of the new active profile.
+pub fn switch_to_next_profile(settings: &mut AppSettings) -> anyhow::Result<Option<String>> {
+    let profiles = load_profiles()?;
+    if profiles.is_empty() {
+        return Ok(None);
+    }
+
+    let active_index = profiles
+        .iter()
+        .position(|profile| Some(&profile.name) == settings.active_profile.as_ref());
+    if active_index.is_some() {
+        save_active_profile(settings)?;
+    }
+
+    let next_index = active_index.map_or(0, |index| (index + 1) % profiles.len());
+    if Some(next_index) != active_index {
+        activate_profile(settings, &profiles[next_index]);
+    }
+
+    Ok(settings.active_profile.clone())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        fs,
+        io::Write,
+        path::PathBuf,
+    };
+
+    use base64::Engine;
+    use flate2::{
+        write::DeflateEncoder,
+        Compression,
+    };
+
+    use super::{
+        delete_profile_from,
+        ProfileSettings,
+        SettingsProfile,
+        SHARE_CODE_CRC32,
+        SHARE_CODE_MAX_SIZE,
+        SHARE_CODE_PREFIX,
+        SHARE_CODE_VERSION,
+        URL_SAFE_NO_PAD,
+    };
+
+    fn test_profile(name: &str) -> SettingsProfile {
+        let mut settings = ProfileSettings {
+            bomb_esp: true,
+            font_scale: 1.25,
+            ..Default::default()
+        };
+        settings.theme.name = "Test".to_string();
+
+        SettingsProfile {
+            name: name.to_string(),
+            settings,
+        }
+    }
+
+    fn encode_share_code(version: u8, crc: u32, payload: &[u8]) -> String {
+        let mut buffer = vec![version];
+        buffer.extend_from_slice(&crc.to_le_bytes());
+
+        let mut encoder = DeflateEncoder::new(buffer, Compression::best());
+        encoder.write_all(payload).unwrap();
+        format!(
+            "{}{}",
+            SHARE_CODE_PREFIX,
+            URL_SAFE_NO_PAD.encode(encoder.finish().unwrap())
+        )
+    }
+
+    fn test_directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("profiles-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    #[test]
+    fn test_share_code_roundtrip() {
+        let profile = test_profile("Shared");
+        let code = profile.to_share_code().unwrap();
+        assert!(code.starts_with(SHARE_CODE_PREFIX));
+
+        /* whitespace from line wrapping will be ignored */
+        let (head, tail) = code.split_at(code.len() / 2);
+        let decoded = SettingsProfile::from_share_code(&format!(" {}\n{} ", head, tail)).unwrap();
+        assert_eq!(decoded.name, "Shared");
+        assert!(decoded.settings.bomb_esp);
+        assert_eq!(decoded.settings.font_scale, 1.25);
+        assert!(decoded.settings.theme == profile.settings.theme);
+    }
+
+    #[test]
+    fn test_share_code_invalid() {
+        let payload = serde_json::to_vec(&test_profile("Shared")).unwrap();
+        let crc = SHARE_CODE_CRC32.checksum(&payload);
+        assert!(SettingsProfile::from_share_code(&encode_share_code(
+            SHARE_CODE_VERSION,
+            crc,
+            &payload
+        ))
+        .is_ok());
+
+        let error = SettingsProfile::from_share_code(&encode_share_code(
+            SHARE_CODE_VERSION,
+            crc ^ 1,
+            &payload,
+        ))
+        .err()
+        .unwrap();
+        assert!(error.to_string().contains("checksum mismatch"));
+
+        let error = SettingsProfile::from_share_code(&encode_share_code(
+            SHARE_CODE_VERSION + 1,
+            crc,
+            &payload,
+        ))
+        .err()
+        .unwrap();
+        assert!(error.to_string().contains("unsupported share code version"));
+
+        assert!(SettingsProfile::from_share_code("VTP").is_err());
+        assert!(SettingsProfile::from_share_code("VTP!!!").is_err());
+        assert!(SettingsProfile::from_share_code("ABCdefgh").is_err());
+    }
+
+    #[test]
+    fn test_share_code_oversize() {
+        let payload = vec![b' '; SHARE_CODE_MAX_SIZE as usize + 1];
+        let code = encode_share_code(
+            SHARE_CODE_VERSION,
+            SHARE_CODE_CRC32.checksum(&payload),
+            &payload,
+        );
+
+        /* the compressed code itself is small */
+        assert!(code.len() < 8 * 1024);
+
+        let error = SettingsProfile::from_share_code(&code).err().unwrap();
+        assert!(error.to_string().contains("max size"));
+    }
+
+    #[test]
+    fn test_profile_name_collision() {
+        let directory = test_directory("collision");
+
+        let path = test_profile("My Profile").save_to(&directory).unwrap();
+        assert_eq!(path, directory.join("my_profile.yaml"));
+
+        /* saving the same profile again replaces it */
+        assert_eq!(
+            test_profile("My Profile").save_to(&directory).unwrap(),
+            path
+        );
+
+        /* a different name with the same file name must not replace the profile */
+        assert!(test_profile("my_profile").save_to(&directory).is_err());
+        assert!(delete_profile_from(&directory, "my_profile").is_err());
+        assert_eq!(SettingsProfile::load(&path).unwrap().name, "My Profile");
+
+        delete_profile_from(&directory, "My Profile").unwrap();
+        assert!(!path.exists());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,424 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use anyhow::Context;
+use imgui::{
+    ColorStackToken,
+    Style,
+    StyleColor,
+    StyleStackToken,
+    StyleVar,
+    Ui,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+pub enum UiThemeBase {
+    Dark,
+    Light,
+    Classic,
+}
+
+fn default_theme_font_scale() -> f32 {
+    1.0
+}
+
+fn default_theme_alpha() -> f32 {
+    1.0
+}
+
+/// Style of the settings UI.
+/// Values not present in a theme file fall back to the imgui defaults of the base colors.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct UiTheme {
+    pub name: String,
+    pub base: UiThemeBase,
+
+    /// Scale of the settings window texts (applied on top of the global font scale)
+    #[serde(default = "default_theme_font_scale")]
+    pub font_scale: f32,
+
+    #[serde(default = "default_theme_alpha")]
+    pub alpha: f32,
+
+    #[serde(default)]
+    pub rounding: UiThemeRounding,
+
+    #[serde(default)]
+    pub spacing: UiThemeSpacing,
+
+    /// Color overrides by imgui color name (e.g. WindowBg)
+    #[serde(default)]
+    pub colors: BTreeMap<String, [f32; 4]>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct UiThemeRounding {
+    pub window: f32,
+    pub child: f32,
+    pub popup: f32,
+    pub frame: f32,
+    pub scrollbar: f32,
+    pub grab: f32,
+    pub tab: f32,
+}
+
+impl Default for UiThemeRounding {
+    fn default() -> Self {
+        Self {
+            window: 0.0,
+            child: 0.0,
+            popup: 0.0,
+            frame: 0.0,
+            scrollbar: 9.0,
+            grab: 0.0,
+            tab: 4.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct UiThemeSpacing {
+    pub window_padding: [f32; 2],
+    pub frame_padding: [f32; 2],
+    pub item_spacing: [f32; 2],
+    pub item_inner_spacing: [f32; 2],
+    pub indent_spacing: f32,
+    pub scrollbar_size: f32,
+    pub grab_min_size: f32,
+
+    pub window_border_size: f32,
+    pub frame_border_size: f32,
+}
+
+impl Default for UiThemeSpacing {
+    fn default() -> Self {
+        Self {
+            window_padding: [8.0, 8.0],
+            frame_padding: [4.0, 3.0],
+            item_spacing: [8.0, 4.0],
+            item_inner_spacing: [4.0, 4.0],
+            indent_spacing: 21.0,
+            scrollbar_size: 14.0,
+            grab_min_size: 10.0,
+
+            window_border_size: 1.0,
+            frame_border_size: 0.0,
+        }
+    }
+}
+
+impl Default for UiTheme {
+    fn default() -> Self {
+        Self::new("Dark", UiThemeBase::Dark)
+    }
+}
+
+pub fn style_color_name(color: StyleColor) -> String {
+    format!("{:?}", color)
+}
+
+impl UiTheme {
+    pub fn new(name: &str, base: UiThemeBase) -> Self {
+        Self {
+            name: name.to_string(),
+            base,
+
+            font_scale: default_theme_font_scale(),
+            alpha: default_theme_alpha(),
+
+            rounding: Default::default(),
+            spacing: Default::default(),
+            colors: Default::default(),
+        }
+    }
+
+    fn with_color(mut self, color: StyleColor, value: [f32; 4]) -> Self {
+        self.colors.insert(style_color_name(color), value);
+        self
+    }
+
+    pub fn color(&self, color: StyleColor) -> Option<[f32; 4]> {
+        self.colors.get(&style_color_name(color)).cloned()
+    }
+
+    pub fn set_color(&mut self, color: StyleColor, value: [f32; 4]) {
+        self.colors.insert(style_color_name(color), value);
+    }
+
+    pub fn reset_color(&mut self, color: StyleColor) {
+        self.colors.remove(&style_color_name(color));
+    }
+
+    /// Copy of the style with the base colors of this theme (without overrides)
+    pub fn base_style(&self, style: &Style) -> Style {
+        let mut style = *style;
+        self.apply_base_colors(&mut style);
+        style
+    }
+
+    fn apply_base_colors(&self, style: &mut Style) {
+        match self.base {
+            UiThemeBase::Dark => style.use_dark_colors(),
+            UiThemeBase::Light => style.use_light_colors(),
+            UiThemeBase::Classic => style.use_classic_colors(),
+        };
+    }
+
+    pub fn apply(&self, style: &mut Style) {
+        self.apply_base_colors(style);
+        for color in StyleColor::VARIANTS {
+            if let Some(value) = self.color(color) {
+                style[color] = value;
+            }
+        }
+
+        style.alpha = self.alpha.clamp(0.2, 1.0);
+
+        style.window_rounding = self.rounding.window;
+        style.child_rounding = self.rounding.child;
+        style.popup_rounding = self.rounding.popup;
+        style.frame_rounding = self.rounding.frame;
+        style.scrollbar_rounding = self.rounding.scrollbar;
+        style.grab_rounding = self.rounding.grab;
+        style.tab_rounding = self.rounding.tab;
+
+        style.window_padding = self.spacing.window_padding;
+        style.frame_padding = self.spacing.frame_padding;
+        style.item_spacing = self.spacing.item_spacing;
+        style.item_inner_spacing = self.spacing.item_inner_spacing;
+        style.indent_spacing = self.spacing.indent_spacing;
+        style.scrollbar_size = self.spacing.scrollbar_size;
+        style.grab_min_size = self.spacing.grab_min_size;
+
+        style.window_border_size = self.spacing.window_border_size;
+        style.frame_border_size = self.spacing.frame_border_size;
+    }
+
+    /// Resolve all style values of the theme relative to the given default style
+    pub fn resolve_style(&self, default_style: &Style) -> UiThemeStyle {
+        let mut style = *default_style;
+        self.apply(&mut style);
+
+        UiThemeStyle {
+            colors: StyleColor::VARIANTS
+                .iter()
+                .map(|color| (*color, style[*color]))
+                .collect(),
+            vars: vec![
+                StyleVar::Alpha(style.alpha),
+                StyleVar::WindowRounding(style.window_rounding),
+                StyleVar::ChildRounding(style.child_rounding),
+                StyleVar::PopupRounding(style.popup_rounding),
+                StyleVar::FrameRounding(style.frame_rounding),
+                StyleVar::ScrollbarRounding(style.scrollbar_rounding),
+                StyleVar::GrabRounding(style.grab_rounding),
+                StyleVar::TabRounding(style.tab_rounding),
+                StyleVar::WindowPadding(style.window_padding),
+                StyleVar::FramePadding(style.frame_padding),
+                StyleVar::ItemSpacing(style.item_spacing),
+                StyleVar::ItemInnerSpacing(style.item_inner_spacing),
+                StyleVar::IndentSpacing(style.indent_spacing),
+                StyleVar::ScrollbarSize(style.scrollbar_size),
+                StyleVar::GrabMinSize(style.grab_min_size),
+                StyleVar::WindowBorderSize(style.window_border_size),
+                StyleVar::FrameBorderSize(style.frame_border_size),
+            ],
+        }
+    }
+
+    pub fn to_yaml(&self) -> anyhow::Result<String> {
+        serde_yaml::to_string(self).context("failed to serialize theme")
+    }
+
+    pub fn from_yaml(value: &str) -> anyhow::Result<Self> {
+        let theme: Self = serde_yaml::from_str(value).context("failed to parse theme")?;
+        for name in theme.colors.keys() {
+            if !StyleColor::VARIANTS
+                .iter()
+                .any(|color| style_color_name(*color) == *name)
+            {
+                log::warn!("Theme {} contains unknown color {}", theme.name, name);
+            }
+        }
+
+        Ok(theme)
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let value = fs::read_to_string(path)
+            .with_context(|| format!("failed to read theme {}", path.display()))?;
+
+        Self::from_yaml(&value)
+    }
+
+    /// Save the theme into the themes directory and return the file path
+    pub fn save(&self) -> anyhow::Result<PathBuf> {
+        let directory = get_themes_path()?;
+        fs::create_dir_all(&directory).context("failed to create the themes directory")?;
+
+        let file_name = self
+            .name
+            .chars()
+            .map(|char| {
+                if char.is_ascii_alphanumeric() {
+                    char.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        let path = directory.join(format!("{}.yaml", file_name));
+        fs::write(&path, self.to_yaml()?)
+            .with_context(|| format!("failed to write theme {}", path.display()))?;
+
+        Ok(path)
+    }
+}
+
+fn theme_high_contrast() -> UiTheme {
+    let black = [0.0, 0.0, 0.0, 1.0];
+    let white = [1.0, 1.0, 1.0, 1.0];
+    let yellow = [1.0, 0.85, 0.0, 1.0];
+    let cyan = [0.0, 0.85, 1.0, 1.0];
+
+    let mut theme = UiTheme::new("High Contrast", UiThemeBase::Dark)
+        .with_color(StyleColor::Text, white)
+        .with_color(StyleColor::TextDisabled, [0.75, 0.75, 0.75, 1.0])
+        .with_color(StyleColor::WindowBg, black)
+        .with_color(StyleColor::ChildBg, black)
+        .with_color(StyleColor::PopupBg, black)
+        .with_color(StyleColor::Border, yellow)
+        .with_color(StyleColor::FrameBg, [0.12, 0.12, 0.12, 1.0])
+        .with_color(StyleColor::FrameBgHovered, [0.25, 0.25, 0.0, 1.0])
+        .with_color(StyleColor::FrameBgActive, [0.4, 0.35, 0.0, 1.0])
+        .with_color(StyleColor::TitleBg, black)
+        .with_color(StyleColor::TitleBgActive, [0.2, 0.2, 0.2, 1.0])
+        .with_color(StyleColor::CheckMark, yellow)
+        .with_color(StyleColor::SliderGrab, yellow)
+        .with_color(StyleColor::SliderGrabActive, white)
+        .with_color(StyleColor::Button, [0.12, 0.12, 0.12, 1.0])
+        .with_color(StyleColor::ButtonHovered, [0.0, 0.35, 0.5, 1.0])
+        .with_color(StyleColor::ButtonActive, [0.0, 0.5, 0.7, 1.0])
+        .with_color(StyleColor::Header, [0.0, 0.35, 0.5, 1.0])
+        .with_color(StyleColor::HeaderHovered, [0.0, 0.45, 0.6, 1.0])
+        .with_color(StyleColor::HeaderActive, [0.0, 0.55, 0.75, 1.0])
+        .with_color(StyleColor::Tab, [0.12, 0.12, 0.12, 1.0])
+        .with_color(StyleColor::TabHovered, [0.0, 0.45, 0.6, 1.0])
+        .with_color(StyleColor::TabActive, [0.0, 0.35, 0.5, 1.0])
+        .with_color(StyleColor::Separator, yellow)
+        .with_color(StyleColor::NavHighlight, cyan)
+        .with_color(StyleColor::TextSelectedBg, [0.0, 0.45, 0.6, 1.0]);
+
+    theme.font_scale = 1.15;
+    theme.spacing.window_border_size = 2.0;
+    theme.spacing.frame_border_size = 1.0;
+    theme
+}
+
+fn theme_rounded() -> UiTheme {
+    let mut theme = UiTheme::new("Rounded", UiThemeBase::Dark)
+        .with_color(StyleColor::WindowBg, [0.08, 0.08, 0.1, 0.96])
+        .with_color(StyleColor::CheckMark, [0.95, 0.27, 0.5, 1.0])
+        .with_color(StyleColor::SliderGrab, [0.86, 0.52, 0.24, 1.0])
+        .with_color(StyleColor::SliderGrabActive, [0.95, 0.27, 0.5, 1.0]);
+
+    theme.rounding = UiThemeRounding {
+        window: 8.0,
+        child: 6.0,
+        popup: 6.0,
+        frame: 4.0,
+        scrollbar: 12.0,
+        grab: 4.0,
+        tab: 6.0,
+    };
+    theme.spacing.window_padding = [12.0, 12.0];
+    theme.spacing.frame_padding = [6.0, 4.0];
+    theme.spacing.item_spacing = [8.0, 6.0];
+    theme
+}
+
+/// Style values of a theme which can be pushed onto the imgui style stack.
+/// Only windows rendered while the style is pushed will use the theme.
+pub struct UiThemeStyle {
+    colors: Vec<(StyleColor, [f32; 4])>,
+    vars: Vec<StyleVar>,
+}
+
+/// Style stack entries which will be popped once dropped
+pub struct UiThemeStyleToken<'ui> {
+    _colors: Vec<ColorStackToken<'ui>>,
+    _vars: Vec<StyleStackToken<'ui>>,
+}
+
+impl UiThemeStyle {
+    pub fn push<'ui>(&self, ui: &'ui Ui) -> UiThemeStyleToken<'ui> {
+        UiThemeStyleToken {
+            _colors: self
+                .colors
+                .iter()
+                .map(|(color, value)| ui.push_style_color(*color, *value))
+                .collect(),
+            _vars: self
+                .vars
+                .iter()
+                .map(|var| ui.push_style_var(*var))
+                .collect(),
+        }
+    }
+}
+
+pub fn builtin_themes() -> Vec<UiTheme> {
+    vec![
+        UiTheme::new("Dark", UiThemeBase::Dark),
+        UiTheme::new("Light", UiThemeBase::Light),
+        UiTheme::new("Classic", UiThemeBase::Classic),
+        theme_rounded(),
+        theme_high_contrast(),
+    ]
+}
+
+pub fn get_themes_path() -> anyhow::Result<PathBuf> {
+    let exe_file = std::env::current_exe().context("missing current exe path")?;
+    let base_dir = exe_file.parent().context("could not get exe directory")?;
+
+    Ok(base_dir.join("themes"))
+}
+
+/// Load all themes from the themes directory
+pub fn load_user_themes() -> anyhow::Result<Vec<UiTheme>> {
+    let directory = get_themes_path()?;
+    if !directory.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut themes = Vec::new();
+    for entry in fs::read_dir(&directory).context("failed to read the themes directory")? {
+        let path = entry?.path();
+        if !path
+            .extension()
+            .is_some_and(|extension| extension == "yaml" || extension == "yml")
+        {
+            continue;
+        }
+
+        match UiTheme::load(&path) {
+            Ok(theme) => themes.push(theme),
+            Err(error) => log::warn!("Failed to load theme {}: {:#}", path.display(), error),
+        }
+    }
+
+    themes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(themes)
+}
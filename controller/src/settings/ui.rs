@@ -1,16 +1,22 @@
 use std::{
+    borrow::Cow,
     collections::btree_map::Entry,
     sync::{
         atomic::Ordering,
         Arc,
         Mutex,
     },
-    time::Instant,
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
 use cs2::{
     BuildInfo,
     CS2Handle,
+    CurrentMapState,
+    GameSettings,
 };
 use imgui::{
     Condition,
@@ -24,9 +30,17 @@ use imgui::{
     TreeNodeFlags,
 };
 use obfstr::obfstr;
+use radar_client::PublishSessionOptions;
 use url::Url;
 
 use super::{
+    activate_profile,
+    builtin_themes,
+    delete_profile,
+    load_profiles,
+    load_user_themes,
+    style_color_name,
+    AppSettingsWatcher,
     Color,
     EspBombColor,
     EspBombColorType,
@@ -35,8 +49,13 @@ use super::{
     EspConfig,
     EspSelector,
     KeyToggleMode,
+    SettingsProfile,
+    UiTheme,
+    UiThemeBase,
+    UiThemeStyle,
 };
 use crate::{
+    localization::GameLocalization,
     radar::{
         self,
         WebRadar,
@@ -66,11 +85,27 @@ enum EspPlayerActiveHeader {
 pub struct SettingsUI {
     discord_link_copied: Option<Instant>,
     radar_session_copied: Option<Instant>,
+    radar_password: String,
+    /// Session lifetime in minutes, zero for unlimited
+    radar_lifetime: i32,
 
     esp_selected_target: EspSelector,
     esp_pending_target: Option<EspSelector>,
 
     esp_player_active_header: EspPlayerActiveHeader,
+
+    /// Built in and user themes, loaded when opening the theme tab
+    themes: Option<Vec<UiTheme>>,
+    theme_message: Option<String>,
+    theme_color_filter: String,
+
+    /// Resolved style of the active theme, updated when the theme changes
+    theme_style: Option<(UiTheme, UiThemeStyle)>,
+
+    /// Stored profiles, loaded when opening the profiles tab
+    profiles: Option<Vec<SettingsProfile>>,
+    profile_name: String,
+    profile_message: Option<String>,
 }
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -79,43 +114,54 @@ impl SettingsUI {
         Self {
             discord_link_copied: None,
             radar_session_copied: None,
+            radar_password: String::new(),
+            radar_lifetime: 0,
 
             esp_selected_target: EspSelector::None,
             esp_pending_target: None,
 
             esp_player_active_header: EspPlayerActiveHeader::Features,
+
+            themes: None,
+            theme_message: None,
+            theme_color_filter: String::new(),
+            theme_style: None,
+
+            profiles: None,
+            profile_name: String::new(),
+            profile_message: None,
         }
     }
 
     pub fn render(&mut self, app: &Application, ui: &imgui::Ui) {
         let content_font = ui.current_font().id();
-        let _title_font = ui.push_font(app.fonts.valthrun);
+        let _title_font = app.fonts.valthrun.font_id().map(|font| ui.push_font(font));
+        let theme_font_scale = app.settings().theme.font_scale;
+
+        {
+            let settings = app.settings();
+            let theme_changed = self
+                .theme_style
+                .as_ref()
+                .is_none_or(|(theme, _)| *theme != settings.theme);
+            if theme_changed {
+                /* the global style is not modified by themes, hence it contains the imgui defaults */
+                let style = settings.theme.resolve_style(&ui.clone_style());
+                self.theme_style = Some((settings.theme.clone(), style));
+            }
+        }
+        let _theme_style = self.theme_style.as_ref().map(|(_, style)| style.push(ui));
 
         ui.window(obfstr!("Valthrun"))
             .size([600.0, 300.0], Condition::FirstUseEver)
             .title_bar(false)
             .build(|| {
-                {
-                    for (text, color) in [
-                        ("V", [0.81, 0.69, 0.06, 1.0]),
-                        ("a", [0.84, 0.61, 0.15, 1.0]),
-                        ("l", [0.86, 0.52, 0.24, 1.0]),
-                        ("t", [0.89, 0.44, 0.33, 1.0]),
-                        ("h", [0.92, 0.36, 0.41, 1.0]),
-                        ("r", [0.95, 0.27, 0.50, 1.0]),
-                        ("u", [0.97, 0.19, 0.59, 1.0]),
-                        ("n", [1.00, 0.11, 0.68, 1.0]),
-                    ] {
-                        ui.text_colored(color, text);
-                        ui.same_line();
-                    }
-
-                    ui.new_line();
-                    ui.dummy([ 0.0, 5.0 ]);
-                }
+                ui.set_window_font_scale(theme_font_scale.clamp(0.5, 3.0));
+                Self::render_title(ui);
 
                 let _content_font = ui.push_font(content_font);
                 let mut settings = app.settings_mut();
+                Self::render_config_file_status(&app.settings_watcher, ui);
 
                 if let Some(_tab_bar) = ui.tab_bar("main") {
                     if let Some(_tab) = ui.tab_item("Information") {
@@ -151,26 +197,11 @@ impl SettingsUI {
                     }
 
                     if let Some(_) = ui.tab_item("Hotkeys") {
-                        ui.button_key(obfstr!("Toggle Settings"), &mut settings.key_settings, [150.0, 0.0]);
-
-                        {
-                            let _enabled = ui.begin_enabled(matches!(settings.esp_mode, KeyToggleMode::Toggle | KeyToggleMode::Trigger));
-                            ui.button_key_optional(obfstr!("ESP toggle/trigger"), &mut settings.esp_toogle, [ 150.0, 0.0 ]);
-                        }
+                        Self::render_hotkey_settings(&mut settings, ui);
                     }
 
                     if let Some(_tab) = ui.tab_item(obfstr!("Visuals")) {
-                        ui.set_next_item_width(150.0);
-                        ui.combo_enum(obfstr!("ESP"), &[
-                            (KeyToggleMode::Off, "Always Off"),
-                            (KeyToggleMode::Trigger, "Trigger"),
-                            (KeyToggleMode::TriggerInverted, "Trigger Inverted"),
-                            (KeyToggleMode::Toggle, "Toggle"),
-                            (KeyToggleMode::AlwaysOn, "Always On"),
-                        ], &mut settings.esp_mode);
-
-                        ui.checkbox(obfstr!("Bomb ESP"), &mut settings.bomb_esp);
-                        ui.checkbox(obfstr!("Spectators List"), &mut settings.spectators_list);
+                        Self::render_visual_settings(&mut settings, ui);
                     }
 
                     if let Some(_tab) = ui.tab_item(obfstr!("ESP")) {
@@ -240,8 +271,18 @@ impl SettingsUI {
                         self.render_web_radar(&mut settings, &mut web_radar, &app.cs2, ui);
                     }
 
+                    if let Some(_) = ui.tab_item(obfstr!("Profiles")) {
+                        self.render_profile_settings(&mut settings, ui);
+                    }
+
+                    if let Some(_) = ui.tab_item(obfstr!("Theme")) {
+                        self.render_theme_settings(&mut settings, ui);
+                    }
+
                     if let Some(_) = ui.tab_item("Misc") {
                         ui.checkbox(obfstr!("Valthrun Watermark"), &mut settings.valthrun_watermark);
+                        ui.slider_config(obfstr!("Font scale"), 0.5, 3.0).display_format("%.2f").build(&mut settings.font_scale);
+                        ui.checkbox(obfstr!("Scale fonts with the game HUD scale"), &mut settings.font_scale_from_game);
 
                         if ui.checkbox(obfstr!("Hide overlay from screen capture"), &mut settings.hide_overlay_from_screen_capture) {
                             app.settings_screen_capture_changed.store(true, Ordering::Relaxed);
@@ -250,9 +291,479 @@ impl SettingsUI {
                         if ui.checkbox(obfstr!("Show render debug overlay"), &mut settings.render_debug_window) {
                             app.settings_render_debug_window_changed.store(true, Ordering::Relaxed);
                         }
+
+                        ui.separator();
+                        ui.checkbox(obfstr!("Use game mouse sensitivity"), &mut settings.mouse_sensitivity_from_game);
+                        match app.app_state.resolve::<GameSettings>(()) {
+                            Ok(game_settings) => {
+                                let format_value = |value: Option<f32>| value.map(|value| format!("{:.2}", value)).unwrap_or_else(|| "unknown".to_string());
+                                ui.text(format!("{}: {}", obfstr!("Game sensitivity"), format_value(game_settings.sensitivity)));
+                                ui.text(format!("{}: {}", obfstr!("Game HUD scale"), format_value(game_settings.hud_scale)));
+                                ui.text(format!("{}: {:.0}", obfstr!("Game FOV"), game_settings.fov));
+                            }
+                            Err(error) => {
+                                ui.text(format!("{}: {:#}", obfstr!("Failed to read game settings"), error));
+                            }
+                        }
+
+                        ui.separator();
+                        {
+                            /* app settings are currently borrowed, therefore the localization can not be updated here */
+                            let localization = app.app_state.get::<GameLocalization>(());
+                            let available_languages = localization.as_ref().map(|localization| localization.available_languages()).unwrap_or_default();
+
+                            let mut languages = vec![None];
+                            languages.extend(available_languages.iter().cloned().map(Some));
+                            if let Some(language) = &settings.localization_language {
+                                if !languages.contains(&Some(language.clone())) {
+                                    languages.push(Some(language.clone()));
+                                }
+                            }
+
+                            let mut language_index = languages.iter().position(|language| *language == settings.localization_language).unwrap_or_default();
+                            /* a closure can not return a value borrowing from its argument */
+                            fn language_name(language: &Option<String>) -> Cow<'_, str> {
+                                match language {
+                                    Some(language) => language.as_str().into(),
+                                    None => obfstr!("Game language").to_string().into(),
+                                }
+                            }
+                            if ui.combo(obfstr!("Language"), &mut language_index, &languages, language_name) {
+                                settings.localization_language = languages[language_index].clone();
+                            }
+
+                            match localization.as_ref().and_then(|localization| localization.language()) {
+                                Some(language) => ui.text(format!("{}: {}", obfstr!("Loaded language"), language)),
+                                None => ui.text(obfstr!("Game localization not loaded. Using built in names.")),
+                            }
+
+                            if let Ok(map) = app.app_state.resolve::<CurrentMapState>(()) {
+                                let map_title = match (&localization, &map.current_map) {
+                                    (Some(localization), Some(map_name)) => localization.map_title(map_name).to_string(),
+                                    (None, Some(map_name)) => map_name.clone(),
+                                    (_, None) => obfstr!("none").to_string(),
+                                };
+                                ui.text(format!("{}: {}", obfstr!("Current map"), map_title));
+                            }
+                        }
+
+                        let mut game_directory = settings.game_directory.clone().unwrap_or_default();
+                        if ui.input_text(obfstr!("Game directory"), &mut game_directory).hint(obfstr!("detect automatically")).build() {
+                            settings.game_directory = Some(game_directory).filter(|directory| !directory.is_empty());
+                        }
+                    }
+                }
+            });
+    }
+
+    /// Colored "Valthrun" title of the settings window
+    fn render_title(ui: &imgui::Ui) {
+        for (text, color) in [
+            ("V", [0.81, 0.69, 0.06, 1.0]),
+            ("a", [0.84, 0.61, 0.15, 1.0]),
+            ("l", [0.86, 0.52, 0.24, 1.0]),
+            ("t", [0.89, 0.44, 0.33, 1.0]),
+            ("h", [0.92, 0.36, 0.41, 1.0]),
+            ("r", [0.95, 0.27, 0.50, 1.0]),
+            ("u", [0.97, 0.19, 0.59, 1.0]),
+            ("n", [1.00, 0.11, 0.68, 1.0]),
+        ] {
+            ui.text_colored(color, text);
+            ui.same_line();
+        }
+
+        ui.new_line();
+        ui.dummy([0.0, 5.0]);
+    }
+
+    fn render_hotkey_settings(settings: &mut AppSettings, ui: &imgui::Ui) {
+        ui.button_key(
+            obfstr!("Toggle Settings"),
+            &mut settings.key_settings,
+            [150.0, 0.0],
+        );
+
+        {
+            let _enabled = ui.begin_enabled(matches!(
+                settings.esp_mode,
+                KeyToggleMode::Toggle | KeyToggleMode::Trigger
+            ));
+            ui.button_key_optional(
+                obfstr!("ESP toggle/trigger"),
+                &mut settings.esp_toggle,
+                [150.0, 0.0],
+            );
+        }
+
+        ui.button_key_optional(
+            obfstr!("Next settings profile"),
+            &mut settings.key_next_profile,
+            [150.0, 0.0],
+        );
+    }
+
+    fn render_visual_settings(settings: &mut AppSettings, ui: &imgui::Ui) {
+        ui.set_next_item_width(150.0);
+        ui.combo_enum(
+            obfstr!("ESP"),
+            &[
+                (KeyToggleMode::Off, "Always Off"),
+                (KeyToggleMode::Trigger, "Trigger"),
+                (KeyToggleMode::TriggerInverted, "Trigger Inverted"),
+                (KeyToggleMode::Toggle, "Toggle"),
+                (KeyToggleMode::AlwaysOn, "Always On"),
+            ],
+            &mut settings.esp_mode,
+        );
+
+        ui.checkbox(obfstr!("Bomb ESP"), &mut settings.bomb_esp);
+        ui.checkbox(obfstr!("Spectators List"), &mut settings.spectators_list);
+    }
+
+    fn render_config_file_status(watcher: &AppSettingsWatcher, ui: &imgui::Ui) {
+        if let Some(error) = watcher.error() {
+            ui.text_colored(
+                [1.0, 0.3, 0.3, 1.0],
+                obfstr!("config.yaml is invalid and has not been reloaded."),
+            );
+            ui.text(obfstr!(
+                "Changes will not be saved until the file has been fixed."
+            ));
+            ui.text_wrapped(error);
+            ui.separator();
+            return;
+        }
+
+        let show_reloaded = watcher
+            .reload_time()
+            .map(|time| time.elapsed().as_millis() < 10_000)
+            .unwrap_or(false);
+        if !show_reloaded {
+            return;
+        }
+
+        ui.text(obfstr!("config.yaml has been reloaded."));
+        if !watcher.reload_conflicts().is_empty() {
+            ui.text_wrapped(format!(
+                "{}: {}",
+                obfstr!("Changed in the file and in the overlay, using the file value"),
+                watcher.reload_conflicts().join(", ")
+            ));
+        }
+        ui.separator();
+    }
+
+    fn render_profile_settings(&mut self, settings: &mut AppSettings, ui: &imgui::Ui) {
+        let profiles = self.profiles.get_or_insert_with(|| {
+            load_profiles().unwrap_or_else(|error| {
+                log::warn!("Failed to load settings profiles: {:#}", error);
+                Vec::new()
+            })
+        });
+
+        ui.text(format!(
+            "{}: {}",
+            obfstr!("Active profile"),
+            settings.active_profile.as_deref().unwrap_or("-")
+        ));
+        ui.text_disabled(obfstr!(
+            "Profiles contain the ESP, bomb, HUD and theme settings."
+        ));
+
+        let mut message = None;
+        let mut reload_profiles = false;
+
+        ui.set_next_item_width(200.0);
+        ui.input_text("##profile_name", &mut self.profile_name)
+            .hint(obfstr!("Profile name"))
+            .build();
+        ui.same_line();
+        {
+            let name = self.profile_name.trim();
+            let _enabled = ui.begin_enabled(!name.is_empty());
+            if ui.button(obfstr!("Create from current settings")) {
+                let profile = SettingsProfile::capture(name, settings);
+                message = Some(match profile.save() {
+                    Ok(_) => {
+                        settings.active_profile = Some(profile.name.clone());
+                        reload_profiles = true;
+                        format!("{} {}", obfstr!("Created profile"), profile.name)
+                    }
+                    Err(error) => format!("{:#}", error),
+                });
+            }
+        }
+
+        ui.same_line();
+        if ui.button(obfstr!("Import share code")) {
+            let profile = ui
+                .clipboard_text()
+                .ok_or_else(|| anyhow::anyhow!("the clipboard is empty"))
+                .and_then(|code| SettingsProfile::from_share_code(&code))
+                .and_then(|profile| profile.save().map(|_| profile));
+
+            message = Some(match profile {
+                Ok(profile) => {
+                    reload_profiles = true;
+                    format!("{} {}", obfstr!("Imported profile"), profile.name)
+                }
+                Err(error) => format!("{}: {:#}", obfstr!("Failed to import profile"), error),
+            });
+        }
+
+        ui.separator();
+        if profiles.is_empty() {
+            ui.text(obfstr!("No profiles have been created yet."));
+        }
+
+        for profile in profiles.iter() {
+            let _id = ui.push_id(profile.name.as_str());
+            let active = settings.active_profile.as_ref() == Some(&profile.name);
+            if active {
+                ui.text_colored([0.18, 0.51, 0.97, 1.0], &profile.name);
+            } else {
+                ui.text(&profile.name);
+            }
+
+            ui.same_line_with_pos(200.0);
+            if ui.small_button(obfstr!("Load")) {
+                activate_profile(settings, profile);
+                message = Some(format!("{} {}", obfstr!("Loaded profile"), profile.name));
+            }
+
+            ui.same_line();
+            if ui.small_button(obfstr!("Save")) {
+                let profile = SettingsProfile::capture(&profile.name, settings);
+                message = Some(match profile.save() {
+                    Ok(_) => {
+                        settings.active_profile = Some(profile.name.clone());
+                        reload_profiles = true;
+                        format!("{} {}", obfstr!("Saved profile"), profile.name)
+                    }
+                    Err(error) => format!("{:#}", error),
+                });
+            }
+
+            ui.same_line();
+            if ui.small_button(obfstr!("Copy share code")) {
+                message = Some(match profile.to_share_code() {
+                    Ok(code) => {
+                        ui.set_clipboard_text(code);
+                        obfstr!("Share code copied to clipboard").to_string()
+                    }
+                    Err(error) => format!("{:#}", error),
+                });
+            }
+
+            ui.same_line();
+            if ui.small_button(obfstr!("Delete")) {
+                message = Some(match delete_profile(&profile.name) {
+                    Ok(_) => {
+                        if active {
+                            settings.active_profile = None;
+                        }
+                        reload_profiles = true;
+                        format!("{} {}", obfstr!("Deleted profile"), profile.name)
                     }
+                    Err(error) => format!("{:#}", error),
+                });
+            }
+        }
+
+        if reload_profiles {
+            self.profiles = None;
+        }
+        if message.is_some() {
+            self.profile_message = message;
+        }
+        if let Some(message) = &self.profile_message {
+            ui.separator();
+            ui.text_wrapped(message);
+        }
+    }
+
+    fn render_theme_settings(&mut self, settings: &mut AppSettings, ui: &imgui::Ui) {
+        let themes = self.themes.get_or_insert_with(|| {
+            let mut themes = builtin_themes();
+            match load_user_themes() {
+                Ok(user_themes) => themes.extend(user_themes),
+                Err(error) => log::warn!("Failed to load user themes: {:#}", error),
+            }
+            themes
+        });
+
+        let mut theme_index = themes
+            .iter()
+            .position(|theme| theme.name == settings.theme.name)
+            .unwrap_or(usize::MAX);
+        ui.set_next_item_width(200.0);
+        if ui.combo(
+            obfstr!("Theme"),
+            &mut theme_index,
+            themes,
+            |theme: &UiTheme| -> Cow<str> { theme.name.clone().into() },
+        ) {
+            settings.theme = themes[theme_index].clone();
+        }
+
+        ui.same_line();
+        if ui.button(obfstr!("Reset")) {
+            settings.theme = themes
+                .iter()
+                .find(|theme| theme.name == settings.theme.name)
+                .cloned()
+                .unwrap_or_default();
+        }
+
+        ui.same_line();
+        if ui.button(obfstr!("Reload themes")) {
+            self.themes = None;
+        }
+
+        if ui.button(obfstr!("Save to themes folder")) {
+            self.theme_message = Some(match settings.theme.save() {
+                Ok(path) => {
+                    self.themes = None;
+                    format!("{} {}", obfstr!("Saved theme to"), path.display())
+                }
+                Err(error) => format!("{}: {:#}", obfstr!("Failed to save theme"), error),
+            });
+        }
+
+        ui.same_line();
+        if ui.button(obfstr!("Copy as YAML")) {
+            self.theme_message = Some(match settings.theme.to_yaml() {
+                Ok(yaml) => {
+                    ui.set_clipboard_text(yaml);
+                    obfstr!("Theme copied to clipboard").to_string()
+                }
+                Err(error) => format!("{:#}", error),
+            });
+        }
+
+        ui.same_line();
+        if ui.button(obfstr!("Import from clipboard")) {
+            let theme = ui
+                .clipboard_text()
+                .ok_or_else(|| anyhow::anyhow!("the clipboard is empty"))
+                .and_then(|yaml| UiTheme::from_yaml(&yaml));
+
+            self.theme_message = Some(match theme {
+                Ok(theme) => {
+                    let message = format!("{} {}", obfstr!("Imported theme"), theme.name);
+                    settings.theme = theme;
+                    message
                 }
+                Err(error) => format!("{}: {:#}", obfstr!("Failed to import theme"), error),
             });
+        }
+
+        if let Some(message) = &self.theme_message {
+            ui.text_wrapped(message);
+        }
+
+        ui.separator();
+
+        let theme = &mut settings.theme;
+        ui.set_next_item_width(200.0);
+        ui.input_text(obfstr!("Name"), &mut theme.name).build();
+
+        ui.set_next_item_width(200.0);
+        ui.combo_enum(
+            obfstr!("Base colors"),
+            &[
+                (UiThemeBase::Dark, "Dark"),
+                (UiThemeBase::Light, "Light"),
+                (UiThemeBase::Classic, "Classic"),
+            ],
+            &mut theme.base,
+        );
+
+        ui.set_next_item_width(200.0);
+        ui.slider_config(obfstr!("Text scale"), 0.5, 2.0)
+            .display_format("%.2f")
+            .build(&mut theme.font_scale);
+        ui.set_next_item_width(200.0);
+        ui.slider_config(obfstr!("Alpha"), 0.2, 1.0)
+            .display_format("%.2f")
+            .build(&mut theme.alpha);
+
+        if ui.collapsing_header(obfstr!("Rounding"), TreeNodeFlags::empty()) {
+            for (label, value) in [
+                ("Window", &mut theme.rounding.window),
+                ("Child", &mut theme.rounding.child),
+                ("Popup", &mut theme.rounding.popup),
+                ("Frame", &mut theme.rounding.frame),
+                ("Scrollbar", &mut theme.rounding.scrollbar),
+                ("Grab", &mut theme.rounding.grab),
+                ("Tab", &mut theme.rounding.tab),
+            ] {
+                ui.set_next_item_width(200.0);
+                ui.slider_config(format!("{}##rounding", label), 0.0, 12.0)
+                    .display_format("%.0f")
+                    .build(value);
+            }
+        }
+
+        if ui.collapsing_header(obfstr!("Spacing"), TreeNodeFlags::empty()) {
+            for (label, value) in [
+                ("Window padding", &mut theme.spacing.window_padding),
+                ("Frame padding", &mut theme.spacing.frame_padding),
+                ("Item spacing", &mut theme.spacing.item_spacing),
+                ("Item inner spacing", &mut theme.spacing.item_inner_spacing),
+            ] {
+                ui.set_next_item_width(200.0);
+                ui.slider_config(label, 0.0, 20.0)
+                    .display_format("%.0f")
+                    .build_array(value);
+            }
+
+            for (label, value, max) in [
+                ("Indent spacing", &mut theme.spacing.indent_spacing, 30.0),
+                ("Scrollbar size", &mut theme.spacing.scrollbar_size, 20.0),
+                ("Grab min size", &mut theme.spacing.grab_min_size, 20.0),
+                ("Window border", &mut theme.spacing.window_border_size, 3.0),
+                ("Frame border", &mut theme.spacing.frame_border_size, 3.0),
+            ] {
+                ui.set_next_item_width(200.0);
+                ui.slider_config(label, 0.0, max)
+                    .display_format("%.0f")
+                    .build(value);
+            }
+        }
+
+        if ui.collapsing_header(obfstr!("Colors"), TreeNodeFlags::empty()) {
+            ui.set_next_item_width(200.0);
+            ui.input_text(obfstr!("Filter"), &mut self.theme_color_filter)
+                .build();
+
+            let filter = self.theme_color_filter.to_lowercase();
+            let base_style = theme.base_style(&ui.clone_style());
+            for color in StyleColor::VARIANTS {
+                let name = style_color_name(color);
+                if !name.to_lowercase().contains(&filter) {
+                    continue;
+                }
+
+                let overridden = theme.color(color);
+                let mut value = overridden.unwrap_or(base_style[color]);
+                if ui
+                    .color_edit4_config(&name, &mut value)
+                    .alpha_bar(true)
+                    .inputs(false)
+                    .build()
+                {
+                    theme.set_color(color, value);
+                }
+
+                if overridden.is_some() {
+                    ui.same_line();
+                    if ui.small_button(format!("{}##{}", obfstr!("Reset"), name)) {
+                        theme.reset_color(color);
+                    }
+                }
+            }
+        }
     }
 
     fn render_web_radar(
@@ -326,7 +837,44 @@ impl SettingsUI {
                             }
                         }
 
+                        {
+                            ui.text("Password");
+
+                            ui.same_line_with_pos(100.0);
+                            ui.set_next_item_width(300.0);
+                            ui.input_text("##radar_password", &mut self.radar_password)
+                                .password(true)
+                                .hint(if radar.is_password_protected() {
+                                    "Password protected"
+                                } else {
+                                    "No password"
+                                })
+                                .build();
+
+                            ui.same_line();
+                            if ui.button("Change password") {
+                                let password = Some(self.radar_password.clone())
+                                    .filter(|password| !password.is_empty());
+                                radar.set_password(password);
+                            }
+                        }
+
+                        if let Some(expires_at) = radar.session_expires_at() {
+                            let remaining = expires_at.saturating_duration_since(Instant::now());
+                            ui.text(format!(
+                                "The session expires in {:02}:{:02}:{:02}",
+                                remaining.as_secs() / 3600,
+                                remaining.as_secs() / 60 % 60,
+                                remaining.as_secs() % 60
+                            ));
+                        }
+
                         ui.new_line();
+                        if ui.button("Kick all viewers") {
+                            radar.kick_viewers();
+                        }
+
+                        ui.same_line();
                         if ui.button("Stop sharing") {
                             radar.close_connection();
                             drop(radar);
@@ -360,7 +908,15 @@ impl SettingsUI {
                 ui.disabled(url.is_err(), || {
                     if ui.button("Enable WebRadar") {
                         let url = url.as_ref().unwrap();
-                        *web_radar = Some(radar::create_web_radar(url.clone(), cs2.clone()));
+                        let options = PublishSessionOptions {
+                            password: Some(self.radar_password.clone())
+                                .filter(|password| !password.is_empty()),
+                            max_lifetime: Some(self.radar_lifetime as u64)
+                                .filter(|minutes| *minutes > 0)
+                                .map(|minutes| Duration::from_secs(minutes * 60)),
+                        };
+                        *web_radar =
+                            Some(radar::create_web_radar(url.clone(), cs2.clone(), options));
                     }
                 });
 
@@ -387,6 +943,28 @@ impl SettingsUI {
                 );
                 ui.text("This means you can also show the radar with all the enemy info with your team mates.");
 
+                ui.new_line();
+                ui.text("Password");
+                ui.same_line_with_pos(100.0);
+                ui.set_next_item_width(200.0);
+                ui.input_text("##radar_password", &mut self.radar_password)
+                    .password(true)
+                    .hint("Optional")
+                    .build();
+
+                ui.text("Lifetime");
+                ui.same_line_with_pos(100.0);
+                ui.set_next_item_width(200.0);
+                if ui
+                    .input_int("##radar_lifetime", &mut self.radar_lifetime)
+                    .step(15)
+                    .build()
+                {
+                    self.radar_lifetime = self.radar_lifetime.clamp(0, 7 * 24 * 60);
+                }
+                ui.same_line();
+                ui.text_disabled("minutes (0 = unlimited)");
+
                 if settings.web_radar_advanced_settings {
                     ui.new_line();
                     ui.text("Advanced Settings");
@@ -1222,3 +1800,70 @@ impl SettingsUI {
         drop(_ui_enable_token);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use imgui::Condition;
+    use utils_reference_renderer::{
+        assert_matches_baseline,
+        FrameHarness,
+        RgbaImage,
+    };
+
+    use super::SettingsUI;
+    use crate::settings::AppSettings;
+
+    fn render_settings_tab(
+        tab: &str,
+        mut settings: AppSettings,
+        mut render: impl FnMut(&mut SettingsUI, &mut AppSettings, &imgui::Ui),
+    ) -> RgbaImage {
+        let mut settings_ui = SettingsUI::new();
+        FrameHarness::new(600, 300)
+            .render(|ui| {
+                ui.window("Valthrun")
+                    .size([600.0, 300.0], Condition::Always)
+                    .position([0.0, 0.0], Condition::Always)
+                    .title_bar(false)
+                    .build(|| {
+                        SettingsUI::render_title(ui);
+                        if let Some(_tab_bar) = ui.tab_bar("main") {
+                            if let Some(_tab) = ui.tab_item(tab) {
+                                render(&mut settings_ui, &mut settings, ui);
+                            }
+                        }
+                    });
+            })
+            .unwrap()
+    }
+
+    fn assert_baseline(image: &RgbaImage, name: &str) {
+        let baseline = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("baselines")
+            .join(name);
+        assert_matches_baseline(image, &baseline, 2).unwrap();
+    }
+
+    #[test]
+    fn test_visual_settings_baseline() {
+        let settings: AppSettings = serde_yaml::from_str("").unwrap();
+        let image = render_settings_tab("Visuals", settings, |_, settings, ui| {
+            SettingsUI::render_visual_settings(settings, ui)
+        });
+        assert_baseline(&image, "settings_visuals.png");
+    }
+
+    #[test]
+    fn test_bomb_settings_baseline() {
+        let mut settings: AppSettings = serde_yaml::from_str("").unwrap();
+        settings.bomb_esp = true;
+        settings.bomb_esp_settings.bomb_position = true;
+
+        let image = render_settings_tab("Bomb", settings, |settings_ui, settings, ui| {
+            settings_ui.render_bomb_settings(settings, ui)
+        });
+        assert_baseline(&image, "settings_bomb.png");
+    }
+}
@@ -0,0 +1,486 @@
+use std::{
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+    },
+};
+
+use anyhow::Context;
+use serde_yaml::{
+    Mapping,
+    Value,
+};
+
+use super::{
+    get_settings_path,
+    parse_app_settings,
+    AppSettings,
+};
+
+/// Interval in which the config file will be checked for external changes
+const SETTINGS_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    length: u64,
+}
+
+impl FileStamp {
+    fn read(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok(),
+            length: metadata.len(),
+        })
+    }
+}
+
+pub enum SettingsFileChange {
+    /// The file has been merged into the settings.
+    /// Values changed in the file and in memory are listed as conflicts (the file value wins).
+    Reloaded {
+        conflicts: Vec<String>,
+
+        /// The merged settings contain changes which are not yet part of the file
+        unsaved_changes: bool,
+    },
+
+    /// The file is invalid and has been ignored
+    Rejected,
+
+    /// The file has been deleted and needs to be recreated by saving the settings
+    Removed,
+}
+
+/// Watches the config file for edits made outside of the application
+/// and merges them with the in memory settings.
+pub struct AppSettingsWatcher {
+    path: PathBuf,
+    stamp: Option<FileStamp>,
+    last_check: Instant,
+
+    /// Content last written by the application.
+    /// The file stamp can not be captured atomically with the write,
+    /// therefore the content will be compared on the next check.
+    saved_content: Option<String>,
+
+    /// Settings as they have last been read from or written to the file
+    base: Value,
+
+    error: Option<String>,
+    reload_conflicts: Vec<String>,
+    reload_time: Option<Instant>,
+}
+
+impl AppSettingsWatcher {
+    pub fn new(settings: &AppSettings) -> anyhow::Result<Self> {
+        Self::with_path(get_settings_path()?, settings)
+    }
+
+    fn with_path(path: PathBuf, settings: &AppSettings) -> anyhow::Result<Self> {
+        Ok(Self {
+            stamp: FileStamp::read(&path),
+            path,
+            last_check: Instant::now(),
+            saved_content: None,
+
+            base: serialize_settings(settings)?,
+
+            error: None,
+            reload_conflicts: Vec::new(),
+            reload_time: None,
+        })
+    }
+
+    /// Error of the last rejected config file
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Conflicting keys of the last reload
+    pub fn reload_conflicts(&self) -> &[String] {
+        &self.reload_conflicts
+    }
+
+    pub fn reload_time(&self) -> Option<Instant> {
+        self.reload_time
+    }
+
+    /// Track settings which have been written to the file by the application
+    pub fn mark_saved(&mut self, settings: &AppSettings) -> anyhow::Result<()> {
+        self.base = serialize_settings(settings)?;
+        self.saved_content =
+            Some(serde_yaml::to_string(settings).context("failed to serialize config")?);
+
+        /* the file might have been changed externally since writing it */
+        self.stamp = None;
+        self.error = None;
+        Ok(())
+    }
+
+    /// Check the config file for changes if the watch interval has elapsed
+    pub fn poll(&mut self, settings: &mut AppSettings) -> Option<SettingsFileChange> {
+        if self.last_check.elapsed() < SETTINGS_WATCH_INTERVAL {
+            return None;
+        }
+
+        self.check(settings)
+    }
+
+    /// Check the config file for changes and merge them into the settings
+    pub fn check(&mut self, settings: &mut AppSettings) -> Option<SettingsFileChange> {
+        self.last_check = Instant::now();
+
+        let stamp = FileStamp::read(&self.path);
+        if stamp == self.stamp {
+            return None;
+        }
+        self.stamp = stamp;
+
+        if stamp.is_none() {
+            /* the error of a deleted file must not prevent recreating it */
+            self.error = None;
+            self.saved_content = None;
+            return Some(SettingsFileChange::Removed);
+        }
+
+        let config = fs::read_to_string(&self.path).context("failed to read app config");
+        let saved_content = self.saved_content.take();
+        if let (Ok(config), Some(saved_content)) = (&config, saved_content) {
+            if *config == saved_content {
+                /* the file contains the settings written by the application */
+                return None;
+            }
+        }
+
+        let change = match config.and_then(|config| self.reload(settings, &config)) {
+            Ok(change) => {
+                self.error = None;
+                change
+            }
+            Err(error) => {
+                log::warn!(
+                    "Ignoring external changes of {}: {:#}",
+                    self.path.to_string_lossy(),
+                    error
+                );
+                self.error = Some(format!("{:#}", error));
+                SettingsFileChange::Rejected
+            }
+        };
+        Some(change)
+    }
+
+    fn reload(
+        &mut self,
+        settings: &mut AppSettings,
+        config: &str,
+    ) -> anyhow::Result<SettingsFileChange> {
+        let config: Value = serde_yaml::from_str(config).context("failed to parse app config")?;
+
+        let (file_settings, report) = parse_app_settings(config)?;
+        report.log();
+
+        let file = serialize_settings(&file_settings)?;
+        let local = serialize_settings(settings)?;
+        if file == self.base {
+            /* only formatting or comments have been changed */
+            return Ok(SettingsFileChange::Reloaded {
+                conflicts: Vec::new(),
+                unsaved_changes: false,
+            });
+        }
+
+        let mut conflicts = Vec::new();
+        let merged = merge_value(
+            Some(&self.base),
+            Some(&local),
+            Some(&file),
+            "",
+            &mut conflicts,
+        )
+        .unwrap_or(Value::Null);
+
+        let merged_settings: AppSettings =
+            serde_yaml::from_value(merged.clone()).context("failed to merge app config")?;
+        let unsaved_changes = merged != file;
+
+        log::info!("Reloaded app config from {}", self.path.to_string_lossy());
+        for key in conflicts.iter() {
+            log::warn!(
+                "App config option {} has been changed in the file and in the overlay. Using the file value.",
+                key
+            );
+        }
+
+        *settings = merged_settings;
+        self.base = file;
+
+        self.reload_time = Some(Instant::now());
+        self.reload_conflicts = conflicts.clone();
+        Ok(SettingsFileChange::Reloaded {
+            conflicts,
+            unsaved_changes,
+        })
+    }
+}
+
+fn serialize_settings(settings: &AppSettings) -> anyhow::Result<Value> {
+    let mut value = serde_yaml::to_value(settings).context("failed to serialize config")?;
+    if let Value::Mapping(value) = &mut value {
+        /* the version is always migrated to the current version */
+        value.remove("version");
+    }
+
+    Ok(value)
+}
+
+/// Three way merge of the local and file changes relative to the last synchronized state.
+/// Nested mappings are merged per key. Returns None if the value has been removed.
+fn merge_value(
+    base: Option<&Value>,
+    local: Option<&Value>,
+    file: Option<&Value>,
+    path: &str,
+    conflicts: &mut Vec<String>,
+) -> Option<Value> {
+    if file == base || local == file {
+        return local.cloned();
+    }
+
+    if local == base {
+        return file.cloned();
+    }
+
+    if let (Some(Value::Mapping(local)), Some(Value::Mapping(file))) = (local, file) {
+        let empty = Mapping::new();
+        let base = match base {
+            Some(Value::Mapping(base)) => base,
+            _ => &empty,
+        };
+
+        let mut result = Mapping::new();
+        for key in local
+            .keys()
+            .chain(file.keys().filter(|key| !local.contains_key(*key)))
+        {
+            let key_path = match key {
+                Value::String(key) if path.is_empty() => key.clone(),
+                Value::String(key) => format!("{}.{}", path, key),
+                key => format!("{}.{:?}", path, key),
+            };
+
+            let value = merge_value(
+                base.get(key),
+                local.get(key),
+                file.get(key),
+                &key_path,
+                conflicts,
+            );
+            if let Some(value) = value {
+                result.insert(key.clone(), value);
+            }
+        }
+
+        return Some(Value::Mapping(result));
+    }
+
+    conflicts.push(path.to_string());
+    file.cloned()
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        fs,
+        path::Path,
+    };
+
+    use serde_yaml::Value;
+
+    use super::{
+        merge_value,
+        AppSettingsWatcher,
+        SettingsFileChange,
+    };
+    use crate::settings::AppSettings;
+
+    fn yaml(value: &str) -> Value {
+        serde_yaml::from_str(value).unwrap()
+    }
+
+    fn merge(base: &str, local: &str, file: &str) -> (Option<Value>, Vec<String>) {
+        let mut conflicts = Vec::new();
+        let result = merge_value(
+            Some(&yaml(base)),
+            Some(&yaml(local)),
+            Some(&yaml(file)),
+            "",
+            &mut conflicts,
+        );
+        (result, conflicts)
+    }
+
+    #[test]
+    fn test_merge_one_side_changed() {
+        let base = "{ a: 1, b: 2 }";
+
+        /* local only */
+        let (result, conflicts) = merge(base, "{ a: 3, b: 2 }", base);
+        assert_eq!(result, Some(yaml("{ a: 3, b: 2 }")));
+        assert!(conflicts.is_empty());
+
+        /* file only */
+        let (result, conflicts) = merge(base, base, "{ a: 1, b: 4 }");
+        assert_eq!(result, Some(yaml("{ a: 1, b: 4 }")));
+        assert!(conflicts.is_empty());
+
+        /* both sides changed different keys */
+        let (result, conflicts) = merge(base, "{ a: 3, b: 2 }", "{ a: 1, b: 4 }");
+        assert_eq!(result, Some(yaml("{ a: 3, b: 4 }")));
+        assert!(conflicts.is_empty());
+
+        /* both sides made the same change */
+        let (result, conflicts) = merge(base, "{ a: 3, b: 2 }", "{ a: 3, b: 2 }");
+        assert_eq!(result, Some(yaml("{ a: 3, b: 2 }")));
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_merge_conflict() {
+        let (result, conflicts) = merge("{ a: 1, b: 2 }", "{ a: 3, b: 2 }", "{ a: 4, b: 2 }");
+        assert_eq!(result, Some(yaml("{ a: 4, b: 2 }")));
+        assert_eq!(conflicts, vec!["a"]);
+    }
+
+    #[test]
+    fn test_merge_nested() {
+        let (result, conflicts) = merge(
+            "{ esp: { player: { box: 1, color: red }, chicken: false } }",
+            "{ esp: { player: { box: 2, color: red }, chicken: true } }",
+            "{ esp: { player: { box: 3, color: blue }, chicken: false } }",
+        );
+        assert_eq!(
+            result,
+            Some(yaml(
+                "{ esp: { player: { box: 3, color: blue }, chicken: true } }"
+            ))
+        );
+        assert_eq!(conflicts, vec!["esp.player.box"]);
+    }
+
+    #[test]
+    fn test_merge_removal() {
+        /* removed from the file */
+        let (result, conflicts) = merge("{ a: 1, b: 2 }", "{ a: 1, b: 2 }", "{ a: 1 }");
+        assert_eq!(result, Some(yaml("{ a: 1 }")));
+        assert!(conflicts.is_empty());
+
+        /* removed locally while the file changed another key */
+        let (result, conflicts) = merge("{ a: 1, b: 2 }", "{ a: 1 }", "{ a: 3, b: 2 }");
+        assert_eq!(result, Some(yaml("{ a: 3 }")));
+        assert!(conflicts.is_empty());
+
+        /* removed from the file while changed locally */
+        let (result, conflicts) = merge("{ a: 1, b: 2 }", "{ a: 1, b: 5 }", "{ a: 1 }");
+        assert_eq!(result, Some(yaml("{ a: 1 }")));
+        assert_eq!(conflicts, vec!["b"]);
+
+        /* added by the file */
+        let (result, conflicts) = merge("{ a: 1 }", "{ a: 2 }", "{ a: 1, c: 3 }");
+        assert_eq!(result, Some(yaml("{ a: 2, c: 3 }")));
+        assert!(conflicts.is_empty());
+    }
+
+    fn write_settings(path: &Path, settings: &AppSettings) {
+        fs::write(path, serde_yaml::to_string(settings).unwrap()).unwrap();
+    }
+
+    fn edit_file(path: &Path, key: &str, value: Value) {
+        let mut config = yaml(&fs::read_to_string(path).unwrap());
+        config[key] = value;
+        fs::write(path, serde_yaml::to_string(&config).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_watch_file() {
+        let directory =
+            std::env::temp_dir().join(format!("settings-watcher-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("config.yaml");
+
+        let mut settings: AppSettings = serde_yaml::from_str("{}").unwrap();
+        settings.bomb_esp = true;
+        settings.font_scale = 1.0;
+        write_settings(&path, &settings);
+        let mut watcher = AppSettingsWatcher::with_path(path.clone(), &settings).unwrap();
+        assert!(watcher.check(&mut settings).is_none());
+
+        /* files written by the application will not be reloaded */
+        settings.font_scale = 1.5;
+        write_settings(&path, &settings);
+        watcher.mark_saved(&settings).unwrap();
+        assert!(watcher.check(&mut settings).is_none());
+
+        /* external changes are merged with the unsaved local changes */
+        settings.spectators_list = !settings.spectators_list;
+        let spectators_list = settings.spectators_list;
+        edit_file(&path, "bomb_esp", Value::Bool(false));
+        assert!(matches!(
+            watcher.check(&mut settings),
+            Some(SettingsFileChange::Reloaded {
+                unsaved_changes: true,
+                ..
+            })
+        ));
+        assert!(!settings.bomb_esp);
+        assert_eq!(settings.spectators_list, spectators_list);
+        assert!(watcher.reload_conflicts().is_empty());
+
+        /* the file value wins on conflicts */
+        settings.font_scale = 2.0;
+        edit_file(&path, "font_scale", Value::from(0.75));
+        match watcher.check(&mut settings) {
+            Some(SettingsFileChange::Reloaded { conflicts, .. }) => {
+                assert_eq!(conflicts, vec!["font_scale"])
+            }
+            _ => panic!("expected a reload"),
+        }
+        assert_eq!(settings.font_scale, 0.75);
+
+        /* invalid files are rejected */
+        fs::write(&path, "font_scale: [").unwrap();
+        assert!(matches!(
+            watcher.check(&mut settings),
+            Some(SettingsFileChange::Rejected)
+        ));
+        assert!(watcher.error().is_some());
+        assert_eq!(settings.font_scale, 0.75);
+
+        /* deleting the invalid file clears the error so the file will be recreated */
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            watcher.check(&mut settings),
+            Some(SettingsFileChange::Removed)
+        ));
+        assert!(watcher.error().is_none());
+        assert!(watcher.check(&mut settings).is_none());
+
+        write_settings(&path, &settings);
+        watcher.mark_saved(&settings).unwrap();
+        assert!(watcher.check(&mut settings).is_none());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            serde_yaml::to_string(&settings).unwrap()
+        );
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,205 @@
+use std::marker::PhantomData;
+
+use cs2_schema_declaration::{
+    MemoryHandle,
+    SchemaValue,
+};
+
+use crate::{
+    align_up,
+    natural_alignment,
+    CUtlMemory,
+};
+
+/// Open addressing hash table.
+///
+/// struct CUtlHashtable<K, V> {
+///     pub table: CUtlMemory<CUtlHashtableEntry<K, V>>, // 0x00
+///     pub used: i32,                                   // 0x10
+///     pub min_size: i32,                               // 0x14
+///     pub size_locked: bool,                           // 0x18
+/// }
+pub struct CUtlHashtable<K, V> {
+    memory: MemoryHandle,
+    _dummy: PhantomData<(K, V)>,
+}
+
+impl<K, V> CUtlHashtable<K, V> {
+    pub fn used(&self) -> anyhow::Result<i32> {
+        self.memory.reference_schema(0x10)
+    }
+
+    pub fn min_size(&self) -> anyhow::Result<i32> {
+        self.memory.reference_schema(0x14)
+    }
+
+    pub fn size_locked(&self) -> anyhow::Result<bool> {
+        self.memory.reference_schema(0x18)
+    }
+}
+
+impl<K: SchemaValue, V: SchemaValue> CUtlHashtable<K, V> {
+    pub fn table(&self) -> anyhow::Result<CUtlMemory<CUtlHashtableEntry<K, V>>> {
+        self.memory.reference_schema(0x00)
+    }
+
+    /// Iterate over all occupied entries.
+    pub fn iter(
+        &self,
+    ) -> anyhow::Result<impl Iterator<Item = anyhow::Result<CUtlHashtableEntry<K, V>>>> {
+        let table = self.table()?;
+        let slot_count = table.allocation_count()? as usize;
+        let entries = if slot_count > 0 {
+            table.buffer()?.read_entries(slot_count)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(entries
+            .into_iter()
+            .filter_map(|entry| match entry.is_free() {
+                Ok(true) => None,
+                Ok(false) => Some(Ok(entry)),
+                Err(err) => Some(Err(err)),
+            }))
+    }
+}
+
+impl<K: SchemaValue + PartialEq, V: SchemaValue> CUtlHashtable<K, V> {
+    /// Find the entry with the given key.
+    pub fn find(&self, key: &K) -> anyhow::Result<Option<CUtlHashtableEntry<K, V>>> {
+        for entry in self.iter()? {
+            let entry = entry?;
+            if entry.key()? == *key {
+                return Ok(Some(entry));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Read the value of the entry with the given key.
+    pub fn get(&self, key: &K) -> anyhow::Result<Option<V>> {
+        self.find(key)?.map(|entry| entry.value()).transpose()
+    }
+}
+
+impl<K, V> SchemaValue for CUtlHashtable<K, V> {
+    fn value_size() -> Option<u64> {
+        Some(0x20)
+    }
+
+    fn from_memory(memory: MemoryHandle) -> anyhow::Result<Self> {
+        Ok(Self {
+            memory,
+            _dummy: Default::default(),
+        })
+    }
+}
+
+const HASHTABLE_FLAG_FREE: u32 = 0x80000000;
+
+/// struct CUtlHashtableEntry<K, V> {
+///     pub flags_and_hash: u32, // 0x00
+///     pub key: K,              // aligned after flags_and_hash
+///     pub value: V,            // aligned after key
+/// }
+pub struct CUtlHashtableEntry<K, V> {
+    memory: MemoryHandle,
+    _dummy: PhantomData<(K, V)>,
+}
+
+impl<K: SchemaValue, V: SchemaValue> CUtlHashtableEntry<K, V> {
+    fn key_offset() -> u64 {
+        let key_size = K::value_size().expect("K to have a size");
+        align_up(0x04, natural_alignment(key_size))
+    }
+
+    fn value_offset() -> u64 {
+        let key_size = K::value_size().expect("K to have a size");
+        let value_size = V::value_size().expect("V to have a size");
+        align_up(Self::key_offset() + key_size, natural_alignment(value_size))
+    }
+
+    pub fn flags_and_hash(&self) -> anyhow::Result<u32> {
+        self.memory.reference_schema(0x00)
+    }
+
+    pub fn is_free(&self) -> anyhow::Result<bool> {
+        Ok((self.flags_and_hash()? & HASHTABLE_FLAG_FREE) > 0)
+    }
+
+    pub fn key(&self) -> anyhow::Result<K> {
+        self.memory.reference_schema(Self::key_offset())
+    }
+
+    pub fn value(&self) -> anyhow::Result<V> {
+        self.memory.reference_schema(Self::value_offset())
+    }
+}
+
+impl<K: SchemaValue, V: SchemaValue> SchemaValue for CUtlHashtableEntry<K, V> {
+    fn value_size() -> Option<u64> {
+        let key_size = K::value_size()?;
+        let value_size = V::value_size()?;
+
+        let alignment = natural_alignment(key_size)
+            .max(natural_alignment(value_size))
+            .max(0x04);
+        Some(align_up(Self::value_offset() + value_size, alignment))
+    }
+
+    fn from_memory(memory: MemoryHandle) -> anyhow::Result<Self> {
+        Ok(Self {
+            memory,
+            _dummy: Default::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cs2_schema_declaration::MemoryHandle;
+
+    use super::{
+        CUtlHashtable,
+        HASHTABLE_FLAG_FREE,
+    };
+    use crate::mock::MockMemory;
+
+    #[test]
+    fn test_iterate_and_find() -> anyhow::Result<()> {
+        let mut memory = MockMemory::new(0x10000);
+        let table = memory.alloc(0x20);
+
+        /* entry layout: flags 0x00, key 0x08, value 0x10 (size 0x14 aligned to 0x18) */
+        let entries = memory.alloc(0x18 * 3);
+        memory.write_u32(entries, 0x1234);
+        memory.write_u64(entries + 0x08, 0xDEAD);
+        memory.write_u32(entries + 0x10, 1);
+
+        memory.write_u32(entries + 0x18, HASHTABLE_FLAG_FREE);
+
+        memory.write_u32(entries + 0x30, 0x4321);
+        memory.write_u64(entries + 0x38, 0xBEEF);
+        memory.write_u32(entries + 0x40, 2);
+
+        memory.write_u64(table, entries);
+        memory.write_u32(table + 0x08, 3);
+        memory.write_u32(table + 0x10, 2);
+
+        let driver = memory.into_driver();
+        let table = MemoryHandle::from_driver(&driver, table)
+            .reference_schema::<CUtlHashtable<u64, u32>>(0x00)?;
+
+        let keys = table
+            .iter()?
+            .map(|entry| entry?.key())
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        assert_eq!(keys, vec![0xDEAD, 0xBEEF]);
+
+        assert_eq!(table.get(&0xBEEF)?, Some(2));
+        assert_eq!(table.get(&0x1234)?, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+/// Natural alignment of a value with the given size.
+/// Schema values do not carry their alignment, hence we assume
+/// that values are aligned to their size (max. 8 bytes).
+pub(crate) fn natural_alignment(size: u64) -> u64 {
+    if size >= 8 {
+        8
+    } else {
+        size.next_power_of_two()
+    }
+}
+
+pub(crate) fn align_up(offset: u64, alignment: u64) -> u64 {
+    offset.div_ceil(alignment) * alignment
+}
+
+/// Upper limit when following linked entries within the target process
+/// to prevent endless loops on corrupted memory.
+pub(crate) const MAX_LINKED_ENTRIES: usize = 0x100000;
@@ -9,3 +9,24 @@ pub use memory::*;
 
 mod rbtree;
 pub use rbtree::*;
+
+mod tshash;
+pub use tshash::*;
+
+mod map;
+pub use map::*;
+
+mod hashtable;
+pub use hashtable::*;
+
+mod linked_list;
+pub use linked_list::*;
+
+mod symbol_table;
+pub use symbol_table::*;
+
+mod layout;
+pub(crate) use layout::*;
+
+#[cfg(test)]
+mod mock;
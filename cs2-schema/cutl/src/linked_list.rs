@@ -0,0 +1,221 @@
+use std::marker::PhantomData;
+
+use cs2_schema_declaration::{
+    MemoryHandle,
+    SchemaValue,
+};
+
+use crate::{
+    align_up,
+    natural_alignment,
+    CUtlMemory,
+};
+
+/// Invalid element index for linked lists indexed by u16
+pub const LINKED_LIST_INVALID_INDEX: u16 = 0xFFFF;
+
+/// Doubly linked list with its elements stored in a `CUtlMemory`.
+///
+/// struct CUtlLinkedList<T> {
+///     pub memory: CUtlMemory<CUtlLinkedListElement<T>>, // 0x00
+///     pub head: u16,                                    // 0x10
+///     pub tail: u16,                                    // 0x12
+///     pub first_free: u16,                              // 0x14
+///     pub element_count: u16,                           // 0x16
+///     pub total_elements: u16,                          // 0x18
+///     pub elements: *const CUtlLinkedListElement<T>,    // 0x20
+/// }
+pub struct CUtlLinkedList<T> {
+    memory: MemoryHandle,
+    _dummy: PhantomData<T>,
+}
+
+impl<T> CUtlLinkedList<T> {
+    pub fn head(&self) -> anyhow::Result<u16> {
+        self.memory.reference_schema(0x10)
+    }
+
+    pub fn tail(&self) -> anyhow::Result<u16> {
+        self.memory.reference_schema(0x12)
+    }
+
+    pub fn first_free(&self) -> anyhow::Result<u16> {
+        self.memory.reference_schema(0x14)
+    }
+
+    pub fn element_count(&self) -> anyhow::Result<u16> {
+        self.memory.reference_schema(0x16)
+    }
+
+    pub fn total_elements(&self) -> anyhow::Result<u16> {
+        self.memory.reference_schema(0x18)
+    }
+}
+
+impl<T: SchemaValue> CUtlLinkedList<T> {
+    pub fn memory(&self) -> anyhow::Result<CUtlMemory<CUtlLinkedListElement<T>>> {
+        self.memory.reference_schema(0x00)
+    }
+
+    /// Iterate over all elements from head to tail.
+    pub fn iter(&self) -> anyhow::Result<impl Iterator<Item = anyhow::Result<T>>> {
+        let element_count = self.element_count()? as usize;
+        let total_elements = self.total_elements()? as usize;
+        let mut elements = if total_elements > 0 {
+            self.memory()?
+                .buffer()?
+                .read_entries(total_elements)?
+                .into_iter()
+                .map(Some)
+                .collect::<Vec<_>>()
+        } else {
+            Vec::new()
+        };
+
+        let mut result = Vec::with_capacity(element_count);
+        let mut current = self.head()?;
+        while current != LINKED_LIST_INVALID_INDEX {
+            if result.len() >= element_count {
+                anyhow::bail!("linked list contains more elements then expected");
+            }
+
+            let element = elements
+                .get_mut(current as usize)
+                .and_then(Option::take)
+                .ok_or_else(|| anyhow::anyhow!("invalid or cyclic element index {}", current))?;
+
+            current = element.next()?;
+            result.push(element.element());
+        }
+
+        Ok(result.into_iter())
+    }
+}
+
+impl<T> SchemaValue for CUtlLinkedList<T> {
+    fn value_size() -> Option<u64> {
+        Some(0x28)
+    }
+
+    fn from_memory(memory: MemoryHandle) -> anyhow::Result<Self> {
+        Ok(Self {
+            memory,
+            _dummy: Default::default(),
+        })
+    }
+}
+
+/// struct CUtlLinkedListElement<T> {
+///     pub element: T,    // 0x00
+///     pub previous: u16, // aligned after element
+///     pub next: u16,     // previous + 0x02
+/// }
+pub struct CUtlLinkedListElement<T> {
+    memory: MemoryHandle,
+    _dummy: PhantomData<T>,
+}
+
+impl<T: SchemaValue> CUtlLinkedListElement<T> {
+    fn links_offset() -> u64 {
+        align_up(T::value_size().expect("T to have a size"), 0x02)
+    }
+
+    pub fn element(&self) -> anyhow::Result<T> {
+        self.memory.reference_schema(0x00)
+    }
+
+    pub fn previous(&self) -> anyhow::Result<u16> {
+        self.memory.reference_schema(Self::links_offset())
+    }
+
+    pub fn next(&self) -> anyhow::Result<u16> {
+        self.memory.reference_schema(Self::links_offset() + 0x02)
+    }
+}
+
+impl<T: SchemaValue> SchemaValue for CUtlLinkedListElement<T> {
+    fn value_size() -> Option<u64> {
+        let element_size = T::value_size()?;
+        Some(align_up(
+            Self::links_offset() + 0x04,
+            natural_alignment(element_size).max(0x02),
+        ))
+    }
+
+    fn from_memory(memory: MemoryHandle) -> anyhow::Result<Self> {
+        Ok(Self {
+            memory,
+            _dummy: Default::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cs2_schema_declaration::MemoryHandle;
+
+    use super::{
+        CUtlLinkedList,
+        LINKED_LIST_INVALID_INDEX,
+    };
+    use crate::mock::MockMemory;
+
+    #[test]
+    fn test_iterate() -> anyhow::Result<()> {
+        let mut memory = MockMemory::new(0x10000);
+        let list = memory.alloc(0x28);
+
+        /* element layout: u32 value 0x00, previous 0x04, next 0x06 */
+        let elements = memory.alloc(0x08 * 3);
+        let mut write_element = |index: u64, value: u32, previous: u16, next: u16| {
+            let address = elements + index * 0x08;
+            memory.write_u32(address, value);
+            memory.write_u16(address + 0x04, previous);
+            memory.write_u16(address + 0x06, next);
+        };
+
+        /* order: 2 -> 0 -> 1 */
+        write_element(0, 100, 2, 1);
+        write_element(1, 200, 0, LINKED_LIST_INVALID_INDEX);
+        write_element(2, 300, LINKED_LIST_INVALID_INDEX, 0);
+
+        memory.write_u64(list, elements);
+        memory.write_u32(list + 0x08, 3);
+        memory.write_u16(list + 0x10, 2);
+        memory.write_u16(list + 0x12, 1);
+        memory.write_u16(list + 0x14, LINKED_LIST_INVALID_INDEX);
+        memory.write_u16(list + 0x16, 3);
+        memory.write_u16(list + 0x18, 3);
+
+        let driver = memory.into_driver();
+        let list = MemoryHandle::from_driver(&driver, list)
+            .reference_schema::<CUtlLinkedList<u32>>(0x00)?;
+
+        let values = list.iter()?.collect::<anyhow::Result<Vec<_>>>()?;
+        assert_eq!(values, vec![300, 100, 200]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cycle_detection() -> anyhow::Result<()> {
+        let mut memory = MockMemory::new(0x10000);
+        let list = memory.alloc(0x28);
+
+        let elements = memory.alloc(0x08 * 2);
+        memory.write_u16(elements + 0x06, 1);
+        memory.write_u16(elements + 0x08 + 0x06, 0);
+
+        memory.write_u64(list, elements);
+        memory.write_u32(list + 0x08, 2);
+        memory.write_u16(list + 0x10, 0);
+        memory.write_u16(list + 0x16, 2);
+        memory.write_u16(list + 0x18, 2);
+
+        let driver = memory.into_driver();
+        let list = MemoryHandle::from_driver(&driver, list)
+            .reference_schema::<CUtlLinkedList<u32>>(0x00)?;
+
+        assert!(list.iter().is_err());
+        Ok(())
+    }
+}
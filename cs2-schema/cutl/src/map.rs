@@ -0,0 +1,175 @@
+use std::marker::PhantomData;
+
+use anyhow::Context;
+use cs2_schema_declaration::{
+    MemoryHandle,
+    SchemaValue,
+};
+
+use crate::{
+    align_up,
+    natural_alignment,
+    CUtlRBTree,
+};
+
+/// Ordered map backed by a red black tree.
+///
+/// struct CUtlMap<K, V> {
+///     pub tree: CUtlRBTree<CUtlMapNode<K, V>>, // 0x00
+/// }
+pub struct CUtlMap<K, V> {
+    memory: MemoryHandle,
+    _dummy: PhantomData<(K, V)>,
+}
+
+impl<K: SchemaValue, V: SchemaValue> CUtlMap<K, V> {
+    pub fn tree(&self) -> anyhow::Result<CUtlRBTree<CUtlMapNode<K, V>>> {
+        self.memory.reference_schema(0x00)
+    }
+
+    pub fn element_count(&self) -> anyhow::Result<u16> {
+        self.tree()?.element_count()
+    }
+
+    /// Iterate over all entries ordered by their key.
+    pub fn iter(&self) -> anyhow::Result<impl Iterator<Item = anyhow::Result<CUtlMapNode<K, V>>>> {
+        Ok(self
+            .tree()?
+            .read_ordered_nodes()?
+            .into_iter()
+            .map(|node| node.value()))
+    }
+}
+
+impl<K: SchemaValue + PartialOrd, V: SchemaValue> CUtlMap<K, V> {
+    /// Find the entry with the given key.
+    /// The key order must match the maps less function.
+    pub fn find(&self, key: &K) -> anyhow::Result<Option<CUtlMapNode<K, V>>> {
+        self.tree()?
+            .find_by(|node| {
+                let node_key = node.key()?;
+                key.partial_cmp(&node_key)
+                    .context("map key is not comparable")
+            })?
+            .map(|node| node.value())
+            .transpose()
+    }
+
+    /// Read the value of the entry with the given key.
+    pub fn get(&self, key: &K) -> anyhow::Result<Option<V>> {
+        self.find(key)?.map(|node| node.value()).transpose()
+    }
+}
+
+impl<K: SchemaValue, V: SchemaValue> SchemaValue for CUtlMap<K, V> {
+    fn value_size() -> Option<u64> {
+        CUtlRBTree::<CUtlMapNode<K, V>>::value_size()
+    }
+
+    fn from_memory(memory: MemoryHandle) -> anyhow::Result<Self> {
+        Ok(Self {
+            memory,
+            _dummy: Default::default(),
+        })
+    }
+}
+
+/// struct CUtlMapNode<K, V> {
+///     pub key: K,   // 0x00
+///     pub value: V, // aligned after key
+/// }
+pub struct CUtlMapNode<K, V> {
+    memory: MemoryHandle,
+    _dummy: PhantomData<(K, V)>,
+}
+
+impl<K: SchemaValue, V: SchemaValue> CUtlMapNode<K, V> {
+    fn value_offset() -> u64 {
+        let key_size = K::value_size().expect("K to have a size");
+        let value_size = V::value_size().expect("V to have a size");
+        align_up(key_size, natural_alignment(value_size))
+    }
+
+    pub fn key(&self) -> anyhow::Result<K> {
+        self.memory.reference_schema(0x00)
+    }
+
+    pub fn value(&self) -> anyhow::Result<V> {
+        self.memory.reference_schema(Self::value_offset())
+    }
+}
+
+impl<K: SchemaValue, V: SchemaValue> SchemaValue for CUtlMapNode<K, V> {
+    fn value_size() -> Option<u64> {
+        let key_size = K::value_size()?;
+        let value_size = V::value_size()?;
+
+        let alignment = natural_alignment(key_size).max(natural_alignment(value_size));
+        Some(align_up(Self::value_offset() + value_size, alignment))
+    }
+
+    fn from_memory(memory: MemoryHandle) -> anyhow::Result<Self> {
+        Ok(Self {
+            memory,
+            _dummy: Default::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cs2_schema_declaration::MemoryHandle;
+
+    use super::CUtlMap;
+    use crate::{
+        mock::MockMemory,
+        RBTREE_INVALID_INDEX,
+    };
+
+    #[test]
+    fn test_ordered_iteration_and_find() -> anyhow::Result<()> {
+        let mut memory = MockMemory::new(0x10000);
+        let map = memory.alloc(0x30);
+
+        /* node size: 0x08 header + (u32 key, u64 value) = 0x18 */
+        let nodes = memory.alloc(0x18 * 4);
+        let mut write_node = |index: u64, left: u16, right: u16, key: u32, value: u64| {
+            let address = nodes + index * 0x18;
+            memory.write_u16(address, left);
+            memory.write_u16(address + 0x02, right);
+            memory.write_u32(address + 0x08, key);
+            memory.write_u64(address + 0x10, value);
+        };
+
+        /* tree: 1 is root, 0 left, 3 right, 2 is free */
+        write_node(0, RBTREE_INVALID_INDEX, RBTREE_INVALID_INDEX, 5, 50);
+        write_node(1, 0, 3, 10, 100);
+        write_node(2, 2, RBTREE_INVALID_INDEX, 0, 0);
+        write_node(3, RBTREE_INVALID_INDEX, RBTREE_INVALID_INDEX, 20, 200);
+
+        memory.write_u64(map + 0x08, nodes);
+        memory.write_u32(map + 0x10, 4);
+        memory.write_u16(map + 0x18, 1);
+        memory.write_u16(map + 0x1A, 3);
+        memory.write_u16(map + 0x1C, 2);
+        memory.write_u32(map + 0x20, 3);
+
+        let driver = memory.into_driver();
+        let map =
+            MemoryHandle::from_driver(&driver, map).reference_schema::<CUtlMap<u32, u64>>(0x00)?;
+
+        let entries = map
+            .iter()?
+            .map(|entry| {
+                let entry = entry?;
+                Ok((entry.key()?, entry.value()?))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        assert_eq!(entries, vec![(5, 50), (10, 100), (20, 200)]);
+
+        assert_eq!(map.get(&20)?, Some(200));
+        assert_eq!(map.get(&5)?, Some(50));
+        assert_eq!(map.get(&15)?, None);
+        Ok(())
+    }
+}
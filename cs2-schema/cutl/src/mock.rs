@@ -0,0 +1,82 @@
+use std::{
+    any::Any,
+    ffi::CStr,
+    sync::Arc,
+};
+
+use cs2_schema_declaration::MemoryDriver;
+
+/// Memory driver backed by a local buffer for testing container implementations.
+pub struct MockMemory {
+    base_address: u64,
+    data: Vec<u8>,
+}
+
+impl MockMemory {
+    pub fn new(base_address: u64) -> Self {
+        Self {
+            base_address,
+            data: Vec::new(),
+        }
+    }
+
+    /// Allocate zeroed memory and return its address.
+    pub fn alloc(&mut self, size: usize) -> u64 {
+        let offset = (self.data.len() + 0x0F) & !0x0F;
+        self.data.resize(offset + size, 0);
+        self.base_address + offset as u64
+    }
+
+    pub fn write(&mut self, address: u64, value: &[u8]) {
+        let offset = (address - self.base_address) as usize;
+        self.data[offset..offset + value.len()].copy_from_slice(value);
+    }
+
+    pub fn write_u16(&mut self, address: u64, value: u16) {
+        self.write(address, &value.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, address: u64, value: u32) {
+        self.write(address, &value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, address: u64, value: u64) {
+        self.write(address, &value.to_le_bytes());
+    }
+
+    pub fn into_driver(self) -> Arc<dyn MemoryDriver> {
+        Arc::new(self)
+    }
+}
+
+impl MemoryDriver for MockMemory {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn read_slice(&self, address: u64, slice: &mut [u8]) -> anyhow::Result<()> {
+        let offset = address
+            .checked_sub(self.base_address)
+            .ok_or_else(|| anyhow::anyhow!("invalid address 0x{:X}", address))?
+            as usize;
+
+        let data = self
+            .data
+            .get(offset..offset + slice.len())
+            .ok_or_else(|| anyhow::anyhow!("invalid address 0x{:X}", address))?;
+
+        slice.copy_from_slice(data);
+        Ok(())
+    }
+
+    fn read_cstring(
+        &self,
+        address: u64,
+        _expected_length: Option<usize>,
+        _max_length: Option<usize>,
+    ) -> anyhow::Result<String> {
+        let offset = (address - self.base_address) as usize;
+        let value = CStr::from_bytes_until_nul(&self.data[offset..])?;
+        Ok(value.to_str()?.to_string())
+    }
+}
@@ -1,4 +1,8 @@
-use std::marker::PhantomData;
+use std::{
+    cmp::Ordering,
+    marker::PhantomData,
+    mem,
+};
 
 use cs2_schema_declaration::{
     MemoryHandle,
@@ -6,6 +10,8 @@ use cs2_schema_declaration::{
     SchemaValue,
 };
 
+use crate::CUtlMemory;
+
 // UtlRBTree has the following layout:
 // pub struct UtlRBTree<T> {
 //    pub elements: Ptr<[UtlRBTreeNode<T>]> = 0x00,
@@ -96,3 +102,214 @@ impl<T: SchemaValue> SchemaValue for UtlRBTreeNode<T> {
         })
     }
 }
+
+/// Red black tree including its less function and bookkeeping (as used by `CUtlMap`).
+/// Attention: The schema system type scopes use a stripped down variant (see `UtlRBTree`).
+///
+/// struct CUtlRBTree<T> {
+///     pub less_func: *const (),                       // 0x00
+///     pub elements: CUtlMemory<UtlRBTreeNode<T>>,     // 0x08
+///     pub root: u16,                                  // 0x18
+///     pub element_count: u16,                         // 0x1A
+///     pub first_free: u16,                            // 0x1C
+///     pub last_alloc: i32,                            // 0x20
+///     pub elements_debug: *const UtlRBTreeNode<T>,    // 0x28
+/// }
+pub struct CUtlRBTree<T> {
+    memory: MemoryHandle,
+    _dummy: PhantomData<T>,
+}
+
+/// Invalid node index for trees indexed by u16
+pub const RBTREE_INVALID_INDEX: u16 = 0xFFFF;
+
+impl<T> CUtlRBTree<T> {
+    pub fn root(&self) -> anyhow::Result<u16> {
+        self.memory.reference_schema(0x18)
+    }
+
+    pub fn element_count(&self) -> anyhow::Result<u16> {
+        self.memory.reference_schema(0x1A)
+    }
+
+    pub fn first_free(&self) -> anyhow::Result<u16> {
+        self.memory.reference_schema(0x1C)
+    }
+
+    pub fn last_alloc(&self) -> anyhow::Result<i32> {
+        self.memory.reference_schema(0x20)
+    }
+}
+
+impl<T: SchemaValue> CUtlRBTree<T> {
+    pub fn elements(&self) -> anyhow::Result<CUtlMemory<UtlRBTreeNode<T>>> {
+        self.memory.reference_schema(0x08)
+    }
+
+    /// Read all allocated nodes (including free nodes).
+    /// The node index equals the index within the returned vector.
+    pub fn read_nodes(&self) -> anyhow::Result<Vec<UtlRBTreeNode<T>>> {
+        let node_count = (self.last_alloc()? + 1).max(0) as usize;
+        if node_count == 0 {
+            return Ok(Vec::new());
+        }
+
+        self.elements()?.buffer()?.read_entries(node_count)
+    }
+
+    /// Read all nodes in tree order.
+    pub fn read_ordered_nodes(&self) -> anyhow::Result<Vec<UtlRBTreeNode<T>>> {
+        let root = self.root()?;
+        let mut nodes = self.read_nodes()?.into_iter().map(Some).collect::<Vec<_>>();
+
+        /* nodes are marked when pushed, as a cycle might not pop a node before revisiting it */
+        let mut visited = vec![false; nodes.len()];
+
+        let mut result = Vec::with_capacity(self.element_count()? as usize);
+        let mut stack = Vec::new();
+        let mut current = root;
+        loop {
+            while current != RBTREE_INVALID_INDEX {
+                let visited = visited
+                    .get_mut(current as usize)
+                    .ok_or_else(|| anyhow::anyhow!("invalid node index {}", current))?;
+                if mem::replace(visited, true) {
+                    anyhow::bail!("cyclic node index {}", current);
+                }
+
+                let node = nodes[current as usize].as_ref().unwrap();
+
+                stack.push(current);
+                current = node.left_node()? as u16;
+            }
+
+            let Some(index) = stack.pop() else {
+                break;
+            };
+
+            let node = nodes[index as usize].take().unwrap();
+            current = node.right_node()? as u16;
+            result.push(node);
+        }
+
+        Ok(result)
+    }
+
+    /// Search the tree for a node.
+    /// The comparator should return the ordering of the target relative to the given value.
+    pub fn find_by(
+        &self,
+        mut comparator: impl FnMut(&T) -> anyhow::Result<Ordering>,
+    ) -> anyhow::Result<Option<UtlRBTreeNode<T>>> {
+        let nodes = self.elements()?.buffer()?;
+
+        let mut current = self.root()?;
+        let mut depth = 0usize;
+        while current != RBTREE_INVALID_INDEX {
+            depth += 1;
+            if depth > u16::MAX as usize {
+                anyhow::bail!("red black tree contains a cycle");
+            }
+
+            let node = nodes.read_element(current as usize)?;
+            current = match comparator(&node.value()?)? {
+                Ordering::Equal => return Ok(Some(node)),
+                Ordering::Less => node.left_node()? as u16,
+                Ordering::Greater => node.right_node()? as u16,
+            };
+        }
+
+        Ok(None)
+    }
+}
+
+impl<T: SchemaValue> SchemaValue for CUtlRBTree<T> {
+    fn value_size() -> Option<u64> {
+        Some(0x30)
+    }
+
+    fn from_memory(memory: MemoryHandle) -> anyhow::Result<Self> {
+        Ok(Self {
+            memory,
+            _dummy: Default::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cs2_schema_declaration::MemoryHandle;
+
+    use super::{
+        CUtlRBTree,
+        RBTREE_INVALID_INDEX,
+    };
+    use crate::mock::MockMemory;
+
+    /// Create a tree of u32 values with the given (left, right) node links and root 0
+    fn create_tree(links: &[(u16, u16)]) -> anyhow::Result<CUtlRBTree<u32>> {
+        let mut memory = MockMemory::new(0x1000);
+        let tree = memory.alloc(0x30);
+
+        /* node size: 0x08 header + u32 value = 0x0C */
+        let nodes = memory.alloc(0x0C * links.len());
+        for (index, (left, right)) in links.iter().enumerate() {
+            let address = nodes + index as u64 * 0x0C;
+            memory.write_u16(address, *left);
+            memory.write_u16(address + 0x02, *right);
+            memory.write_u32(address + 0x08, index as u32);
+        }
+
+        memory.write_u64(tree + 0x08, nodes);
+        memory.write_u32(tree + 0x10, links.len() as u32);
+        memory.write_u16(tree + 0x18, 0);
+        memory.write_u16(tree + 0x1A, links.len() as u16);
+        memory.write_u16(tree + 0x1C, RBTREE_INVALID_INDEX);
+        memory.write_u32(tree + 0x20, links.len() as u32 - 1);
+
+        let driver = memory.into_driver();
+        MemoryHandle::from_driver(&driver, tree).reference_schema(0x00)
+    }
+
+    #[test]
+    fn test_ordered_nodes() -> anyhow::Result<()> {
+        let tree = create_tree(&[
+            (1, 2),
+            (RBTREE_INVALID_INDEX, RBTREE_INVALID_INDEX),
+            (RBTREE_INVALID_INDEX, RBTREE_INVALID_INDEX),
+        ])?;
+
+        let values = tree
+            .read_ordered_nodes()?
+            .iter()
+            .map(|node| node.value())
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        assert_eq!(values, vec![1, 0, 2]);
+        Ok(())
+    }
+
+    fn assert_cyclic(tree: &CUtlRBTree<u32>) {
+        let error = tree.read_ordered_nodes().err().expect("a cycle error");
+        assert!(error.to_string().contains("cyclic"), "{}", error);
+    }
+
+    #[test]
+    fn test_ordered_nodes_cycle() -> anyhow::Result<()> {
+        /* left node points to itself */
+        let tree = create_tree(&[(0, RBTREE_INVALID_INDEX)])?;
+        assert_cyclic(&tree);
+
+        /* left nodes form a loop */
+        let tree = create_tree(&[(1, RBTREE_INVALID_INDEX), (0, RBTREE_INVALID_INDEX)])?;
+        assert_cyclic(&tree);
+
+        /* right node points back to the root */
+        let tree = create_tree(&[(RBTREE_INVALID_INDEX, 1), (RBTREE_INVALID_INDEX, 0)])?;
+        assert_cyclic(&tree);
+
+        /* invalid node index */
+        let tree = create_tree(&[(5, RBTREE_INVALID_INDEX)])?;
+        assert!(tree.read_ordered_nodes().is_err());
+        Ok(())
+    }
+}
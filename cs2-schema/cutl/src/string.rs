@@ -24,3 +24,63 @@ impl CUtlString {
         Ok(String::from_utf8(buffer)?)
     }
 }
+
+/// Seed used by the engine to hash string tokens
+pub const STRING_TOKEN_SEED: u32 = 0x31415926;
+
+impl CUtlStringToken {
+    /// Calculate the token hash for a string.
+    /// Tokens are case insensitive and hashed using MurmurHash2.
+    pub fn hash_string(value: &str) -> u32 {
+        const M: u32 = 0x5BD1E995;
+        const R: u32 = 24;
+
+        let data = value.to_ascii_lowercase().into_bytes();
+        let mut hash = STRING_TOKEN_SEED ^ data.len() as u32;
+
+        let mut chunks = data.chunks_exact(4);
+        for chunk in &mut chunks {
+            let mut k = u32::from_le_bytes(chunk.try_into().unwrap());
+            k = k.wrapping_mul(M);
+            k ^= k >> R;
+            k = k.wrapping_mul(M);
+
+            hash = hash.wrapping_mul(M);
+            hash ^= k;
+        }
+
+        let remainder = chunks.remainder();
+        if !remainder.is_empty() {
+            for (index, value) in remainder.iter().enumerate() {
+                hash ^= (*value as u32) << (index * 8);
+            }
+            hash = hash.wrapping_mul(M);
+        }
+
+        hash ^= hash >> 13;
+        hash = hash.wrapping_mul(M);
+        hash ^= hash >> 15;
+        hash
+    }
+
+    /// Check if the token has been created from the given string.
+    pub fn matches(&self, value: &str) -> anyhow::Result<bool> {
+        Ok(self.hash_code()? == Self::hash_string(value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CUtlStringToken;
+
+    #[test]
+    fn test_token_hash() {
+        assert_eq!(CUtlStringToken::hash_string(""), 0xB5D89F2F);
+        assert_eq!(CUtlStringToken::hash_string("abc"), 0xD6CF0D16);
+        assert_eq!(CUtlStringToken::hash_string("m_iHealth"), 0x5C0CCE6A);
+        assert_eq!(
+            CUtlStringToken::hash_string("CCSPlayerController"),
+            CUtlStringToken::hash_string("ccsplayercontroller")
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use cs2_schema_declaration::{
+    define_schema,
+    Ptr,
+};
+
+use crate::{
+    CUtlRBTree,
+    CUtlVector,
+};
+
+define_schema! {
+    pub struct CStringPoolIndex[0x04] {
+        pub pool: u16 = 0x00,
+        pub offset: u16 = 0x02,
+    }
+
+    pub struct CUtlStringPool[0x08] {
+        pub total_length: i32 = 0x00,
+        pub space_used: i32 = 0x04,
+        /* string data starts at 0x08 */
+    }
+
+    pub struct CUtlSymbolTable[0x48] {
+        pub lookup: CUtlRBTree<CStringPoolIndex> = 0x00,
+        pub insensitive: bool = 0x30,
+        pub string_pools: CUtlVector<Ptr<CUtlStringPool>> = 0x38,
+    }
+}
+
+/// Max length of a symbol string
+const SYMBOL_MAX_LENGTH: usize = 0x1000;
+
+impl CUtlSymbolTable {
+    /// Resolve the string of a symbol (the index of the lookup node).
+    pub fn symbol_string(&self, symbol: u16) -> anyhow::Result<String> {
+        let index = self
+            .lookup()?
+            .elements()?
+            .buffer()?
+            .read_element(symbol as usize)?
+            .value()?;
+
+        self.read_pool_string(&index)
+    }
+
+    fn read_pool_string(&self, index: &CStringPoolIndex) -> anyhow::Result<String> {
+        let pools = self.string_pools()?;
+        let pool_index = index.pool()? as usize;
+        if pool_index >= pools.element_count()?.max(0) as usize {
+            anyhow::bail!("invalid string pool {}", pool_index);
+        }
+
+        let pool = pools.read_element(pool_index)?;
+        let address = pool.address()? + 0x08 + index.offset()? as u64;
+        self.memory
+            .driver
+            .read_cstring(address, None, Some(SYMBOL_MAX_LENGTH))
+    }
+
+    /// Iterate over all symbols and their strings.
+    pub fn iter(&self) -> anyhow::Result<impl Iterator<Item = anyhow::Result<(u16, String)>> + '_> {
+        let nodes = self.lookup()?.read_nodes()?;
+        Ok(nodes
+            .into_iter()
+            .enumerate()
+            .filter_map(|(symbol, node)| match node.left_node() {
+                /* free nodes are linked to themself */
+                Ok(left) if left as u16 == symbol as u16 => None,
+                Ok(_) => Some(
+                    node.value()
+                        .and_then(|index| self.read_pool_string(&index))
+                        .map(|value| (symbol as u16, value)),
+                ),
+                Err(err) => Some(Err(err)),
+            }))
+    }
+
+    /// Find the symbol for a string.
+    /// Respects the case insensitivity of the symbol table.
+    pub fn find_symbol(&self, value: &str) -> anyhow::Result<Option<u16>> {
+        let insensitive = self.insensitive()?;
+        for entry in self.iter()? {
+            let (symbol, symbol_value) = entry?;
+            let matches = if insensitive {
+                symbol_value.eq_ignore_ascii_case(value)
+            } else {
+                symbol_value == value
+            };
+
+            if matches {
+                return Ok(Some(symbol));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cs2_schema_declaration::{
+        MemoryHandle,
+        SchemaValue,
+    };
+
+    use super::CUtlSymbolTable;
+    use crate::{
+        mock::MockMemory,
+        RBTREE_INVALID_INDEX,
+    };
+
+    #[test]
+    fn test_symbol_lookup() -> anyhow::Result<()> {
+        let mut memory = MockMemory::new(0x10000);
+        let table = memory.alloc(0x48);
+
+        let pool = memory.alloc(0x20);
+        memory.write(pool + 0x08, b"alpha\0Beta\0");
+
+        let pools = memory.alloc(0x08);
+        memory.write_u64(pools, pool);
+
+        /* node size: 0x08 header + CStringPoolIndex */
+        let nodes = memory.alloc(0x0C * 3);
+        memory.write_u16(nodes, RBTREE_INVALID_INDEX);
+        memory.write_u16(nodes + 0x08, 0);
+        memory.write_u16(nodes + 0x0A, 0);
+
+        memory.write_u16(nodes + 0x0C, 1);
+
+        memory.write_u16(nodes + 0x18, RBTREE_INVALID_INDEX);
+        memory.write_u16(nodes + 0x20, 0);
+        memory.write_u16(nodes + 0x22, 6);
+
+        memory.write_u64(table + 0x08, nodes);
+        memory.write_u32(table + 0x10, 3);
+        memory.write_u16(table + 0x18, 0);
+        memory.write_u16(table + 0x1A, 2);
+        memory.write_u32(table + 0x20, 2);
+        memory.write(table + 0x30, &[1]);
+        memory.write_u32(table + 0x38, 1);
+        memory.write_u64(table + 0x40, pools);
+
+        let driver = memory.into_driver();
+        let table = CUtlSymbolTable::from_memory(MemoryHandle::from_driver(&driver, table))?;
+
+        assert_eq!(table.symbol_string(2)?, "Beta");
+        assert_eq!(
+            table.iter()?.collect::<anyhow::Result<Vec<_>>>()?,
+            vec![(0, "alpha".to_string()), (2, "Beta".to_string())]
+        );
+
+        assert_eq!(table.find_symbol("BETA")?, Some(2));
+        assert_eq!(table.find_symbol("gamma")?, None);
+        Ok(())
+    }
+}
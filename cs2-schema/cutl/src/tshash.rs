@@ -0,0 +1,369 @@
+use std::marker::PhantomData;
+
+use cs2_schema_declaration::{
+    MemoryHandle,
+    Ptr,
+    SchemaValue,
+};
+
+use crate::MAX_LINKED_ENTRIES;
+
+const TSHASH_BUCKET_COUNT: usize = 256;
+const TSHASH_BUCKET_SIZE: u64 = 0x28;
+const TSHASH_BUCKETS_OFFSET: u64 = 0x80;
+
+/// Thread safe hash table used by the engine (e.g. for schema bindings).
+///
+/// struct CUtlTSHash<K, V> {
+///     pub entry_memory: CUtlMemoryPoolBase, // 0x0000
+///     pub buckets: [HashBucket<K, V>; 256], // 0x0080
+///     pub needs_commit: bool,               // 0x2880
+/// }
+///
+/// struct CUtlMemoryPoolBase {
+///     pub block_size: i32,       // 0x00
+///     pub blocks_per_blob: i32,  // 0x04
+///     pub grow_mode: i32,        // 0x08
+///     pub blocks_allocated: i32, // 0x0C
+///     pub peak_alloc: i32,       // 0x10
+///     ...
+/// }
+///
+/// struct HashBucket<K, V> {
+///     pub add_lock: CThreadSpinRWLock,                       // 0x00
+///     pub first: *const CUtlTSHashEntry<K, V>,               // 0x18
+///     pub first_uncommitted: *const CUtlTSHashEntry<K, V>,   // 0x20
+/// }
+pub struct CUtlTSHash<K, V> {
+    memory: MemoryHandle,
+    _dummy: PhantomData<(K, V)>,
+}
+
+impl<K, V> CUtlTSHash<K, V> {
+    pub fn block_size(&self) -> anyhow::Result<i32> {
+        self.memory.reference_schema(0x00)
+    }
+
+    pub fn blocks_allocated(&self) -> anyhow::Result<i32> {
+        self.memory.reference_schema(0x0C)
+    }
+
+    pub fn peak_alloc(&self) -> anyhow::Result<i32> {
+        self.memory.reference_schema(0x10)
+    }
+
+    pub fn bucket_count(&self) -> usize {
+        TSHASH_BUCKET_COUNT
+    }
+
+    pub fn bucket_first(&self, bucket: usize) -> anyhow::Result<Ptr<CUtlTSHashEntry<K, V>>> {
+        assert!(bucket < TSHASH_BUCKET_COUNT);
+        self.memory
+            .reference_schema(TSHASH_BUCKETS_OFFSET + bucket as u64 * TSHASH_BUCKET_SIZE + 0x18)
+    }
+
+    pub fn bucket_first_uncommitted(
+        &self,
+        bucket: usize,
+    ) -> anyhow::Result<Ptr<CUtlTSHashEntry<K, V>>> {
+        assert!(bucket < TSHASH_BUCKET_COUNT);
+        self.memory
+            .reference_schema(TSHASH_BUCKETS_OFFSET + bucket as u64 * TSHASH_BUCKET_SIZE + 0x20)
+    }
+
+    /// Iterate over all committed entries of all buckets.
+    /// Entries are yielded in bucket order.
+    pub fn iter(&self) -> anyhow::Result<CUtlTSHashIter<K, V>> {
+        /* read all bucket heads at once */
+        let mut buckets = self.memory.clone().with_offset(TSHASH_BUCKETS_OFFSET)?;
+        buckets.cache(TSHASH_BUCKET_COUNT * TSHASH_BUCKET_SIZE as usize)?;
+
+        Ok(CUtlTSHashIter {
+            buckets,
+            bucket_index: 0,
+            current: None,
+            visited: 0,
+        })
+    }
+}
+
+/// Key of a `CUtlTSHash` which determines the bucket of its entry.
+pub trait TSHashKey: SchemaValue + PartialEq {
+    /// Engine hash of the key (CUtlTSHashGenericHash)
+    fn bucket_hash(&self) -> u32;
+}
+
+/// HashIntConventional of the engine.
+/// The key will be truncated to 32 bits before hashing.
+fn hash_int_conventional(value: u32) -> u32 {
+    let mut hash = 0xAAAAAAAAu32.wrapping_add(value & 0xFF);
+    hash = (hash << 5)
+        .wrapping_add(hash)
+        .wrapping_add((value >> 8) & 0xFF);
+    hash = (hash << 5)
+        .wrapping_add(hash)
+        .wrapping_add((value >> 16) & 0xFF);
+    hash = (hash << 5)
+        .wrapping_add(hash)
+        .wrapping_add((value >> 24) & 0xFF);
+    hash
+}
+
+macro_rules! impl_tshash_key {
+    ($($type:ty),*) => {
+        $(
+            impl TSHashKey for $type {
+                fn bucket_hash(&self) -> u32 {
+                    let hash = hash_int_conventional(*self as u32);
+                    /* the bucket count does not exceed USHRT_MAX */
+                    hash ^ (hash >> 16)
+                }
+            }
+        )*
+    };
+}
+
+impl_tshash_key!(u32, i32, u64, i64);
+
+impl<K: TSHashKey, V: SchemaValue> CUtlTSHash<K, V> {
+    /// Index of the bucket which contains the key
+    pub fn bucket_index(&self, key: &K) -> usize {
+        key.bucket_hash() as usize & (TSHASH_BUCKET_COUNT - 1)
+    }
+
+    /// Find the committed entry with the given key.
+    /// Only the bucket of the key will be searched.
+    pub fn find(&self, key: &K) -> anyhow::Result<Option<CUtlTSHashEntry<K, V>>> {
+        let mut current = self.bucket_first(self.bucket_index(key))?;
+        for _ in 0..MAX_LINKED_ENTRIES {
+            let Some(entry) = current.try_reference_schema()? else {
+                return Ok(None);
+            };
+
+            if entry.key()? == *key {
+                return Ok(Some(entry));
+            }
+
+            current = entry.next()?;
+        }
+
+        anyhow::bail!("hash bucket exceeds the max entry count")
+    }
+
+    /// Read the value of the entry with the given key.
+    pub fn get(&self, key: &K) -> anyhow::Result<Option<V>> {
+        self.find(key)?.map(|entry| entry.value()).transpose()
+    }
+}
+
+impl<K, V> SchemaValue for CUtlTSHash<K, V> {
+    fn value_size() -> Option<u64> {
+        Some(TSHASH_BUCKETS_OFFSET + TSHASH_BUCKET_COUNT as u64 * TSHASH_BUCKET_SIZE + 0x10)
+    }
+
+    fn from_memory(memory: MemoryHandle) -> anyhow::Result<Self> {
+        Ok(Self {
+            memory,
+            _dummy: Default::default(),
+        })
+    }
+}
+
+/// struct CUtlTSHashEntry<K, V> {
+///     pub key: K,                              // 0x00
+///     pub next: *const CUtlTSHashEntry<K, V>,  // 0x08
+///     pub value: V,                            // 0x10
+/// }
+pub struct CUtlTSHashEntry<K, V> {
+    memory: MemoryHandle,
+    _dummy: PhantomData<(K, V)>,
+}
+
+impl<K, V> CUtlTSHashEntry<K, V> {
+    pub fn next(&self) -> anyhow::Result<Ptr<CUtlTSHashEntry<K, V>>> {
+        self.memory.reference_schema(0x08)
+    }
+}
+
+impl<K: SchemaValue, V: SchemaValue> CUtlTSHashEntry<K, V> {
+    pub fn key(&self) -> anyhow::Result<K> {
+        self.memory.reference_schema(0x00)
+    }
+
+    pub fn value(&self) -> anyhow::Result<V> {
+        self.memory.reference_schema(0x10)
+    }
+}
+
+impl<K, V> SchemaValue for CUtlTSHashEntry<K, V> {
+    fn value_size() -> Option<u64> {
+        None
+    }
+
+    fn from_memory(memory: MemoryHandle) -> anyhow::Result<Self> {
+        Ok(Self {
+            memory,
+            _dummy: Default::default(),
+        })
+    }
+}
+
+pub struct CUtlTSHashIter<K, V> {
+    buckets: MemoryHandle,
+    bucket_index: usize,
+    current: Option<Ptr<CUtlTSHashEntry<K, V>>>,
+    visited: usize,
+}
+
+impl<K, V> CUtlTSHashIter<K, V> {
+    fn next_entry(&mut self) -> anyhow::Result<Option<CUtlTSHashEntry<K, V>>> {
+        loop {
+            if let Some(current) = self.current.take() {
+                if let Some(entry) = current.try_reference_schema()? {
+                    self.visited += 1;
+                    if self.visited > MAX_LINKED_ENTRIES {
+                        anyhow::bail!("hash bucket exceeds the max entry count")
+                    }
+
+                    self.current = Some(entry.next()?);
+                    return Ok(Some(entry));
+                }
+            }
+
+            if self.bucket_index >= TSHASH_BUCKET_COUNT {
+                return Ok(None);
+            }
+
+            self.current = Some(
+                self.buckets
+                    .reference_schema(self.bucket_index as u64 * TSHASH_BUCKET_SIZE + 0x18)?,
+            );
+            self.bucket_index += 1;
+        }
+    }
+}
+
+impl<K, V> Iterator for CUtlTSHashIter<K, V> {
+    type Item = anyhow::Result<CUtlTSHashEntry<K, V>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_entry() {
+            Ok(entry) => entry.map(Ok),
+            Err(err) => {
+                /* stop iterating after the first error */
+                self.bucket_index = TSHASH_BUCKET_COUNT;
+                self.current = None;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cs2_schema_declaration::MemoryHandle;
+
+    use super::{
+        CUtlTSHash,
+        TSHashKey,
+        TSHASH_BUCKETS_OFFSET,
+        TSHASH_BUCKET_SIZE,
+    };
+    use crate::mock::MockMemory;
+
+    fn alloc_entry(memory: &mut MockMemory, key: u64, next: u64, value: u32) -> u64 {
+        let address = memory.alloc(0x18);
+        memory.write_u64(address, key);
+        memory.write_u64(address + 0x08, next);
+        memory.write_u32(address + 0x10, value);
+        address
+    }
+
+    #[test]
+    fn test_iterate_and_find() -> anyhow::Result<()> {
+        let mut memory = MockMemory::new(0x10000);
+        let hash = memory.alloc(0x2890);
+
+        /* entries are placed in the bucket of their key */
+        let bucket_of = |key: u64| (key.bucket_hash() & 0xFF) as u64;
+        assert_ne!(bucket_of(1), bucket_of(2));
+        assert_ne!(bucket_of(1), bucket_of(3));
+
+        let entry_c = alloc_entry(&mut memory, 3, 0, 30);
+        let entry_a = alloc_entry(&mut memory, 1, 0, 10);
+        let entry_b = alloc_entry(&mut memory, 2, 0, 20);
+        memory.write_u64(
+            hash + TSHASH_BUCKETS_OFFSET + bucket_of(1) * TSHASH_BUCKET_SIZE + 0x18,
+            entry_a,
+        );
+        memory.write_u64(
+            hash + TSHASH_BUCKETS_OFFSET + bucket_of(2) * TSHASH_BUCKET_SIZE + 0x18,
+            entry_b,
+        );
+        memory.write_u64(
+            hash + TSHASH_BUCKETS_OFFSET + bucket_of(3) * TSHASH_BUCKET_SIZE + 0x18,
+            entry_c,
+        );
+
+        let driver = memory.into_driver();
+        let hash = MemoryHandle::from_driver(&driver, hash)
+            .reference_schema::<CUtlTSHash<u64, u32>>(0x00)?;
+
+        let mut entries = hash
+            .iter()?
+            .map(|entry| {
+                let entry = entry?;
+                Ok((entry.key()?, entry.value()?))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        entries.sort();
+        assert_eq!(entries, vec![(1, 10), (2, 20), (3, 30)]);
+
+        assert_eq!(hash.get(&1)?, Some(10));
+        assert_eq!(hash.get(&3)?, Some(30));
+        assert_eq!(hash.get(&4)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_only_searches_key_bucket() -> anyhow::Result<()> {
+        let mut memory = MockMemory::new(0x10000);
+        let hash = memory.alloc(0x2890);
+
+        /* key 5 within the wrong bucket must not be found */
+        let wrong_bucket = (5u64.bucket_hash() as u64 + 1) & 0xFF;
+        let entry_a = alloc_entry(&mut memory, 5, 0, 50);
+        memory.write_u64(
+            hash + TSHASH_BUCKETS_OFFSET + wrong_bucket * TSHASH_BUCKET_SIZE + 0x18,
+            entry_a,
+        );
+
+        /* colliding keys are chained within the bucket */
+        let bucket = (7u64.bucket_hash() & 0xFF) as u64;
+        let entry_c = alloc_entry(&mut memory, 7, 0, 70);
+        let entry_b = alloc_entry(&mut memory, 0x1234_0000_0000_0007, entry_c, 71);
+        memory.write_u64(
+            hash + TSHASH_BUCKETS_OFFSET + bucket * TSHASH_BUCKET_SIZE + 0x18,
+            entry_b,
+        );
+
+        let driver = memory.into_driver();
+        let hash = MemoryHandle::from_driver(&driver, hash)
+            .reference_schema::<CUtlTSHash<u64, u32>>(0x00)?;
+
+        assert_eq!(hash.iter()?.count(), 3);
+        assert_eq!(hash.get(&5)?, None);
+        assert_eq!(hash.get(&7)?, Some(70));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bucket_hash() {
+        /* HashIntConventional(0) = 0xAAAAAAAA * 33^3 */
+        let expected = 0xAAAAAAAAu32.wrapping_mul(33 * 33 * 33);
+        assert_eq!(0u32.bucket_hash(), expected ^ (expected >> 16));
+
+        /* only the lower 32 bits are hashed */
+        assert_eq!(0x1234_0000_0007u64.bucket_hash(), 7u32.bucket_hash());
+    }
+}
@@ -0,0 +1,166 @@
+use std::{
+    marker::PhantomData,
+    sync::Arc,
+};
+
+use anyhow::Context;
+
+use crate::{
+    MemoryCached,
+    MemoryDriver,
+    MemoryHandle,
+    SchemaValue,
+};
+
+/// A single read within a batched read
+pub struct BatchReadEntry<'a> {
+    pub address: u64,
+    pub buffer: &'a mut [u8],
+
+    /// Will be set by the driver when the read succeeded
+    pub success: bool,
+}
+
+/// Reference to a planned read within a `MemoryReadBatch`
+pub struct BatchSlot<T: ?Sized> {
+    index: usize,
+    element_count: usize,
+    _data: PhantomData<T>,
+}
+
+/// Collects multiple memory reads and executes them with one driver call.
+///
+/// Every planned read returns a `BatchSlot` which can be used to access
+/// the read value once the batch has been executed.
+pub struct MemoryReadBatch {
+    driver: Arc<dyn MemoryDriver>,
+    reads: Vec<(u64, usize)>,
+}
+
+impl MemoryReadBatch {
+    pub fn new(driver: &Arc<dyn MemoryDriver>) -> Self {
+        Self {
+            driver: driver.clone(),
+            reads: Default::default(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.reads.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reads.is_empty()
+    }
+
+    /// Plan reading the whole schema value at the target address.
+    pub fn plan_schema<T: SchemaValue>(&mut self, address: u64) -> anyhow::Result<BatchSlot<T>> {
+        let size = T::value_size().context("could not batch read a dynamic sized schema")?;
+
+        self.reads.push((address, size as usize));
+        Ok(BatchSlot {
+            index: self.reads.len() - 1,
+            element_count: 1,
+            _data: Default::default(),
+        })
+    }
+
+    /// Plan reading `count` consecutive schema values at the target address.
+    pub fn plan_entries<T: SchemaValue>(
+        &mut self,
+        address: u64,
+        count: usize,
+    ) -> anyhow::Result<BatchSlot<[T]>> {
+        let size = T::value_size().context("could not batch read a dynamic sized schema")?;
+
+        self.reads.push((address, size as usize * count));
+        Ok(BatchSlot {
+            index: self.reads.len() - 1,
+            element_count: count,
+            _data: Default::default(),
+        })
+    }
+
+    pub fn execute(self) -> anyhow::Result<MemoryReadBatchResult> {
+        let mut buffers = self
+            .reads
+            .iter()
+            .map(|(_, length)| vec![0u8; *length])
+            .collect::<Vec<_>>();
+
+        let mut entries = self
+            .reads
+            .iter()
+            .zip(buffers.iter_mut())
+            .map(|((address, _), buffer)| BatchReadEntry {
+                address: *address,
+                buffer,
+                success: false,
+            })
+            .collect::<Vec<_>>();
+
+        self.driver.read_batch(&mut entries)?;
+        let read_success = entries
+            .into_iter()
+            .map(|entry| entry.success)
+            .collect::<Vec<_>>();
+
+        let reads = self
+            .reads
+            .iter()
+            .zip(buffers)
+            .zip(read_success)
+            .map(|(((address, _), buffer), success)| {
+                success.then(|| {
+                    Arc::new(MemoryCached {
+                        address: *address,
+                        buffer,
+                    })
+                })
+            })
+            .collect();
+
+        Ok(MemoryReadBatchResult {
+            driver: self.driver,
+            reads,
+        })
+    }
+}
+
+/// Memory read by a `MemoryReadBatch`
+pub struct MemoryReadBatchResult {
+    driver: Arc<dyn MemoryDriver>,
+    reads: Vec<Option<Arc<MemoryCached>>>,
+}
+
+impl MemoryReadBatchResult {
+    fn memory<T: ?Sized>(&self, slot: &BatchSlot<T>) -> anyhow::Result<MemoryHandle> {
+        let cache = self
+            .reads
+            .get(slot.index)
+            .context("batch slot does not belong to this batch")?
+            .as_ref()
+            .context("batch read failed")?;
+
+        Ok(MemoryHandle::from_cache(&self.driver, cache.clone()))
+    }
+
+    pub fn read_schema<T: SchemaValue>(&self, slot: &BatchSlot<T>) -> anyhow::Result<T> {
+        T::from_memory(self.memory(slot)?)
+    }
+
+    pub fn read_entries<T: SchemaValue>(&self, slot: &BatchSlot<[T]>) -> anyhow::Result<Vec<T>> {
+        let memory = self.memory(slot)?;
+        let element_size =
+            T::value_size().context("could not read an array entry for a dynamic sized schema")?;
+
+        let mut result = Vec::with_capacity(slot.element_count);
+        for index in 0..slot.element_count {
+            result.push(T::from_memory(
+                memory.clone().with_offset(index as u64 * element_size)?,
+            )?);
+        }
+
+        Ok(result)
+    }
+}
@@ -7,6 +7,9 @@ pub use memory::*;
 mod ptr;
 pub use ptr::*;
 
+mod batch;
+pub use batch::*;
+
 mod basics;
 
 mod offset;
@@ -3,7 +3,12 @@ use std::{
     sync::Arc,
 };
 
-use crate::SchemaValue;
+use crate::{
+    BatchReadEntry,
+    BatchSlot,
+    MemoryReadBatch,
+    SchemaValue,
+};
 
 pub trait MemoryDriver: Any + Send + Sync {
     fn as_any(&self) -> &dyn Any;
@@ -16,12 +21,22 @@ pub trait MemoryDriver: Any + Send + Sync {
         max_length: Option<usize>,
     ) -> anyhow::Result<String>;
 
+    /// Execute multiple reads at once.
+    /// Drivers which are unable to batch reads, will execute every read on its own.
+    fn read_batch(&self, entries: &mut [BatchReadEntry]) -> anyhow::Result<()> {
+        for entry in entries.iter_mut() {
+            entry.success = self.read_slice(entry.address, entry.buffer).is_ok();
+        }
+
+        Ok(())
+    }
+
     /* fn write_slice(&self, address: u64, slice: &[u8]) -> anyhow::Result<()>; */
 }
 
 pub struct MemoryCached {
-    address: u64,
-    buffer: Vec<u8>,
+    pub(crate) address: u64,
+    pub(crate) buffer: Vec<u8>,
 }
 
 #[derive(Clone)]
@@ -42,6 +57,21 @@ impl MemoryHandle {
         }
     }
 
+    pub(crate) fn from_cache(driver: &Arc<dyn MemoryDriver>, cache: Arc<MemoryCached>) -> Self {
+        Self {
+            driver: driver.clone(),
+            address: cache.address,
+
+            cache: Some(cache),
+        }
+    }
+
+    /// Create a handle which serves all reads within the buffer from the buffer itself.
+    /// Reads outside of the buffer will fail.
+    pub fn from_buffer(driver: &Arc<dyn MemoryDriver>, address: u64, buffer: Vec<u8>) -> Self {
+        Self::from_cache(driver, Arc::new(MemoryCached { address, buffer }))
+    }
+
     pub fn with_offset(self, offset: u64) -> anyhow::Result<Self> {
         Ok(Self {
             driver: self.driver,
@@ -94,4 +124,13 @@ impl MemoryHandle {
     pub fn reference_schema<T: SchemaValue>(&self, offset: u64) -> anyhow::Result<T> {
         T::from_memory(self.clone().with_offset(offset)?)
     }
+
+    /// Plan reading the schema value at the target offset within a batch.
+    pub fn plan_schema<T: SchemaValue>(
+        &self,
+        batch: &mut MemoryReadBatch,
+        offset: u64,
+    ) -> anyhow::Result<BatchSlot<T>> {
+        batch.plan_schema(self.address + offset)
+    }
 }
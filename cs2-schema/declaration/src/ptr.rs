@@ -8,8 +8,10 @@ use std::{
 use anyhow::Context;
 
 use crate::{
+    BatchSlot,
     MemoryDriver,
     MemoryHandle,
+    MemoryReadBatch,
     SchemaValue,
 };
 
@@ -114,6 +116,12 @@ impl<T: SchemaValue> Ptr<T> {
             Ok(None)
         }
     }
+
+    /// Plan reading the whole schema within a batch.
+    /// The value can be read from the batch result once executed.
+    pub fn plan_read_schema(&self, batch: &mut MemoryReadBatch) -> anyhow::Result<BatchSlot<T>> {
+        batch.plan_schema(self.address()?)
+    }
 }
 
 /// Unbound array implementation
@@ -153,6 +161,16 @@ impl<T: SchemaValue> Ptr<[T]> {
 
         Ok(result)
     }
+
+    /// Plan reading `length` entries within a batch.
+    /// The entries can be read from the batch result once executed.
+    pub fn plan_read_entries(
+        &self,
+        batch: &mut MemoryReadBatch,
+        length: usize,
+    ) -> anyhow::Result<BatchSlot<[T]>> {
+        batch.plan_entries(self.address()?, length)
+    }
 }
 
 pub type PtrCStr = Ptr<*const i8>;
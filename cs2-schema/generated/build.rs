@@ -1,6 +1,9 @@
 use std::{
     env,
-    fs::File,
+    fs::{
+        self,
+        File,
+    },
     io::{
         BufWriter,
         Write,
@@ -11,15 +14,26 @@ use std::{
 use anyhow::Context;
 
 #[path = "src/definition.rs"]
+#[allow(dead_code)]
 mod schema;
 
 fn main() -> anyhow::Result<()> {
+    println!("cargo:rerun-if-changed=cs2_schema.json");
+    println!("cargo:rerun-if-changed=cs2_schema.rs");
+
+    let out_dir = env::var_os("OUT_DIR").unwrap();
+    let dest_path = Path::new(&out_dir).join("cs2_schema.rs");
+
+    if !Path::new("./cs2_schema.json").exists() && Path::new("./cs2_schema.rs").exists() {
+        /* pinned schema created by the schema dump (rust format) */
+        fs::copy("./cs2_schema.rs", &dest_path).context("failed to copy pinned cs2_schema.rs")?;
+        return Ok(());
+    }
+
     let mut schema = File::open("./cs2_schema.json").context("failed to open cs2_schema.json")?;
     let schema_scopes = serde_json::from_reader::<_, Vec<schema::SchemaScope>>(&mut schema)
         .context("failed to parse schema")?;
 
-    let out_dir = env::var_os("OUT_DIR").unwrap();
-    let dest_path = Path::new(&out_dir).join("cs2_schema.rs");
     let output = File::options()
         .create(true)
         .truncate(true)
@@ -27,12 +41,39 @@ fn main() -> anyhow::Result<()> {
         .open(dest_path)
         .context("failed to create cs2_schema.rs")?;
 
+    let types = schema::SchemaTypeIndex::from_scopes(&schema_scopes);
+    let mut unmapped = Vec::new();
+
     let mut writer = BufWriter::new(output);
     writeln!(&mut writer, "/* Autogenerated schema offsets */")?;
-    for scope in schema_scopes {
-        scope.emit_rust_definition(&mut writer)?;
+    for scope in schema_scopes.iter() {
+        scope.emit_rust_definition(
+            &mut writer,
+            &types,
+            schema::RustOffsetMode::Runtime,
+            &mut unmapped,
+        )?;
+    }
+
+    let report_path = Path::new(&out_dir).join("cs2_schema_unmapped.txt");
+    let mut report = BufWriter::new(
+        File::create(&report_path).context("failed to create cs2_schema_unmapped.txt")?,
+    );
+    for field in unmapped.iter() {
+        writeln!(
+            &mut report,
+            "{}::{}: {}",
+            field.class_name, field.field_name, field.field_ctype
+        )?;
+    }
+
+    if !unmapped.is_empty() {
+        println!(
+            "cargo:warning={} schema fields could not be mapped (see {})",
+            unmapped.len(),
+            report_path.display()
+        );
     }
 
-    println!("cargo:rerun-if-changed=cs2_schema.json");
     Ok(())
 }
@@ -1,8 +1,11 @@
-use std::io::{
-    self,
-    Error,
-    Result,
-    Write,
+use std::{
+    collections::HashMap,
+    io::{
+        self,
+        Error,
+        Result,
+        Write,
+    },
 };
 
 use serde::{
@@ -33,8 +36,173 @@ pub fn mod_name_from_schema_name(name: &str) -> &str {
     }
 }
 
+/// Index of all declared classes and enums across all schema scopes.
+/// Used to resolve engine type names into their generated rust types.
+///
+/// Types declared within multiple scopes resolve to the declaration of the current scope.
+/// Otherwise the first declaration wins.
+#[derive(Debug, Default)]
+pub struct SchemaTypeIndex {
+    /// Rust type by the declaring module and the engine type name
+    scoped_types: HashMap<(String, String), String>,
+
+    /// Rust type of the first declaration by the engine type name
+    types: HashMap<String, String>,
+}
+
+impl SchemaTypeIndex {
+    pub fn from_scopes(scopes: &[SchemaScope]) -> Self {
+        let mut index = Self::default();
+        for scope in scopes {
+            let mod_name = mod_name_from_schema_name(&scope.schema_name);
+            for class in scope.classes.iter() {
+                index.insert(mod_name, &class.class_name);
+            }
+
+            for definition in scope.enums.iter() {
+                index.insert(mod_name, &definition.enum_name);
+            }
+        }
+
+        index
+    }
+
+    fn insert(&mut self, mod_name: &str, name: &str) {
+        let rust_type = format!("{}::{}", mod_name, name.replace(":", "_"));
+        self.types
+            .entry(name.to_string())
+            .or_insert_with(|| rust_type.clone());
+        self.scoped_types
+            .entry((mod_name.to_string(), name.to_string()))
+            .or_insert(rust_type);
+    }
+
+    fn lookup(&self, scope: Option<&str>, name: &str) -> Option<String> {
+        scope
+            .and_then(|scope| {
+                self.scoped_types
+                    .get(&(scope.to_string(), name.to_string()))
+            })
+            .or_else(|| self.types.get(name))
+            .cloned()
+    }
+
+    /// Map an engine type name into the rust type.
+    pub fn map_type(&self, ctype: &str) -> Option<String> {
+        self.map_scoped_type(None, ctype)
+    }
+
+    /// Map an engine type name used within the given scope (module name) into the rust type.
+    /// Declarations of that scope are preferred.
+    pub fn map_scoped_type(&self, scope: Option<&str>, ctype: &str) -> Option<String> {
+        let ctype = ctype.trim();
+        if let Some(bits) = ctype.strip_prefix("bitfield:") {
+            /* bitfields will be represented by their underlying storage */
+            let bits = bits.trim().parse::<u32>().ok()?;
+            return match bits {
+                1..=8 => Some("u8".to_string()),
+                9..=16 => Some("u16".to_string()),
+                17..=32 => Some("u32".to_string()),
+                33..=64 => Some("u64".to_string()),
+                _ => None,
+            };
+        }
+
+        if let Some(base_type) = ctype.strip_suffix('*') {
+            return self
+                .map_scoped_type(scope, base_type)
+                .map(|base_type| format!("Ptr<{}>", base_type));
+        }
+
+        if let Some(array_type) = ctype.strip_suffix(']') {
+            /* Multidimensional arrays are declared as T[A][B] which equals [[T; B]; A] */
+            let (base_type, length) = array_type.split_once('[')?;
+            let (length, inner_dimensions) = match length.split_once(']') {
+                Some((length, inner_dimensions)) => (length, format!("{}]", inner_dimensions)),
+                None => (length, String::new()),
+            };
+            let length = length.trim().parse::<usize>().ok()?;
+            let element_type =
+                self.map_scoped_type(scope, &format!("{}{}", base_type, inner_dimensions))?;
+            return Some(format!("[{}; 0x{:X}]", element_type, length));
+        }
+
+        if let Some((template, inner_type)) = ctype
+            .strip_suffix('>')
+            .and_then(|ctype| ctype.split_once('<'))
+        {
+            let inner_type = self.map_scoped_type(scope, inner_type)?;
+            return match template.trim() {
+                "CHandle" => Some(format!("EntityHandle<{}>", inner_type)),
+                "CUtlVector" | "CNetworkUtlVectorBase" | "C_NetworkUtlVectorBase" => {
+                    Some(format!("CUtlVector<{}>", inner_type))
+                }
+                _ => None,
+            };
+        }
+
+        let rust_type = match ctype {
+            "bool" => "bool",
+            "char" => "u8",
+
+            "int8" => "i8",
+            "uint8" => "u8",
+
+            "int16" => "i16",
+            "uint16" => "u16",
+
+            "int32" => "i32",
+            "uint32" => "u32",
+
+            "int64" => "i64",
+            "uint64" => "u64",
+
+            "float32" => "f32",
+            "float64" => "f64",
+
+            "CEntityIndex" => "CEntityIndex",
+            "CUtlStringToken" => "CUtlStringToken",
+            "CUtlSymbolLarge" => "PtrCStr",
+            "CUtlString" => "CUtlString",
+            "Vector" => "[f32; 0x03]",
+            "QAngle" => "[f32; 0x03]",
+            "Color" => "Color",
+
+            ctype => return self.lookup(scope, ctype),
+        };
+
+        Some(rust_type.to_string())
+    }
+}
+
+/// How the emitted rust definitions resolve their field offsets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RustOffsetMode {
+    /// Offsets are resolved from the games schema system at runtime
+    Runtime,
+
+    /// Offsets are fixed to the values of the schema dump
+    Fixed,
+}
+
+/// A class field which could not be mapped to a rust type
+#[derive(Debug, Clone)]
+pub struct UnmappedField {
+    pub class_name: String,
+    pub field_name: String,
+    pub field_ctype: String,
+}
+
 impl SchemaScope {
-    pub fn emit_rust_definition(&self, output: &mut dyn std::io::Write) -> Result<()> {
+    /// Emit the rust definitions for all classes and enums.
+    /// Fields which could not be mapped will be added to `unmapped`.
+    pub fn emit_rust_definition(
+        &self,
+        output: &mut dyn std::io::Write,
+        types: &SchemaTypeIndex,
+        offset_mode: RustOffsetMode,
+        unmapped: &mut Vec<UnmappedField>,
+    ) -> Result<()> {
         let mod_name = mod_name_from_schema_name(&self.schema_name);
         writeln!(output, "")?;
         writeln!(output, "/* {} ({}) */", mod_name, self.schema_name)?;
@@ -49,9 +217,9 @@ impl SchemaScope {
             .iter()
             .try_for_each(|definition| definition.emit(output))?;
 
-        self.classes
-            .iter()
-            .try_for_each(|definition| definition.emit(&self.schema_name, output))?;
+        self.classes.iter().try_for_each(|definition| {
+            definition.emit(&self.schema_name, output, types, offset_mode, unmapped)
+        })?;
 
         writeln!(output, "}}")?;
         writeln!(output, "/* {} */", mod_name)?;
@@ -140,7 +308,14 @@ pub struct ClassDefinition {
 }
 
 impl ClassDefinition {
-    fn emit(&self, mod_name: &str, output: &mut dyn std::io::Write) -> Result<()> {
+    fn emit(
+        &self,
+        mod_name: &str,
+        output: &mut dyn std::io::Write,
+        types: &SchemaTypeIndex,
+        offset_mode: RustOffsetMode,
+        unmapped: &mut Vec<UnmappedField>,
+    ) -> Result<()> {
         let class_name = self.class_name.replace(":", "_");
 
         writeln!(output, "  /* class {} ({}) */", class_name, self.class_name)?;
@@ -160,9 +335,16 @@ impl ClassDefinition {
         }
 
         writeln!(output, "      pub vtable: Ptr<()> = 0x00,")?; // Every schema class has a vtable
-        self.offsets
-            .iter()
-            .try_for_each(|offset| offset.emit(mod_name, &self.class_name, output))?;
+        self.offsets.iter().try_for_each(|offset| {
+            offset.emit(
+                mod_name,
+                &self.class_name,
+                output,
+                types,
+                offset_mode,
+                unmapped,
+            )
+        })?;
 
         writeln!(output, "    }}")?;
         writeln!(output, "  }}")?;
@@ -178,7 +360,7 @@ pub struct ClassField {
     pub field_name: String,
 
     /// Rust mapped field type.
-    /// If none the type isn't yet supported.
+    /// If none the type will be resolved from the engines field type while emitting.
     pub field_type: Option<String>,
 
     /// The engines field type
@@ -194,19 +376,39 @@ impl ClassField {
         mod_name: &str,
         class_name: &str,
         output: &mut dyn std::io::Write,
+        types: &SchemaTypeIndex,
+        offset_mode: RustOffsetMode,
+        unmapped: &mut Vec<UnmappedField>,
     ) -> Result<()> {
-        if let Some(field_type) = &self.field_type {
+        let field_type = self.field_type.clone().or_else(|| {
+            types.map_scoped_type(Some(mod_name_from_schema_name(mod_name)), &self.field_ctype)
+        });
+
+        if let Some(field_type) = field_type {
             writeln!(
                 output,
                 "      /// Var: {} {}  ",
                 self.field_ctype, self.field_name
             )?;
             writeln!(output, "      /// Offset: 0x{:X}  ", self.offset)?;
-            writeln!(
-                output,
-                "      pub {}: {} = RuntimeOffset::new(\"{}\", \"{}\", \"{}\"),",
-                self.field_name, field_type, mod_name, class_name, self.field_name
-            )?;
+            if self.field_ctype.starts_with("bitfield:") {
+                writeln!(
+                    output,
+                    "      /// Attention: Value contains the storage of the whole bitfield  "
+                )?;
+            }
+            match offset_mode {
+                RustOffsetMode::Runtime => writeln!(
+                    output,
+                    "      pub {}: {} = RuntimeOffset::new(\"{}\", \"{}\", \"{}\"),",
+                    self.field_name, field_type, mod_name, class_name, self.field_name
+                )?,
+                RustOffsetMode::Fixed => writeln!(
+                    output,
+                    "      pub {}: {} = 0x{:X}u64,",
+                    self.field_name, field_type, self.offset
+                )?,
+            }
         } else {
             writeln!(
                 output,
@@ -219,6 +421,12 @@ impl ClassField {
                 "      /* pub {}: {} = 0x{:X}, */",
                 self.field_name, self.field_ctype, self.offset
             )?;
+
+            unmapped.push(UnmappedField {
+                class_name: class_name.to_string(),
+                field_name: self.field_name.clone(),
+                field_ctype: self.field_ctype.clone(),
+            });
         }
 
         Ok(())
@@ -234,3 +442,58 @@ pub enum Metadata {
     NetworkVarNames { var_name: String, var_type: String },
     Unknown { name: String },
 }
+
+#[cfg(test)]
+mod test {
+    use super::{
+        ClassDefinition,
+        SchemaScope,
+        SchemaTypeIndex,
+    };
+
+    fn scope(schema_name: &str, classes: &[&str]) -> SchemaScope {
+        SchemaScope {
+            schema_name: schema_name.to_string(),
+            classes: classes
+                .iter()
+                .map(|class_name| ClassDefinition {
+                    class_name: class_name.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            enums: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_scope_preference() {
+        let index = SchemaTypeIndex::from_scopes(&[
+            scope("client.dll", &["CBodyComponent", "C_BaseEntity"]),
+            scope("server.dll", &["CBodyComponent", "CBaseEntity"]),
+        ]);
+
+        assert_eq!(
+            index.map_scoped_type(Some("server"), "CBodyComponent*"),
+            Some("Ptr<server::CBodyComponent>".to_string())
+        );
+        assert_eq!(
+            index.map_scoped_type(Some("client"), "CBodyComponent*"),
+            Some("Ptr<client::CBodyComponent>".to_string())
+        );
+
+        /* other scopes fall back to the first declaration */
+        assert_eq!(
+            index.map_scoped_type(Some("globals"), "CBodyComponent"),
+            Some("client::CBodyComponent".to_string())
+        );
+        assert_eq!(
+            index.map_type("CBodyComponent"),
+            Some("client::CBodyComponent".to_string())
+        );
+        assert_eq!(
+            index.map_scoped_type(Some("client"), "CHandle<CBaseEntity>"),
+            Some("EntityHandle<server::CBaseEntity>".to_string())
+        );
+        assert_eq!(index.map_type("QAngle"), Some("[f32; 0x03]".to_string()));
+    }
+}
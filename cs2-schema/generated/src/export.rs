@@ -0,0 +1,952 @@
+//! Alternative output formats for schema dumps.
+use std::{
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    io::{
+        Result,
+        Write,
+    },
+};
+
+use crate::definition::{
+    mod_name_from_schema_name,
+    ClassDefinition,
+    EnumDefinition,
+    RustOffsetMode,
+    SchemaScope,
+    SchemaTypeIndex,
+    UnmappedField,
+};
+
+enum DeclarationKind<'a> {
+    Class(&'a ClassDefinition),
+    Enum(&'a EnumDefinition),
+}
+
+struct Declaration<'a> {
+    mod_name: &'a str,
+    kind: DeclarationKind<'a>,
+}
+
+/// Lookup of all declared classes and enums by their engine name
+struct DeclarationIndex<'a> {
+    declarations: HashMap<&'a str, Declaration<'a>>,
+
+    /// Classes which directly inherit from the class
+    derived: HashMap<&'a str, Vec<&'a str>>,
+}
+
+impl<'a> DeclarationIndex<'a> {
+    fn from_scopes(scopes: &'a [SchemaScope]) -> Self {
+        let mut declarations = HashMap::new();
+        let mut derived = HashMap::<&str, Vec<&str>>::new();
+
+        for scope in scopes {
+            let mod_name = mod_name_from_schema_name(&scope.schema_name);
+            for definition in scope.enums.iter() {
+                declarations
+                    .entry(definition.enum_name.as_str())
+                    .or_insert(Declaration {
+                        mod_name,
+                        kind: DeclarationKind::Enum(definition),
+                    });
+            }
+
+            for class in scope.classes.iter() {
+                declarations
+                    .entry(class.class_name.as_str())
+                    .or_insert(Declaration {
+                        mod_name,
+                        kind: DeclarationKind::Class(class),
+                    });
+
+                if let Some(base_class) = &class.inherits {
+                    derived
+                        .entry(base_class.as_str())
+                        .or_default()
+                        .push(&class.class_name);
+                }
+            }
+        }
+
+        for classes in derived.values_mut() {
+            classes.sort();
+        }
+
+        Self {
+            declarations,
+            derived,
+        }
+    }
+
+    fn class(&self, name: &str) -> Option<&'a ClassDefinition> {
+        match self.declarations.get(name)?.kind {
+            DeclarationKind::Class(class) => Some(class),
+            DeclarationKind::Enum(_) => None,
+        }
+    }
+
+    /// Anchor name of the first declaration within the class reference
+    fn anchor(&self, name: &str) -> Option<String> {
+        let declaration = self.declarations.get(name)?;
+        Some(scoped_anchor(declaration.mod_name, name))
+    }
+
+    /// Qualified C++ name of the declaration
+    fn cpp_name(&self, name: &str) -> Option<String> {
+        let declaration = self.declarations.get(name)?;
+        Some(format!(
+            "{}::{}",
+            declaration.mod_name,
+            name.replace(':', "_")
+        ))
+    }
+}
+
+/// Extract the declared type out of an engine type name
+/// (e.g. `CHandle< C_BaseEntity >[2]` to `C_BaseEntity`).
+fn referenced_type(ctype: &str) -> &str {
+    let mut ctype = ctype.trim();
+    loop {
+        let next = if let Some(base_type) = ctype.strip_suffix('*') {
+            base_type
+        } else if let Some((base_type, _)) = ctype.split_once('[') {
+            base_type
+        } else if let Some((_, inner_type)) = ctype
+            .strip_suffix('>')
+            .and_then(|ctype| ctype.split_once('<'))
+        {
+            inner_type
+        } else {
+            return ctype;
+        };
+
+        ctype = next.trim();
+    }
+}
+
+/// Emit a standalone rust source file containing all schema definitions.
+/// Returns all fields which could not be mapped.
+pub fn emit_rust_source(
+    scopes: &[SchemaScope],
+    output: &mut dyn Write,
+    offset_mode: RustOffsetMode,
+) -> Result<Vec<UnmappedField>> {
+    let types = SchemaTypeIndex::from_scopes(scopes);
+    let mut unmapped = Vec::new();
+
+    writeln!(output, "/* Autogenerated schema offsets */")?;
+    for scope in scopes.iter() {
+        scope.emit_rust_definition(output, &types, offset_mode, &mut unmapped)?;
+    }
+
+    Ok(unmapped)
+}
+
+/// C++ representation of a field type
+struct CppType {
+    name: String,
+    /// Array dimensions (e.g. `[2][3]`)
+    dimensions: String,
+    size: u64,
+}
+
+impl CppType {
+    fn new(name: impl Into<String>, size: u64) -> Self {
+        Self {
+            name: name.into(),
+            dimensions: String::new(),
+            size,
+        }
+    }
+}
+
+fn map_cpp_type(index: &DeclarationIndex, ctype: &str) -> Option<CppType> {
+    let ctype = ctype.trim();
+    if ctype.starts_with("bitfield:") {
+        return None;
+    }
+
+    if let Some(base_type) = ctype.strip_suffix('*') {
+        let name = match map_cpp_type(index, base_type) {
+            Some(base_type) if base_type.dimensions.is_empty() => format!("{}*", base_type.name),
+            _ => "void*".to_string(),
+        };
+        return Some(CppType::new(name, 0x08));
+    }
+
+    if let Some(array_type) = ctype.strip_suffix(']') {
+        let (base_type, length) = array_type.split_once('[')?;
+        let (length, inner_dimensions) = match length.split_once(']') {
+            Some((length, inner_dimensions)) => (length, format!("{}]", inner_dimensions)),
+            None => (length, String::new()),
+        };
+        let length = length.trim().parse::<u64>().ok()?;
+        let element_type = map_cpp_type(index, &format!("{}{}", base_type, inner_dimensions))?;
+        return Some(CppType {
+            name: element_type.name,
+            dimensions: format!("[{}]{}", length, element_type.dimensions),
+            size: element_type.size * length,
+        });
+    }
+
+    if let Some(template) = ctype
+        .strip_suffix('>')
+        .and_then(|ctype| ctype.split_once('<'))
+        .map(|(template, _)| template.trim())
+    {
+        return match template {
+            "CHandle" => Some(CppType::new("uint32_t", 0x04)),
+            _ => None,
+        };
+    }
+
+    let (name, size) = match ctype {
+        "bool" => ("bool", 1),
+        "char" => ("char", 1),
+
+        "int8" => ("int8_t", 1),
+        "uint8" => ("uint8_t", 1),
+
+        "int16" => ("int16_t", 2),
+        "uint16" => ("uint16_t", 2),
+
+        "int32" => ("int32_t", 4),
+        "uint32" => ("uint32_t", 4),
+
+        "int64" => ("int64_t", 8),
+        "uint64" => ("uint64_t", 8),
+
+        "float32" => ("float", 4),
+        "float64" => ("double", 8),
+
+        "CEntityIndex" => ("int32_t", 4),
+        "CUtlStringToken" => ("uint32_t", 4),
+        "CUtlSymbolLarge" => ("const char*", 8),
+        "CUtlString" => ("const char*", 8),
+        "Vector" | "QAngle" => {
+            return Some(CppType {
+                name: "float".to_string(),
+                dimensions: "[3]".to_string(),
+                size: 0x0C,
+            })
+        }
+        "Color" => {
+            return Some(CppType {
+                name: "uint8_t".to_string(),
+                dimensions: "[4]".to_string(),
+                size: 0x04,
+            })
+        }
+
+        ctype => {
+            let declaration = index.declarations.get(ctype)?;
+            let size = match declaration.kind {
+                DeclarationKind::Class(class) => class.class_size,
+                DeclarationKind::Enum(definition) => definition.enum_size as u64,
+            };
+
+            return Some(CppType::new(index.cpp_name(ctype)?, size));
+        }
+    };
+
+    Some(CppType::new(name, size))
+}
+
+fn cpp_enum_type(enum_size: usize) -> Option<(&'static str, u64)> {
+    match enum_size {
+        1 => Some(("uint8_t", 0xFF)),
+        2 => Some(("uint16_t", 0xFFFF)),
+        4 => Some(("uint32_t", 0xFFFFFFFF)),
+        8 => Some(("uint64_t", 0xFFFFFFFFFFFFFFFF)),
+        _ => None,
+    }
+}
+
+fn emit_cpp_enum(
+    output: &mut dyn Write,
+    mod_name: &str,
+    definition: &EnumDefinition,
+) -> Result<()> {
+    let Some((enum_type, value_mask)) = cpp_enum_type(definition.enum_size) else {
+        writeln!(
+            output,
+            "// enum {}::{} has an invalid size of {}",
+            mod_name, definition.enum_name, definition.enum_size
+        )?;
+        return Ok(());
+    };
+
+    writeln!(output, "namespace {} {{", mod_name)?;
+    writeln!(output, "    // Size: 0x{:X}", definition.enum_size)?;
+    writeln!(
+        output,
+        "    enum class {} : {} {{",
+        definition.enum_name.replace(':', "_"),
+        enum_type
+    )?;
+    for member in definition.memebers.iter() {
+        writeln!(
+            output,
+            "        {} = 0x{:X},",
+            member.name,
+            member.value & value_mask
+        )?;
+    }
+    writeln!(output, "    }};")?;
+    writeln!(output, "}}")?;
+    writeln!(output)?;
+    Ok(())
+}
+
+fn emit_cpp_class(
+    output: &mut dyn Write,
+    index: &DeclarationIndex,
+    mod_name: &str,
+    class: &ClassDefinition,
+) -> Result<()> {
+    let class_name = class.class_name.replace(':', "_");
+    let base_class = class
+        .inherits
+        .as_ref()
+        .and_then(|base_class| Some((index.cpp_name(base_class)?, index.class(base_class)?)));
+
+    writeln!(output, "namespace {} {{", mod_name)?;
+    writeln!(output, "    // Size: 0x{:X}", class.class_size)?;
+    if let Some((base_name, _)) = &base_class {
+        writeln!(
+            output,
+            "    struct {} : public {} {{",
+            class_name, base_name
+        )?;
+    } else {
+        writeln!(output, "    struct {} {{", class_name)?;
+    }
+
+    let mut fields = class.offsets.iter().collect::<Vec<_>>();
+    fields.sort_by_key(|field| field.offset);
+
+    let mut cursor = base_class
+        .as_ref()
+        .map(|(_, base_class)| base_class.class_size)
+        .unwrap_or(0);
+
+    for (field_index, field) in fields.iter().enumerate() {
+        if field.offset < cursor || field.field_ctype.starts_with("bitfield:") {
+            writeln!(
+                output,
+                "        // 0x{:04X} {} {}",
+                field.offset, field.field_ctype, field.field_name
+            )?;
+            continue;
+        }
+
+        if field.offset > cursor {
+            writeln!(
+                output,
+                "        uint8_t __pad_0x{:04X}[0x{:X}];",
+                cursor,
+                field.offset - cursor
+            )?;
+        }
+
+        /* unknown types will be represented by their raw bytes up to the next field */
+        let next_offset = fields[field_index + 1..]
+            .iter()
+            .map(|field| field.offset)
+            .find(|offset| *offset > field.offset)
+            .unwrap_or(class.class_size);
+
+        let field_type = map_cpp_type(index, &field.field_ctype)
+            .filter(|field_type| field.offset + field_type.size <= class.class_size)
+            .unwrap_or_else(|| CppType {
+                name: "uint8_t".to_string(),
+                dimensions: format!("[0x{:X}]", next_offset.saturating_sub(field.offset)),
+                size: next_offset.saturating_sub(field.offset),
+            });
+
+        if field_type.size == 0 {
+            writeln!(
+                output,
+                "        // 0x{:04X} {} {}",
+                field.offset, field.field_ctype, field.field_name
+            )?;
+            continue;
+        }
+
+        writeln!(
+            output,
+            "        {} {}{}; // 0x{:04X} {}",
+            field_type.name,
+            field.field_name,
+            field_type.dimensions,
+            field.offset,
+            field.field_ctype
+        )?;
+        cursor = field.offset + field_type.size;
+    }
+
+    if class.class_size > cursor {
+        writeln!(
+            output,
+            "        uint8_t __pad_0x{:04X}[0x{:X}];",
+            cursor,
+            class.class_size - cursor
+        )?;
+    }
+
+    writeln!(output, "    }};")?;
+    if class.class_size > 0 && cursor <= class.class_size {
+        writeln!(
+            output,
+            "    static_assert(sizeof({}) == 0x{:X});",
+            class_name, class.class_size
+        )?;
+    }
+    writeln!(output, "}}")?;
+    writeln!(output)?;
+    Ok(())
+}
+
+/// Order the classes so every class gets declared after its base class
+/// and all classes it contains by value.
+fn cpp_class_order<'a>(
+    scopes: &'a [SchemaScope],
+    index: &DeclarationIndex<'a>,
+) -> Vec<(&'a str, &'a ClassDefinition)> {
+    fn visit<'a>(
+        class: &'a ClassDefinition,
+        index: &DeclarationIndex<'a>,
+        visited: &mut HashSet<*const ClassDefinition>,
+        result: &mut Vec<(&'a str, &'a ClassDefinition)>,
+    ) {
+        if !visited.insert(class as *const _) {
+            return;
+        }
+
+        let dependencies = class.inherits.iter().map(String::as_str).chain(
+            class
+                .offsets
+                .iter()
+                .filter(|field| !field.field_ctype.trim().ends_with('*'))
+                .map(|field| referenced_type(&field.field_ctype)),
+        );
+        for dependency in dependencies {
+            if let Some(dependency) = index.class(dependency) {
+                visit(dependency, index, visited, result);
+            }
+        }
+
+        if let Some(declaration) = index.declarations.get(class.class_name.as_str()) {
+            result.push((declaration.mod_name, class));
+        }
+    }
+
+    let mut visited = HashSet::new();
+    let mut result = Vec::new();
+    for scope in scopes {
+        for class in scope.classes.iter() {
+            if let Some(class) = index.class(&class.class_name) {
+                visit(class, index, &mut visited, &mut result);
+            }
+        }
+    }
+
+    result
+}
+
+/// Emit a C++ header containing all classes (with padding and offset comments) and enums.
+pub fn emit_cpp_header(scopes: &[SchemaScope], output: &mut dyn Write) -> Result<()> {
+    let index = DeclarationIndex::from_scopes(scopes);
+
+    writeln!(output, "// Autogenerated CS2 schema")?;
+    writeln!(output, "#pragma once")?;
+    writeln!(output, "#include <cstdint>")?;
+    writeln!(output)?;
+    writeln!(output, "#pragma pack(push, 1)")?;
+    writeln!(output)?;
+
+    for scope in scopes {
+        if scope.classes.is_empty() {
+            continue;
+        }
+
+        let mod_name = mod_name_from_schema_name(&scope.schema_name);
+        writeln!(output, "namespace {} {{", mod_name)?;
+        for class in scope.classes.iter() {
+            writeln!(output, "    struct {};", class.class_name.replace(':', "_"))?;
+        }
+        writeln!(output, "}}")?;
+        writeln!(output)?;
+    }
+
+    for scope in scopes {
+        let mod_name = mod_name_from_schema_name(&scope.schema_name);
+        for definition in scope.enums.iter() {
+            emit_cpp_enum(output, mod_name, definition)?;
+        }
+    }
+
+    for (mod_name, class) in cpp_class_order(scopes, &index) {
+        emit_cpp_class(output, &index, mod_name, class)?;
+    }
+
+    writeln!(output, "#pragma pack(pop)")?;
+    Ok(())
+}
+
+/// Anchor name of a declaration within the section of its scope.
+/// Types might be declared in multiple scopes, hence the scope is part of the anchor.
+fn scoped_anchor(mod_name: &str, name: &str) -> String {
+    format!("{}-{}", mod_name, name.replace(':', "_"))
+}
+
+fn markdown_type_link(index: &DeclarationIndex, ctype: &str) -> String {
+    match index.anchor(referenced_type(ctype)) {
+        Some(anchor) => format!("[`{}`](#{})", ctype, anchor),
+        None => format!("`{}`", ctype),
+    }
+}
+
+/// Emit a browsable Markdown class reference
+pub fn emit_markdown(scopes: &[SchemaScope], output: &mut dyn Write) -> Result<()> {
+    let index = DeclarationIndex::from_scopes(scopes);
+
+    writeln!(output, "# CS2 Schema")?;
+    writeln!(output)?;
+    for scope in scopes {
+        let mod_name = mod_name_from_schema_name(&scope.schema_name);
+        writeln!(
+            output,
+            "- [{}](#{}) ({} classes, {} enums)",
+            scope.schema_name,
+            mod_name,
+            scope.classes.len(),
+            scope.enums.len()
+        )?;
+    }
+
+    for scope in scopes {
+        let mod_name = mod_name_from_schema_name(&scope.schema_name);
+        writeln!(output)?;
+        writeln!(output, "<a name=\"{}\"></a>", mod_name)?;
+        writeln!(output, "## {}", scope.schema_name)?;
+
+        for class in scope.classes.iter() {
+            writeln!(output)?;
+            writeln!(
+                output,
+                "<a name=\"{}\"></a>",
+                scoped_anchor(mod_name, &class.class_name)
+            )?;
+            writeln!(output, "### class {}", class.class_name)?;
+            writeln!(output)?;
+            writeln!(output, "Size: `0x{:X}`  ", class.class_size)?;
+            if let Some(base_class) = &class.inherits {
+                writeln!(
+                    output,
+                    "Inherits: {}  ",
+                    markdown_type_link(&index, base_class)
+                )?;
+            }
+            if let Some(derived) = index.derived.get(class.class_name.as_str()) {
+                let derived = derived
+                    .iter()
+                    .map(|class_name| markdown_type_link(&index, class_name))
+                    .collect::<Vec<_>>();
+                writeln!(output, "Derived: {}  ", derived.join(", "))?;
+            }
+
+            if class.offsets.is_empty() {
+                continue;
+            }
+
+            writeln!(output)?;
+            writeln!(output, "| Offset | Type | Name |")?;
+            writeln!(output, "|---|---|---|")?;
+            for field in class.offsets.iter() {
+                writeln!(
+                    output,
+                    "| `0x{:04X}` | {} | `{}` |",
+                    field.offset,
+                    markdown_type_link(&index, &field.field_ctype),
+                    field.field_name
+                )?;
+            }
+        }
+
+        for definition in scope.enums.iter() {
+            writeln!(output)?;
+            writeln!(
+                output,
+                "<a name=\"{}\"></a>",
+                scoped_anchor(mod_name, &definition.enum_name)
+            )?;
+            writeln!(output, "### enum {}", definition.enum_name)?;
+            writeln!(output)?;
+            writeln!(output, "Size: `0x{:X}`", definition.enum_size)?;
+            writeln!(output)?;
+            writeln!(output, "| Name | Value |")?;
+            writeln!(output, "|---|---|")?;
+            let value_mask = cpp_enum_type(definition.enum_size)
+                .map(|(_, value_mask)| value_mask)
+                .unwrap_or(u64::MAX);
+            for member in definition.memebers.iter() {
+                writeln!(
+                    output,
+                    "| `{}` | `0x{:X}` |",
+                    member.name,
+                    member.value & value_mask
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn html_type_link(index: &DeclarationIndex, ctype: &str) -> String {
+    match index.anchor(referenced_type(ctype)) {
+        Some(anchor) => format!(
+            "<a href=\"#{}\"><code>{}</code></a>",
+            anchor,
+            html_escape(ctype)
+        ),
+        None => format!("<code>{}</code>", html_escape(ctype)),
+    }
+}
+
+/// Emit a browsable HTML class reference
+pub fn emit_html(scopes: &[SchemaScope], output: &mut dyn Write) -> Result<()> {
+    let index = DeclarationIndex::from_scopes(scopes);
+
+    writeln!(output, "<!DOCTYPE html>")?;
+    writeln!(output, "<html>")?;
+    writeln!(output, "<head>")?;
+    writeln!(output, "<meta charset=\"utf-8\">")?;
+    writeln!(output, "<title>CS2 Schema</title>")?;
+    writeln!(output, "<style>")?;
+    writeln!(
+        output,
+        "body {{ font-family: sans-serif; margin: 0; display: flex; }}"
+    )?;
+    writeln!(
+        output,
+        "nav {{ width: 20em; height: 100vh; overflow: auto; position: sticky; top: 0; padding: 1em; box-sizing: border-box; background: #f4f4f4; }}"
+    )?;
+    writeln!(output, "main {{ flex: 1; padding: 1em 2em; }}")?;
+    writeln!(
+        output,
+        "table {{ border-collapse: collapse; }} td, th {{ border: 1px solid #ccc; padding: 0.2em 0.6em; text-align: left; }}"
+    )?;
+    writeln!(output, "</style>")?;
+    writeln!(output, "</head>")?;
+    writeln!(output, "<body>")?;
+
+    writeln!(output, "<nav>")?;
+    for scope in scopes {
+        let mod_name = mod_name_from_schema_name(&scope.schema_name);
+        writeln!(output, "<h3>{}</h3>", html_escape(&scope.schema_name))?;
+        writeln!(output, "<ul>")?;
+        let names = scope
+            .classes
+            .iter()
+            .map(|class| class.class_name.as_str())
+            .chain(
+                scope
+                    .enums
+                    .iter()
+                    .map(|definition| definition.enum_name.as_str()),
+            );
+        for name in names {
+            writeln!(
+                output,
+                "<li><a href=\"#{}\">{}</a></li>",
+                scoped_anchor(mod_name, name),
+                html_escape(name)
+            )?;
+        }
+        writeln!(output, "</ul>")?;
+    }
+    writeln!(output, "</nav>")?;
+
+    writeln!(output, "<main>")?;
+    for scope in scopes {
+        let mod_name = mod_name_from_schema_name(&scope.schema_name);
+        writeln!(output, "<h1>{}</h1>", html_escape(&scope.schema_name))?;
+
+        for class in scope.classes.iter() {
+            let anchor = scoped_anchor(mod_name, &class.class_name);
+            writeln!(
+                output,
+                "<h2 id=\"{}\">class {}</h2>",
+                anchor,
+                html_escape(&class.class_name)
+            )?;
+            writeln!(output, "<p>Size: <code>0x{:X}</code>", class.class_size)?;
+            if let Some(base_class) = &class.inherits {
+                writeln!(
+                    output,
+                    "<br>Inherits: {}",
+                    html_type_link(&index, base_class)
+                )?;
+            }
+            if let Some(derived) = index.derived.get(class.class_name.as_str()) {
+                let derived = derived
+                    .iter()
+                    .map(|class_name| html_type_link(&index, class_name))
+                    .collect::<Vec<_>>();
+                writeln!(output, "<br>Derived: {}", derived.join(", "))?;
+            }
+            writeln!(output, "</p>")?;
+
+            if class.offsets.is_empty() {
+                continue;
+            }
+
+            writeln!(output, "<table>")?;
+            writeln!(output, "<tr><th>Offset</th><th>Type</th><th>Name</th></tr>")?;
+            for field in class.offsets.iter() {
+                writeln!(
+                    output,
+                    "<tr><td><code>0x{:04X}</code></td><td>{}</td><td><code>{}</code></td></tr>",
+                    field.offset,
+                    html_type_link(&index, &field.field_ctype),
+                    html_escape(&field.field_name)
+                )?;
+            }
+            writeln!(output, "</table>")?;
+        }
+
+        for definition in scope.enums.iter() {
+            let anchor = scoped_anchor(mod_name, &definition.enum_name);
+            writeln!(
+                output,
+                "<h2 id=\"{}\">enum {}</h2>",
+                anchor,
+                html_escape(&definition.enum_name)
+            )?;
+            writeln!(
+                output,
+                "<p>Size: <code>0x{:X}</code></p>",
+                definition.enum_size
+            )?;
+            writeln!(output, "<table>")?;
+            writeln!(output, "<tr><th>Name</th><th>Value</th></tr>")?;
+            let value_mask = cpp_enum_type(definition.enum_size)
+                .map(|(_, value_mask)| value_mask)
+                .unwrap_or(u64::MAX);
+            for member in definition.memebers.iter() {
+                writeln!(
+                    output,
+                    "<tr><td><code>{}</code></td><td><code>0x{:X}</code></td></tr>",
+                    html_escape(&member.name),
+                    member.value & value_mask
+                )?;
+            }
+            writeln!(output, "</table>")?;
+        }
+    }
+    writeln!(output, "</main>")?;
+
+    writeln!(output, "</body>")?;
+    writeln!(output, "</html>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        emit_cpp_header,
+        emit_html,
+        emit_markdown,
+        html_escape,
+    };
+    use crate::definition::{
+        ClassDefinition,
+        ClassField,
+        EnumDefinition,
+        EnumMember,
+        SchemaScope,
+    };
+
+    fn field(field_name: &str, field_ctype: &str, offset: u64) -> ClassField {
+        ClassField {
+            field_name: field_name.to_string(),
+            field_ctype: field_ctype.to_string(),
+            offset,
+            ..Default::default()
+        }
+    }
+
+    fn fixture() -> Vec<SchemaScope> {
+        vec![SchemaScope {
+            schema_name: "client.dll".to_string(),
+            classes: vec![
+                ClassDefinition {
+                    class_name: "C_Player".to_string(),
+                    class_size: 0x38,
+                    inherits: Some("C_BaseEntity".to_string()),
+                    offsets: vec![
+                        field("m_vecOrigin", "Vector", 0x10),
+                        field("m_hOwner", "CHandle< C_BaseEntity >", 0x1C),
+                        field("m_Inner", "C_Player::Inner_t", 0x20),
+                        field("m_Unknown", "CUnknownType", 0x30),
+                    ],
+                    ..Default::default()
+                },
+                ClassDefinition {
+                    class_name: "C_Player::Inner_t".to_string(),
+                    class_size: 0x08,
+                    offsets: vec![field("m_nState", "PlayerState_t", 0x04)],
+                    ..Default::default()
+                },
+                ClassDefinition {
+                    class_name: "C_BaseEntity".to_string(),
+                    class_size: 0x10,
+                    offsets: vec![field("m_iHealth", "int32", 0x08)],
+                    ..Default::default()
+                },
+            ],
+            enums: vec![EnumDefinition {
+                enum_name: "PlayerState_t".to_string(),
+                enum_size: 4,
+                memebers: vec![
+                    EnumMember {
+                        name: "STATE_ACTIVE".to_string(),
+                        value: 1,
+                    },
+                    EnumMember {
+                        name: "STATE_INVALID".to_string(),
+                        value: u64::MAX,
+                    },
+                ],
+                ..Default::default()
+            }],
+        }]
+    }
+
+    #[test]
+    fn test_cpp_header() {
+        let mut output = Vec::new();
+        emit_cpp_header(&fixture(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("#pragma once\n"));
+        let pack_push = output.find("#pragma pack(push, 1)\n").unwrap();
+        let pack_pop = output.find("#pragma pack(pop)\n").unwrap();
+        assert!(pack_push < pack_pop);
+        assert!(output.trim_end().ends_with("#pragma pack(pop)"));
+
+        /* nested type names are flattened into a valid C++ identifier */
+        assert!(output.contains("    struct C_Player__Inner_t;\n"));
+
+        /* enum values are truncated to the size of the enum */
+        assert!(output.contains(
+            "    enum class PlayerState_t : uint32_t {\n        STATE_ACTIVE = 0x1,\n        STATE_INVALID = 0xFFFFFFFF,\n    };\n"
+        ));
+
+        assert!(output.contains(concat!(
+            "namespace client {\n",
+            "    // Size: 0x8\n",
+            "    struct C_Player__Inner_t {\n",
+            "        uint8_t __pad_0x0000[0x4];\n",
+            "        client::PlayerState_t m_nState; // 0x0004 PlayerState_t\n",
+            "    };\n",
+            "    static_assert(sizeof(C_Player__Inner_t) == 0x8);\n",
+            "}\n",
+        )));
+
+        assert!(output.contains(concat!(
+            "namespace client {\n",
+            "    // Size: 0x38\n",
+            "    struct C_Player : public client::C_BaseEntity {\n",
+            "        float m_vecOrigin[3]; // 0x0010 Vector\n",
+            "        uint32_t m_hOwner; // 0x001C CHandle< C_BaseEntity >\n",
+            "        client::C_Player__Inner_t m_Inner; // 0x0020 C_Player::Inner_t\n",
+            "        uint8_t __pad_0x0028[0x8];\n",
+            "        uint8_t m_Unknown[0x8]; // 0x0030 CUnknownType\n",
+            "    };\n",
+            "    static_assert(sizeof(C_Player) == 0x38);\n",
+            "}\n",
+        )));
+
+        /* base classes and classes contained by value must be declared first */
+        let base_class = output.find("struct C_BaseEntity {").unwrap();
+        let inner_class = output.find("struct C_Player__Inner_t {").unwrap();
+        let player_class = output.find("struct C_Player : ").unwrap();
+        assert!(base_class < player_class);
+        assert!(inner_class < player_class);
+    }
+
+    #[test]
+    fn test_html_escaping() {
+        let mut output = Vec::new();
+        emit_html(&fixture(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains(
+            "<td><a href=\"#client-C_BaseEntity\"><code>CHandle&lt; C_BaseEntity &gt;</code></a></td>"
+        ));
+        assert!(output.contains(
+            "<td><a href=\"#client-C_Player__Inner_t\"><code>C_Player::Inner_t</code></a></td>"
+        ));
+        assert!(output.contains("<td><code>CUnknownType</code></td>"));
+        assert!(output.contains("<h2 id=\"client-C_Player__Inner_t\">class C_Player::Inner_t</h2>"));
+
+        /* no unescaped engine types should end up in the document */
+        assert!(!output.contains("< C_BaseEntity >"));
+        assert_eq!(
+            html_escape("CUtlVector< \"A&B\" >"),
+            "CUtlVector&lt; &quot;A&amp;B&quot; &gt;"
+        );
+    }
+
+    #[test]
+    fn test_anchors_per_scope() {
+        let mut scopes = fixture();
+        scopes.push(SchemaScope {
+            schema_name: "server.dll".to_string(),
+            classes: vec![ClassDefinition {
+                class_name: "C_BaseEntity".to_string(),
+                class_size: 0x10,
+                ..Default::default()
+            }],
+            enums: vec![],
+        });
+
+        let mut output = Vec::new();
+        emit_html(&scopes, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        /* every scope has its own section with a unique id */
+        assert_eq!(output.matches("<h2 id=\"client-C_BaseEntity\">").count(), 1);
+        assert_eq!(output.matches("<h2 id=\"server-C_BaseEntity\">").count(), 1);
+        assert!(output.contains("<li><a href=\"#server-C_BaseEntity\">C_BaseEntity</a></li>"));
+
+        /* type references link to the first declaration */
+        assert!(output.contains("<br>Inherits: <a href=\"#client-C_BaseEntity\">"));
+
+        let mut output = Vec::new();
+        emit_markdown(&scopes, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output.matches("<a name=\"client-C_BaseEntity\">").count(),
+            1
+        );
+        assert_eq!(
+            output.matches("<a name=\"server-C_BaseEntity\">").count(),
+            1
+        );
+    }
+}
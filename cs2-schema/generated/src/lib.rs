@@ -2,6 +2,7 @@
 #![feature(sync_unsafe_cell)]
 
 pub mod definition;
+pub mod export;
 
 mod entity;
 pub use entity::*;
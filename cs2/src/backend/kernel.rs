@@ -0,0 +1,127 @@
+use cs2_schema_declaration::BatchReadEntry;
+use obfstr::obfstr;
+use valthrun_kernel_interface::{
+    BatchRead,
+    IoctrlDriverInterface,
+    KernelInterface,
+    KeyboardState,
+    MouseState,
+    SearchPattern,
+};
+
+use super::{
+    MemoryBackend,
+    ProcessModule,
+};
+
+/// Memory backend using the Valthrun kernel driver
+pub struct KernelMemoryBackend {
+    interface: KernelInterface,
+    process_id: i32,
+    modules: Vec<ProcessModule>,
+}
+
+impl KernelMemoryBackend {
+    pub fn create() -> anyhow::Result<Self> {
+        let interface = Box::new(IoctrlDriverInterface::create(obfstr!(
+            "\\\\.\\GLOBALROOT\\Device\\valthrun"
+        ))?);
+        let interface = KernelInterface::create(interface)?;
+
+        /*
+         * Please no not analyze me:
+         * https://www.unknowncheats.me/wiki/Valve_Anti-Cheat:VAC_external_tool_detection_(and_more)
+         *
+         * Even tough we don't have open handles to CS2 we don't want anybody to read our process.
+         */
+        interface.toggle_process_protection(true)?;
+
+        let (process_id, modules) = interface.request_cs2_modules()?;
+        let modules = modules
+            .iter()
+            .map(|module| ProcessModule {
+                name: module.base_dll_name().to_string(),
+                base_address: module.base_address as u64,
+                module_size: module.module_size as u64,
+            })
+            .collect();
+
+        Ok(Self {
+            interface,
+            process_id,
+            modules,
+        })
+    }
+
+    pub fn interface(&self) -> &KernelInterface {
+        &self.interface
+    }
+}
+
+impl MemoryBackend for KernelMemoryBackend {
+    fn process_id(&self) -> i32 {
+        self.process_id
+    }
+
+    fn modules(&self) -> anyhow::Result<Vec<ProcessModule>> {
+        Ok(self.modules.clone())
+    }
+
+    fn read_slice(&self, offsets: &[u64], buffer: &mut [u8]) -> anyhow::Result<()> {
+        Ok(self
+            .interface
+            .read_slice(self.process_id, offsets, buffer)?)
+    }
+
+    fn read_batch(&self, entries: &mut [BatchReadEntry]) -> anyhow::Result<()> {
+        let mut reads = entries
+            .iter_mut()
+            .map(|entry| BatchRead {
+                address: entry.address,
+                buffer: &mut *entry.buffer,
+                success: false,
+            })
+            .collect::<Vec<_>>();
+
+        self.interface.read_batch(self.process_id, &mut reads)?;
+        let read_success = reads
+            .into_iter()
+            .map(|read| read.success)
+            .collect::<Vec<_>>();
+
+        for (entry, success) in entries.iter_mut().zip(read_success) {
+            entry.success = success;
+        }
+
+        Ok(())
+    }
+
+    fn find_pattern(
+        &self,
+        address: u64,
+        length: usize,
+        pattern: &dyn SearchPattern,
+    ) -> anyhow::Result<Option<u64>> {
+        Ok(self
+            .interface
+            .find_pattern(self.process_id, address, length, pattern)?)
+    }
+
+    fn total_read_calls(&self) -> usize {
+        self.interface.total_read_calls()
+    }
+
+    fn add_metrics_record(&self, record_type: &str, record_payload: &str) -> anyhow::Result<()> {
+        Ok(self
+            .interface
+            .add_metrics_record(record_type, record_payload)?)
+    }
+
+    fn send_keyboard_state(&self, states: &[KeyboardState]) -> anyhow::Result<()> {
+        Ok(self.interface.send_keyboard_state(states)?)
+    }
+
+    fn send_mouse_state(&self, states: &[MouseState]) -> anyhow::Result<()> {
+        Ok(self.interface.send_mouse_state(states)?)
+    }
+}
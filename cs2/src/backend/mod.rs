@@ -0,0 +1,90 @@
+use cs2_schema_declaration::BatchReadEntry;
+use valthrun_kernel_interface::{
+    KeyboardState,
+    MouseState,
+    SearchPattern,
+};
+
+mod kernel;
+pub use kernel::*;
+
+mod snapshot;
+pub use snapshot::*;
+
+/// A module loaded by the target process
+#[derive(Debug, Clone)]
+pub struct ProcessModule {
+    pub name: String,
+    pub base_address: u64,
+    pub module_size: u64,
+}
+
+impl ProcessModule {
+    pub fn contains_address(&self, address: u64) -> bool {
+        address >= self.base_address && address < self.base_address + self.module_size
+    }
+}
+
+/// Memory backend used by the `CS2Handle` to access the game process.
+///
+/// The kernel driver is the default backend, but everything which
+/// can enumerate modules and read memory (e.g. a memory snapshot) can be used.
+pub trait MemoryBackend: Send + Sync {
+    /// Id of the target process
+    fn process_id(&self) -> i32;
+
+    /// Enumerate all modules of the target process
+    fn modules(&self) -> anyhow::Result<Vec<ProcessModule>>;
+
+    /// Read memory into the target buffer.
+    /// All offsets except the last one will be dereferenced as pointers before reading.
+    fn read_slice(&self, offsets: &[u64], buffer: &mut [u8]) -> anyhow::Result<()>;
+
+    /// Execute multiple reads at once.
+    /// Backends which are unable to batch reads, will execute every read on its own.
+    fn read_batch(&self, entries: &mut [BatchReadEntry]) -> anyhow::Result<()> {
+        for entry in entries.iter_mut() {
+            entry.success = self.read_slice(&[entry.address], entry.buffer).is_ok();
+        }
+
+        Ok(())
+    }
+
+    /// Search for the first occurrence of the pattern within the target memory region.
+    fn find_pattern(
+        &self,
+        address: u64,
+        length: usize,
+        pattern: &dyn SearchPattern,
+    ) -> anyhow::Result<Option<u64>> {
+        if pattern.length() > length {
+            return Ok(None);
+        }
+
+        let mut buffer = vec![0u8; length];
+        self.read_slice(&[address], &mut buffer)?;
+
+        Ok(buffer
+            .windows(pattern.length())
+            .position(|window| pattern.is_matching(window))
+            .map(|index| address + index as u64))
+    }
+
+    /// Total amount of read calls issued to this backend
+    fn total_read_calls(&self) -> usize {
+        0
+    }
+
+    fn add_metrics_record(&self, _record_type: &str, _record_payload: &str) -> anyhow::Result<()> {
+        /* metrics are not supported by default */
+        Ok(())
+    }
+
+    fn send_keyboard_state(&self, _states: &[KeyboardState]) -> anyhow::Result<()> {
+        anyhow::bail!("memory backend does not support keyboard input")
+    }
+
+    fn send_mouse_state(&self, _states: &[MouseState]) -> anyhow::Result<()> {
+        anyhow::bail!("memory backend does not support mouse input")
+    }
+}
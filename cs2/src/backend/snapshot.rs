@@ -0,0 +1,122 @@
+use std::{
+    collections::BTreeMap,
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+};
+
+use anyhow::Context;
+
+use super::{
+    MemoryBackend,
+    ProcessModule,
+};
+
+/// Memory backend serving reads from a fixed set of memory regions.
+/// Can be used to run the cs2 logic against captured memory or in tests without the kernel driver.
+pub struct MemorySnapshotBackend {
+    process_id: i32,
+    modules: Vec<ProcessModule>,
+
+    /// Memory regions keyed by their start address
+    regions: BTreeMap<u64, Vec<u8>>,
+    read_calls: AtomicUsize,
+}
+
+impl MemorySnapshotBackend {
+    pub fn new(process_id: i32) -> Self {
+        Self {
+            process_id,
+            modules: Default::default(),
+
+            regions: Default::default(),
+            read_calls: AtomicUsize::new(0),
+        }
+    }
+
+    /// Add a memory region.
+    /// Regions must not overlap with each other.
+    pub fn add_region(&mut self, address: u64, data: Vec<u8>) -> anyhow::Result<()> {
+        let end_address = address + data.len() as u64;
+        if let Some((region_address, region)) = self.regions.range(..end_address).next_back() {
+            if region_address + region.len() as u64 > address {
+                anyhow::bail!(
+                    "region 0x{:X} overlaps with region 0x{:X}",
+                    address,
+                    region_address
+                );
+            }
+        }
+
+        self.regions.insert(address, data);
+        Ok(())
+    }
+
+    /// Register a module and map its memory image at the modules base address.
+    pub fn add_module(
+        &mut self,
+        name: impl Into<String>,
+        base_address: u64,
+        image: Vec<u8>,
+    ) -> anyhow::Result<()> {
+        let module = ProcessModule {
+            name: name.into(),
+            base_address,
+            module_size: image.len() as u64,
+        };
+
+        self.add_region(base_address, image)
+            .with_context(|| format!("map module {}", module.name))?;
+        self.modules.push(module);
+        Ok(())
+    }
+
+    fn read_region(&self, address: u64, buffer: &mut [u8]) -> anyhow::Result<()> {
+        let (region_address, region) = self
+            .regions
+            .range(..=address)
+            .next_back()
+            .with_context(|| format!("address 0x{:X} is not mapped", address))?;
+
+        let offset = (address - region_address) as usize;
+        if offset + buffer.len() > region.len() {
+            anyhow::bail!(
+                "read of 0x{:X} bytes at 0x{:X} exceeds the mapped region",
+                buffer.len(),
+                address
+            );
+        }
+
+        buffer.copy_from_slice(&region[offset..offset + buffer.len()]);
+        Ok(())
+    }
+}
+
+impl MemoryBackend for MemorySnapshotBackend {
+    fn process_id(&self) -> i32 {
+        self.process_id
+    }
+
+    fn modules(&self) -> anyhow::Result<Vec<ProcessModule>> {
+        Ok(self.modules.clone())
+    }
+
+    fn read_slice(&self, offsets: &[u64], buffer: &mut [u8]) -> anyhow::Result<()> {
+        let (target_offset, pointer_offsets) = offsets.split_last().context("missing offsets")?;
+
+        self.read_calls.fetch_add(1, Ordering::Relaxed);
+        let mut address = 0u64;
+        for offset in pointer_offsets {
+            let mut value = [0u8; 8];
+            self.read_region(address + offset, &mut value)?;
+            address = u64::from_le_bytes(value);
+        }
+
+        self.read_region(address + target_offset, buffer)
+    }
+
+    fn total_read_calls(&self) -> usize {
+        self.read_calls.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,75 @@
+use std::{
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use anyhow::Context;
+use clap::Parser;
+use cs2::{
+    validate_signatures,
+    Module,
+    PeImage,
+};
+
+/// Validate all registered signatures against game modules on disk
+#[derive(Debug, Parser)]
+struct Args {
+    /// Enable verbose logging
+    #[clap(short, long)]
+    verbose: bool,
+
+    /// Paths to the game modules (client.dll, engine2.dll, schemasystem.dll, tier0.dll)
+    #[clap(required = true)]
+    modules: Vec<PathBuf>,
+}
+
+fn load_modules(paths: &[PathBuf]) -> anyhow::Result<Vec<(Module, PeImage)>> {
+    let mut images = Vec::with_capacity(paths.len());
+    for path in paths {
+        let file_name = path
+            .file_name()
+            .context("missing file name")?
+            .to_string_lossy();
+
+        let module = Module::ALL
+            .into_iter()
+            .find(|module| module.get_module_name().eq_ignore_ascii_case(&file_name))
+            .with_context(|| format!("unknown game module {}", file_name))?;
+
+        log::info!("Loading {} from {}", file_name, path.to_string_lossy());
+        images.push((module, PeImage::load(path)?));
+    }
+
+    Ok(images)
+}
+
+fn main() -> anyhow::Result<ExitCode> {
+    let args = Args::parse();
+    env_logger::builder()
+        .filter_level(if args.verbose {
+            log::LevelFilter::Trace
+        } else {
+            log::LevelFilter::Info
+        })
+        .parse_default_env()
+        .init();
+
+    let images = load_modules(&args.modules)?;
+    let reports = validate_signatures(images)?;
+
+    let mut failed = 0;
+    for report in reports.iter() {
+        println!("{}", report);
+        if !report.is_valid() {
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        println!("{} of {} signatures failed", failed, reports.len());
+        Ok(ExitCode::FAILURE)
+    } else {
+        println!("All {} signatures resolved", reports.len());
+        Ok(ExitCode::SUCCESS)
+    }
+}
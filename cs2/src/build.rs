@@ -33,18 +33,24 @@ impl State for BuildInfo {
 }
 
 impl BuildInfo {
-    fn find_build_info(cs2: &CS2Handle) -> anyhow::Result<u64> {
-        cs2.resolve_signature(
-            Module::Engine,
-            &Signature::relative_address(
-                obfstr!("client build info"),
-                obfstr!("48 8B 1D ? ? ? ? 48 85 DB 74 6B"),
-                0x03,
-                0x07,
-            ),
+    /// All signatures used to read the build info
+    pub fn signatures() -> Vec<(Module, Signature)> {
+        vec![(Module::Engine, Self::signature_build_info())]
+    }
+
+    fn signature_build_info() -> Signature {
+        Signature::relative_address(
+            obfstr!("client build info"),
+            obfstr!("48 8B 1D ? ? ? ? 48 85 DB 74 6B"),
+            0x03,
+            0x07,
         )
     }
 
+    fn find_build_info(cs2: &CS2Handle) -> anyhow::Result<u64> {
+        cs2.resolve_signature(Module::Engine, &Self::signature_build_info())
+    }
+
     pub fn read_build_info(cs2: &CS2Handle) -> anyhow::Result<Self> {
         let address = Self::find_build_info(cs2)?;
         let engine_build_info = cs2.read_schema::<EngineBuildInfo>(&[address])?;
@@ -1,14 +1,22 @@
 use std::sync::Arc;
 
+use anyhow::Context;
 use cs2_schema_declaration::{
     define_schema,
     Ptr,
     PtrCStr,
 };
 use obfstr::obfstr;
+use serde::Serialize;
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
 
 use crate::{
     CS2Handle,
+    CS2HandleState,
     Module,
     Signature,
 };
@@ -18,11 +26,34 @@ pub struct ConVars {
 }
 
 define_schema! {
+    /* Value storage of a convar (CVValue_t). Which field is valid depends on the convars type. */
+    pub struct ConVarValueData[0x10] {
+        pub value_bool: bool = 0x00,
+        pub value_i16: i16 = 0x00,
+        pub value_u16: u16 = 0x00,
+        pub value_i32: i32 = 0x00,
+        pub value_u32: u32 = 0x00,
+        pub value_i64: i64 = 0x00,
+        pub value_u64: u64 = 0x00,
+        pub value_f32: f32 = 0x00,
+        pub value_f64: f64 = 0x00,
+        pub value_string: PtrCStr = 0x00,
+        pub value_color: [u8; 4] = 0x00,
+        pub value_vector: [f32; 4] = 0x00,
+    }
+
     pub struct ConVar[0x50] {
         pub name: PtrCStr = 0x00,
+        pub default_value: Ptr<ConVarValueData> = 0x08,
+        pub min_value: Ptr<ConVarValueData> = 0x10,
+        pub max_value: Ptr<ConVarValueData> = 0x18,
         pub description: PtrCStr = 0x20,
+        pub value_type: u16 = 0x28,
 
         pub n_change_count: u32 = 0x2C,
+        pub flags: u64 = 0x30,
+
+        pub value: ConVarValueData = 0x40,
 
         pub n_value: u32 = 0x40,
         pub n_value_min: u32 = 0x48,
@@ -44,17 +75,191 @@ define_schema! {
     }
 }
 
+/// Value type of a convar (EConVarType)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ConVarType {
+    Bool,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Int64,
+    UInt64,
+    Float32,
+    Float64,
+    String,
+    Color,
+    Vector2,
+    Vector3,
+    Vector4,
+    QAngle,
+    Unknown(u16),
+}
+
+impl ConVarType {
+    pub fn from_id(value: u16) -> Self {
+        match value {
+            0 => Self::Bool,
+            1 => Self::Int16,
+            2 => Self::UInt16,
+            3 => Self::Int32,
+            4 => Self::UInt32,
+            5 => Self::Int64,
+            6 => Self::UInt64,
+            7 => Self::Float32,
+            8 => Self::Float64,
+            9 => Self::String,
+            10 => Self::Color,
+            11 => Self::Vector2,
+            12 => Self::Vector3,
+            13 => Self::Vector4,
+            14 => Self::QAngle,
+            value => Self::Unknown(value),
+        }
+    }
+}
+
+/// Decoded value of a convar
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ConVarValue {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    String(String),
+    Color([u8; 4]),
+    Vector(Vec<f32>),
+}
+
+impl ConVarValue {
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            Self::Bool(value) => Some(if *value { 1.0 } else { 0.0 }),
+            Self::Int(value) => Some(*value as f32),
+            Self::UInt(value) => Some(*value as f32),
+            Self::Float(value) => Some(*value as f32),
+            Self::String(value) => value.trim().parse().ok(),
+            Self::Color(_) | Self::Vector(_) => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            Self::Int(value) => Some(*value != 0),
+            Self::UInt(value) => Some(*value != 0),
+            _ => None,
+        }
+    }
+}
+
+impl ConVarValueData {
+    pub fn decode(&self, value_type: ConVarType) -> anyhow::Result<ConVarValue> {
+        Ok(match value_type {
+            ConVarType::Bool => ConVarValue::Bool(self.value_bool()?),
+            ConVarType::Int16 => ConVarValue::Int(self.value_i16()? as i64),
+            ConVarType::UInt16 => ConVarValue::UInt(self.value_u16()? as u64),
+            ConVarType::Int32 => ConVarValue::Int(self.value_i32()? as i64),
+            ConVarType::UInt32 => ConVarValue::UInt(self.value_u32()? as u64),
+            ConVarType::Int64 => ConVarValue::Int(self.value_i64()?),
+            ConVarType::UInt64 => ConVarValue::UInt(self.value_u64()?),
+            ConVarType::Float32 => ConVarValue::Float(self.value_f32()? as f64),
+            ConVarType::Float64 => ConVarValue::Float(self.value_f64()?),
+            ConVarType::String => {
+                ConVarValue::String(self.value_string()?.try_read_string()?.unwrap_or_default())
+            }
+            ConVarType::Color => ConVarValue::Color(self.value_color()?),
+            ConVarType::Vector2 => ConVarValue::Vector(self.value_vector()?[0..2].to_vec()),
+            ConVarType::Vector3 | ConVarType::QAngle => {
+                ConVarValue::Vector(self.value_vector()?[0..3].to_vec())
+            }
+            ConVarType::Vector4 => ConVarValue::Vector(self.value_vector()?.to_vec()),
+            ConVarType::Unknown(value) => anyhow::bail!("unknown convar type {}", value),
+        })
+    }
+}
+
+/// All information about a convar
+#[derive(Debug, Clone, Serialize)]
+pub struct ConVarInfo {
+    pub name: String,
+    pub description: String,
+    pub flags: u64,
+    pub value_type: ConVarType,
+
+    pub value: Option<ConVarValue>,
+    pub default_value: Option<ConVarValue>,
+    pub min_value: Option<ConVarValue>,
+    pub max_value: Option<ConVarValue>,
+}
+
+fn decode_value_ptr(
+    value: &Ptr<ConVarValueData>,
+    value_type: ConVarType,
+) -> anyhow::Result<Option<ConVarValue>> {
+    value
+        .try_read_schema()?
+        .map(|value| value.decode(value_type))
+        .transpose()
+}
+
+impl ConVar {
+    pub fn con_var_type(&self) -> anyhow::Result<ConVarType> {
+        Ok(ConVarType::from_id(self.value_type()?))
+    }
+
+    /// Read and decode the current value
+    pub fn read_value(&self) -> anyhow::Result<ConVarValue> {
+        self.value()?.decode(self.con_var_type()?)
+    }
+
+    pub fn read_info(&self) -> anyhow::Result<ConVarInfo> {
+        let value_type = self.con_var_type()?;
+        Ok(ConVarInfo {
+            name: self.name()?.read_string()?,
+            description: self.description()?.try_read_string()?.unwrap_or_default(),
+            flags: self.flags()?,
+            value_type,
+
+            value: self.value()?.decode(value_type).ok(),
+            default_value: decode_value_ptr(&self.default_value()?, value_type).unwrap_or(None),
+            min_value: decode_value_ptr(&self.min_value()?, value_type).unwrap_or(None),
+            max_value: decode_value_ptr(&self.max_value()?, value_type).unwrap_or(None),
+        })
+    }
+}
+
+impl State for ConVars {
+    type Parameter = ();
+
+    fn create(states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        let cs2 = states.resolve::<CS2HandleState>(())?;
+        Self::new(cs2.handle().clone())
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+}
+
 impl ConVars {
+    /// All signatures used to access the convars
+    pub fn signatures() -> Vec<(Module, Signature)> {
+        vec![(Module::Tier0, Self::signature_ccvars())]
+    }
+
+    fn signature_ccvars() -> Signature {
+        Signature::relative_address(
+            obfstr!("CCVars"),
+            obfstr!("4C 8D 3D ? ? ? ? 0F 28"),
+            0x03,
+            0x07,
+        )
+    }
+
     pub fn new(handle: Arc<CS2Handle>) -> anyhow::Result<Self> {
-        let ccvar_instance = handle.resolve_signature(
-            Module::Tier0,
-            &Signature::relative_address(
-                obfstr!("CCVars"),
-                obfstr!("4C 8D 3D ? ? ? ? 0F 28"),
-                0x03,
-                0x07,
-            ),
-        )?;
+        let ccvar_instance = handle.resolve_signature(Module::Tier0, &Self::signature_ccvars())?;
 
         let ccvars = handle.reference_schema::<CCVar>(&[ccvar_instance])?;
         let result = Self { ccvars };
@@ -62,23 +267,307 @@ impl ConVars {
         Ok(result)
     }
 
-    pub fn find_cvar(&self, name: &str) -> anyhow::Result<Option<ConVar>> {
+    /// All registered convars with their names
+    pub fn all_cvars(&self) -> anyhow::Result<Vec<(String, ConVar)>> {
         let entry_count = self.ccvars.entries_count()? as usize;
         let entries = self.ccvars.entries()?.read_entries(entry_count)?;
+
+        let mut result = Vec::with_capacity(entries.len());
         for entry in entries {
-            let con_var = entry.con_var()?.read_schema()?;
+            let Some(con_var) = entry.con_var()?.try_read_schema()? else {
+                continue;
+            };
+
             let con_var_name = match con_var.name()?.read_string() {
                 Ok(name) => name,
                 Err(_) => continue,
             };
 
-            if con_var_name != name {
-                continue;
+            result.push((con_var_name, con_var));
+        }
+
+        Ok(result)
+    }
+
+    /// Read the information of all registered convars.
+    /// Convars which could not be read will be skipped.
+    pub fn read_all(&self) -> anyhow::Result<Vec<ConVarInfo>> {
+        let mut result = Vec::new();
+        for (name, con_var) in self.all_cvars()? {
+            match con_var.read_info() {
+                Ok(info) => result.push(info),
+                Err(error) => log::warn!("Failed to read convar {}: {:#}", name, error),
+            }
+        }
+
+        result.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(result)
+    }
+
+    pub fn find_cvar(&self, name: &str) -> anyhow::Result<Option<ConVar>> {
+        Ok(self
+            .all_cvars()?
+            .into_iter()
+            .find(|(con_var_name, _)| con_var_name == name)
+            .map(|(_, con_var)| con_var))
+    }
+
+    /// Find multiple convars with one lookup.
+    /// The result has the same order as the requested names.
+    pub fn find_cvars(&self, names: &[&str]) -> anyhow::Result<Vec<Option<ConVar>>> {
+        let mut result = vec![None; names.len()];
+        for (con_var_name, con_var) in self.all_cvars()? {
+            if let Some(index) = names.iter().position(|name| *name == con_var_name) {
+                result[index] = Some(con_var);
             }
+        }
+
+        Ok(result)
+    }
+}
+
+/// Field of view (in degrees) of the game unless changed by `fov_cs_debug`
+pub const GAME_DEFAULT_FOV: f32 = 90.0;
+
+/// The FOV can only be changed with the cheat protected `fov_cs_debug`.
+/// The convar is zero if unset and has no effect while `sv_cheats` is disabled.
+fn effective_fov(fov_cs_debug: Option<f32>, sv_cheats: Option<f32>) -> f32 {
+    match (fov_cs_debug, sv_cheats) {
+        (Some(fov), Some(sv_cheats)) if fov > 0.0 && sv_cheats != 0.0 => fov,
+        _ => GAME_DEFAULT_FOV,
+    }
+}
+
+/// Game settings which are relevant for the overlay, read from the games convars
+pub struct GameSettings {
+    con_vars: [Option<ConVar>; 5],
+    con_var_language: Option<ConVar>,
 
-            return Ok(Some(con_var));
+    /// Mouse sensitivity (`sensitivity`)
+    pub sensitivity: Option<f32>,
+    /// Degrees per mouse count (`m_yaw`)
+    pub m_yaw: Option<f32>,
+    /// HUD scale (`hud_scaling`)
+    pub hud_scale: Option<f32>,
+    /// Field of view in degrees (`fov_cs_debug`).
+    /// Falls back to GAME_DEFAULT_FOV if the convar is missing, unset or locked.
+    pub fov: f32,
+    /// Game language (`cl_language`, e.g. english)
+    pub language: Option<String>,
+}
+
+impl GameSettings {
+    /// Mouse counts required for one degree of rotation
+    pub fn mouse_counts_per_degree(&self) -> Option<f32> {
+        let degrees_per_count = self.sensitivity? * self.m_yaw.unwrap_or(0.022);
+        if degrees_per_count > 0.0 {
+            Some(1.0 / degrees_per_count)
+        } else {
+            None
+        }
+    }
+}
+
+impl State for GameSettings {
+    type Parameter = ();
+
+    fn create(states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        let con_vars = states.resolve::<ConVars>(())?;
+        let names = [
+            obfstr!("sensitivity").to_string(),
+            obfstr!("m_yaw").to_string(),
+            obfstr!("hud_scaling").to_string(),
+            obfstr!("fov_cs_debug").to_string(),
+            obfstr!("sv_cheats").to_string(),
+        ];
+        let names = names.iter().map(String::as_str).collect::<Vec<_>>();
+        let con_var_language = con_vars.find_cvar(obfstr!("cl_language"))?;
+        if con_var_language.is_none() {
+            log::warn!("Missing convar cl_language");
         }
 
-        Ok(None)
+        let con_vars = con_vars.find_cvars(&names)?;
+        for (name, con_var) in names.iter().zip(con_vars.iter()) {
+            if con_var.is_none() {
+                log::warn!("Missing convar {}", name);
+            }
+        }
+
+        Ok(Self {
+            con_vars: con_vars
+                .try_into()
+                .ok()
+                .context("unexpected convar count")?,
+            con_var_language,
+
+            sensitivity: None,
+            m_yaw: None,
+            hud_scale: None,
+            fov: GAME_DEFAULT_FOV,
+            language: None,
+        })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+
+    fn update(&mut self, _states: &StateRegistry) -> anyhow::Result<()> {
+        let [sensitivity, m_yaw, hud_scale, fov_cs_debug, sv_cheats] =
+            self.con_vars.clone().map(|con_var| {
+                con_var
+                    .and_then(|con_var| con_var.read_value().ok())
+                    .and_then(|value| value.as_f32())
+            });
+
+        self.sensitivity = sensitivity;
+        self.m_yaw = m_yaw;
+        self.hud_scale = hud_scale;
+        self.fov = effective_fov(fov_cs_debug, sv_cheats);
+        self.language = match self
+            .con_var_language
+            .as_ref()
+            .and_then(|con_var| con_var.read_value().ok())
+        {
+            Some(ConVarValue::String(language)) if !language.is_empty() => Some(language),
+            _ => None,
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::{
+        effective_fov,
+        ConVar,
+        ConVarType,
+        ConVarValue,
+        ConVarValueData,
+        GAME_DEFAULT_FOV,
+    };
+    use crate::{
+        CS2Handle,
+        MemorySnapshotBackend,
+    };
+
+    const CONVAR_ADDRESS: u64 = 0x1000;
+    const STRING_ADDRESS: u64 = 0x2000;
+    const DEFAULT_VALUE_ADDRESS: u64 = 0x3000;
+
+    fn write_u64(buffer: &mut [u8], offset: usize, value: u64) {
+        buffer[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn create_handle(value_type: u16, value: &[u8]) -> anyhow::Result<Arc<CS2Handle>> {
+        let mut con_var = vec![0u8; 0x58];
+        write_u64(&mut con_var, 0x00, STRING_ADDRESS);
+        write_u64(&mut con_var, 0x08, DEFAULT_VALUE_ADDRESS);
+        con_var[0x28..0x2A].copy_from_slice(&value_type.to_le_bytes());
+        write_u64(&mut con_var, 0x30, 0x0000_0100_0000_0080);
+        con_var[0x40..0x40 + value.len()].copy_from_slice(value);
+
+        let mut strings = vec![0u8; 0x100];
+        strings[0..12].copy_from_slice(b"sensitivity\0");
+        strings[0x80..0x88].copy_from_slice(b"english\0");
+
+        let mut default_value = vec![0u8; 0x10];
+        default_value[0..4].copy_from_slice(&1.25f32.to_le_bytes());
+
+        let mut backend = MemorySnapshotBackend::new(1);
+        backend.add_region(CONVAR_ADDRESS, con_var)?;
+        backend.add_region(STRING_ADDRESS, strings)?;
+        backend.add_region(DEFAULT_VALUE_ADDRESS, default_value)?;
+        CS2Handle::create_with_backend(Box::new(backend), false)
+    }
+
+    fn decode(cs2: &CS2Handle, value_type: ConVarType) -> anyhow::Result<ConVarValue> {
+        cs2.read_schema::<ConVarValueData>(&[CONVAR_ADDRESS + 0x40])?
+            .decode(value_type)
+    }
+
+    #[test]
+    fn test_con_var_offsets() -> anyhow::Result<()> {
+        let cs2 = create_handle(7, &2.5f32.to_le_bytes())?;
+        let con_var = cs2.reference_schema::<ConVar>(&[CONVAR_ADDRESS])?;
+
+        assert_eq!(con_var.value_type()?, 7);
+        assert_eq!(con_var.con_var_type()?, ConVarType::Float32);
+        assert_eq!(con_var.flags()?, 0x0000_0100_0000_0080);
+        assert_eq!(con_var.read_value()?, ConVarValue::Float(2.5));
+
+        let info = con_var.read_info()?;
+        assert_eq!(info.name, "sensitivity");
+        assert_eq!(info.description, "");
+        assert_eq!(info.value, Some(ConVarValue::Float(2.5)));
+        assert_eq!(info.default_value, Some(ConVarValue::Float(1.25)));
+        assert_eq!(info.min_value, None);
+        assert_eq!(info.max_value, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_value_decoding() -> anyhow::Result<()> {
+        let cs2 = create_handle(1, &(-2i16).to_le_bytes())?;
+        assert_eq!(decode(&cs2, ConVarType::Int16)?, ConVarValue::Int(-2));
+        assert_eq!(decode(&cs2, ConVarType::UInt16)?, ConVarValue::UInt(0xFFFE));
+        assert_eq!(decode(&cs2, ConVarType::Bool)?, ConVarValue::Bool(true));
+
+        let cs2 = create_handle(
+            12,
+            &[1.0f32, 2.0, 3.0, 4.0]
+                .iter()
+                .flat_map(|value| value.to_le_bytes())
+                .collect::<Vec<_>>(),
+        )?;
+        assert_eq!(
+            decode(&cs2, ConVarType::Vector2)?,
+            ConVarValue::Vector(vec![1.0, 2.0])
+        );
+        assert_eq!(
+            decode(&cs2, ConVarType::QAngle)?,
+            ConVarValue::Vector(vec![1.0, 2.0, 3.0])
+        );
+        assert_eq!(
+            decode(&cs2, ConVarType::Vector4)?,
+            ConVarValue::Vector(vec![1.0, 2.0, 3.0, 4.0])
+        );
+
+        let cs2 = create_handle(9, &(STRING_ADDRESS + 0x80).to_le_bytes())?;
+        assert_eq!(
+            decode(&cs2, ConVarType::String)?,
+            ConVarValue::String("english".to_string())
+        );
+
+        assert!(decode(&cs2, ConVarType::from_id(0x42)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_value_conversion() {
+        assert_eq!(ConVarType::from_id(7), ConVarType::Float32);
+        assert_eq!(ConVarType::from_id(14), ConVarType::QAngle);
+        assert_eq!(ConVarType::from_id(99), ConVarType::Unknown(99));
+
+        assert_eq!(ConVarValue::Int(-3).as_f32(), Some(-3.0));
+        assert_eq!(ConVarValue::String(" 1.5 ".to_string()).as_f32(), Some(1.5));
+        assert_eq!(ConVarValue::Vector(vec![1.0]).as_f32(), None);
+        assert_eq!(ConVarValue::UInt(0).as_bool(), Some(false));
+        assert_eq!(ConVarValue::Float(1.0).as_bool(), None);
+    }
+
+    #[test]
+    fn test_effective_fov() {
+        assert_eq!(effective_fov(Some(110.0), Some(1.0)), 110.0);
+
+        /* locked */
+        assert_eq!(effective_fov(Some(110.0), Some(0.0)), GAME_DEFAULT_FOV);
+        assert_eq!(effective_fov(Some(110.0), None), GAME_DEFAULT_FOV);
+
+        /* unset or missing */
+        assert_eq!(effective_fov(Some(0.0), Some(1.0)), GAME_DEFAULT_FOV);
+        assert_eq!(effective_fov(None, Some(1.0)), GAME_DEFAULT_FOV);
     }
 }
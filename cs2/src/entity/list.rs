@@ -45,11 +45,21 @@ impl State for EntityList {
         self.handle_lookup.clear();
 
         let outer_list = cs2.read_schema::<OuterEntityList>(&[offsets.global_entity_list, 0x00])?;
+
+        /* read all inner lists at once */
+        let mut batch = cs2.create_memory_batch();
+        let mut bulk_slots = Vec::with_capacity(outer_list.len());
         for (bulk_index, bulk) in outer_list.into_iter().enumerate() {
-            let list = match bulk.try_read_schema()? {
-                Some(list) => list,
-                None => continue,
-            };
+            if bulk.is_null()? {
+                continue;
+            }
+
+            bulk_slots.push((bulk_index, bulk.plan_read_schema(&mut batch)?));
+        }
+
+        let batch = batch.execute()?;
+        for (bulk_index, bulk_slot) in bulk_slots {
+            let list = batch.read_schema(&bulk_slot)?;
 
             for (entry_index, entry) in list.into_iter().enumerate() {
                 let entity_index = ((bulk_index << 9) | entry_index) as u32;
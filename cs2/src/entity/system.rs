@@ -61,6 +61,7 @@ impl<T> DerefMut for TypedEntityIdentity<T> {
 }
 
 /// Helper class for CS2 global entity system
+#[derive(Clone)]
 pub struct EntitySystem {
     entity_list: EntityList,
     local_player: Ptr<CCSPlayerController>,
@@ -2,19 +2,23 @@
 
 use std::{
     any::Any,
+    collections::BTreeMap,
     ffi::CStr,
     fmt::Debug,
     ops::Deref,
     sync::{
         Arc,
+        Mutex,
         Weak,
     },
 };
 
 use anyhow::Context;
 use cs2_schema_declaration::{
+    BatchReadEntry,
     MemoryDriver,
     MemoryHandle,
+    MemoryReadBatch,
     SchemaValue,
 };
 use obfstr::obfstr;
@@ -24,14 +28,15 @@ use utils_state::{
     StateRegistry,
 };
 use valthrun_kernel_interface::{
-    IoctrlDriverInterface,
-    KernelInterface,
     KeyboardState,
-    ModuleInfo,
     MouseState,
 };
 
 use crate::{
+    KernelMemoryBackend,
+    MemoryBackend,
+    PageCache,
+    ProcessModule,
     Signature,
     SignatureType,
 };
@@ -47,6 +52,11 @@ impl MemoryDriver for CSMemoryDriver {
         cs2.read_slice(&[address], slice)
     }
 
+    fn read_batch(&self, entries: &mut [BatchReadEntry]) -> anyhow::Result<()> {
+        let cs2 = self.0.upgrade().context("cs2 handle has been dropped")?;
+        cs2.read_batch(entries)
+    }
+
     fn read_cstring(
         &self,
         address: u64,
@@ -67,7 +77,14 @@ pub enum Module {
 }
 
 impl Module {
-    fn get_module_name<'a>(&self) -> &'static str {
+    pub const ALL: [Module; 4] = [
+        Module::Client,
+        Module::Engine,
+        Module::Schemasystem,
+        Module::Tier0,
+    ];
+
+    pub fn get_module_name<'a>(&self) -> &'static str {
         match self {
             Module::Client => "client.dll",
             Module::Engine => "engine2.dll",
@@ -82,28 +99,30 @@ pub struct CS2Handle {
     weak_self: Weak<Self>,
     metrics: bool,
 
-    modules: Vec<ModuleInfo>,
+    modules: Vec<ProcessModule>,
     process_id: i32,
 
-    pub ke_interface: KernelInterface,
+    backend: Box<dyn MemoryBackend>,
+    page_cache: PageCache,
+
+    /// Instruction offsets (relative to the module base) of resolved signatures
+    signature_cache: Mutex<BTreeMap<String, u64>>,
 }
 
 impl CS2Handle {
+    /// Create a new handle using the kernel driver as memory backend.
     pub fn create(metrics: bool) -> anyhow::Result<Arc<Self>> {
-        let interface = Box::new(IoctrlDriverInterface::create(obfstr!(
-            "\\\\.\\GLOBALROOT\\Device\\valthrun"
-        ))?);
-        let interface = KernelInterface::create(interface)?;
-
-        /*
-         * Please no not analyze me:
-         * https://www.unknowncheats.me/wiki/Valve_Anti-Cheat:VAC_external_tool_detection_(and_more)
-         *
-         * Even tough we don't have open handles to CS2 we don't want anybody to read our process.
-         */
-        interface.toggle_process_protection(true)?;
-
-        let (process_id, modules) = interface.request_cs2_modules()?;
+        let backend = KernelMemoryBackend::create()?;
+        Self::create_with_backend(Box::new(backend), metrics)
+    }
+
+    /// Create a new handle using the given memory backend.
+    pub fn create_with_backend(
+        backend: Box<dyn MemoryBackend>,
+        metrics: bool,
+    ) -> anyhow::Result<Arc<Self>> {
+        let process_id = backend.process_id();
+        let modules = backend.modules()?;
         log::debug!(
             "{}. Process id {}",
             obfstr!("Successfully initialized CS2 handle"),
@@ -114,7 +133,7 @@ impl CS2Handle {
         for module in modules.iter() {
             log::trace!(
                 "  - {} ({:X} - {:X})",
-                module.base_dll_name(),
+                module.name,
                 module.base_address,
                 module.base_address + module.module_size
             );
@@ -126,28 +145,44 @@ impl CS2Handle {
             modules,
             process_id,
 
-            ke_interface: interface,
+            backend,
+            page_cache: PageCache::new(0),
+
+            signature_cache: Default::default(),
         }))
     }
 
-    fn get_module_info(&self, target: Module) -> Option<&ModuleInfo> {
+    fn get_module_info(&self, target: Module) -> Option<&ProcessModule> {
         self.modules
             .iter()
-            .find(|module| module.base_dll_name() == target.get_module_name())
+            .find(|module| module.name == target.get_module_name())
     }
 
     pub fn process_id(&self) -> i32 {
         self.process_id
     }
 
+    pub fn backend(&self) -> &dyn MemoryBackend {
+        &*self.backend
+    }
+
+    /// Page cache for single address reads.
+    /// The cache is disabled by default and must be invalidated every frame once enabled.
+    pub fn page_cache(&self) -> &PageCache {
+        &self.page_cache
+    }
+
+    /// Total amount of read calls issued to the memory backend
+    pub fn total_read_calls(&self) -> usize {
+        self.backend.total_read_calls()
+    }
+
     pub fn send_keyboard_state(&self, states: &[KeyboardState]) -> anyhow::Result<()> {
-        self.ke_interface.send_keyboard_state(states)?;
-        Ok(())
+        self.backend.send_keyboard_state(states)
     }
 
     pub fn send_mouse_state(&self, states: &[MouseState]) -> anyhow::Result<()> {
-        self.ke_interface.send_mouse_state(states)?;
-        Ok(())
+        self.backend.send_mouse_state(states)
     }
 
     pub fn add_metrics_record(&self, record_type: &str, record_payload: &str) {
@@ -156,19 +191,56 @@ impl CS2Handle {
             return;
         }
 
-        let _ = self
-            .ke_interface
-            .add_metrics_record(record_type, record_payload);
+        let _ = self.backend.add_metrics_record(record_type, record_payload);
+    }
+
+    /// Hash of the modules PE headers and size.
+    /// Changes whenever the module gets updated.
+    pub fn module_fingerprint(&self, module: Module) -> anyhow::Result<u64> {
+        let module_info = self.get_module_info(module).context("invalid module")?;
+
+        let mut headers = vec![0u8; 0x1000.min(module_info.module_size as usize)];
+        self.backend
+            .read_slice(&[module_info.base_address], &mut headers)
+            .context("read module headers")?;
+
+        /* FNV-1a as the hash needs to be stable across builds */
+        let mut hash = 0xCBF29CE484222325u64;
+        for byte in headers
+            .iter()
+            .chain(module_info.module_size.to_le_bytes().iter())
+        {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001B3);
+        }
+
+        Ok(hash)
+    }
+
+    /// Resolved signatures by their module and name.
+    /// The values are the instruction offsets relative to the module base.
+    pub fn signature_cache(&self) -> BTreeMap<String, u64> {
+        self.signature_cache.lock().unwrap().clone()
+    }
+
+    /// Preload resolved signatures (e.g. from a previous run).
+    /// Cached entries will be verified before use.
+    pub fn load_signature_cache(&self, entries: BTreeMap<String, u64>) {
+        self.signature_cache.lock().unwrap().extend(entries);
+    }
+
+    fn is_pattern_at(&self, address: u64, signature: &Signature) -> bool {
+        let mut buffer = vec![0u8; signature.pattern.length()];
+        self.backend.read_slice(&[address], &mut buffer).is_ok()
+            && signature.pattern.is_matching(&buffer)
     }
 
     pub fn module_address(&self, module: Module, address: u64) -> Option<u64> {
         let module = self.get_module_info(module)?;
-        if (address as usize) < module.base_address
-            || (address as usize) >= (module.base_address + module.module_size)
-        {
-            None
+        if module.contains_address(address) {
+            Some(address - module.base_address)
         } else {
-            Some(address - module.base_address as u64)
+            None
         }
     }
 
@@ -176,18 +248,40 @@ impl CS2Handle {
         Ok(self
             .get_module_info(module)
             .context("invalid module")?
-            .base_address as u64
+            .base_address
             + offset)
     }
 
     pub fn read_sized<T: Copy>(&self, offsets: &[u64]) -> anyhow::Result<T> {
-        Ok(self.ke_interface.read(self.process_id, offsets)?)
+        let mut result = unsafe { std::mem::zeroed::<T>() };
+        self.read_slice(offsets, std::slice::from_mut(&mut result))?;
+        Ok(result)
     }
 
     pub fn read_slice<T: Copy>(&self, offsets: &[u64], buffer: &mut [T]) -> anyhow::Result<()> {
-        Ok(self
-            .ke_interface
-            .read_slice(self.process_id, offsets, buffer)?)
+        let buffer = unsafe {
+            /*
+             * Safety:
+             * T is Copy and the byte slice covers exactly the memory of the target buffer.
+             */
+            std::slice::from_raw_parts_mut(
+                buffer.as_mut_ptr() as *mut u8,
+                std::mem::size_of_val(buffer),
+            )
+        };
+
+        if let [address] = offsets {
+            self.page_cache.read(&*self.backend, *address, buffer)
+        } else {
+            self.backend.read_slice(offsets, buffer)
+        }
+    }
+
+    /// Execute multiple reads at once.
+    /// Use `create_memory_batch` to plan batched schema reads.
+    /// Batched reads are not served by the page cache.
+    pub fn read_batch(&self, entries: &mut [BatchReadEntry]) -> anyhow::Result<()> {
+        self.backend.read_batch(entries)
     }
 
     pub fn read_string(
@@ -216,6 +310,11 @@ impl CS2Handle {
         Arc::new(CSMemoryDriver(self.weak_self.clone())) as Arc<(dyn MemoryDriver + 'static)>
     }
 
+    /// Create a new batch to read multiple schema values with one backend call.
+    pub fn create_memory_batch(&self) -> MemoryReadBatch {
+        MemoryReadBatch::new(&self.create_memory_driver())
+    }
+
     /// Read the whole schema class and return a wrapper around the data.
     pub fn read_schema<T: SchemaValue>(&self, offsets: &[u64]) -> anyhow::Result<T> {
         let address = if offsets.len() == 1 {
@@ -254,21 +353,48 @@ impl CS2Handle {
         log::trace!("Resolving '{}' in {:?}", signature.debug_name, module);
         let module_info = self.get_module_info(module).context("invalid module")?;
 
-        let inst_offset = self
-            .ke_interface
-            .find_pattern(
-                self.process_id,
-                module_info.base_address as u64,
-                module_info.module_size,
-                &*signature.pattern,
-            )?
-            .with_context(|| {
-                format!(
-                    "{} {}",
-                    obfstr!("failed to find pattern"),
+        let cache_key = format!("{}:{}", module.get_module_name(), signature.debug_name);
+        let cached_offset = self
+            .signature_cache
+            .lock()
+            .unwrap()
+            .get(&cache_key)
+            .cloned();
+        let cached_inst_offset = cached_offset
+            .map(|offset| module_info.base_address + offset)
+            .filter(|address| self.is_pattern_at(*address, signature));
+
+        let inst_offset = if let Some(inst_offset) = cached_inst_offset {
+            inst_offset
+        } else {
+            if cached_offset.is_some() {
+                log::debug!(
+                    "Cached signature {} does not match. Scanning again.",
                     signature.debug_name
-                )
-            })?;
+                );
+            }
+
+            let inst_offset = self
+                .backend
+                .find_pattern(
+                    module_info.base_address,
+                    module_info.module_size as usize,
+                    &*signature.pattern,
+                )?
+                .with_context(|| {
+                    format!(
+                        "{} {}",
+                        obfstr!("failed to find pattern"),
+                        signature.debug_name
+                    )
+                })?;
+
+            self.signature_cache
+                .lock()
+                .unwrap()
+                .insert(cache_key, inst_offset - module_info.base_address);
+            inst_offset
+        };
 
         let value = self.reference_schema::<u32>(&[inst_offset + signature.offset])? as u64;
         let value = match &signature.value_type {
@@ -1,6 +1,12 @@
+mod backend;
+pub use backend::*;
+
 mod handle;
 pub use handle::*;
 
+mod page_cache;
+pub use page_cache::*;
+
 mod entity;
 pub use entity::*;
 
@@ -10,6 +16,9 @@ pub use offsets::*;
 pub mod offsets_manual;
 pub mod offsets_runtime;
 
+mod offsets_cache;
+pub use offsets_cache::*;
+
 mod build;
 pub use build::*;
 
@@ -25,6 +34,12 @@ pub use globals::*;
 mod signature;
 pub use signature::*;
 
+mod signature_validation;
+pub use signature_validation::*;
+
+mod pe;
+pub use pe::*;
+
 mod convar;
 pub use convar::*;
 
@@ -39,3 +54,6 @@ pub use class_name_cache::*;
 
 mod state;
 pub use state::*;
+
+mod replay;
+pub use replay::*;
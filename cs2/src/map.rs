@@ -3,6 +3,10 @@ use cs2_schema_declaration::{
     Ptr,
     PtrCStr,
 };
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use utils_state::{
     State,
     StateCacheType,
@@ -22,6 +26,7 @@ define_schema! {
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct CurrentMapState {
     pub current_map: Option<String>,
 }
@@ -64,76 +64,106 @@ impl State for CS2Offsets {
 }
 
 impl CS2Offsets {
-    fn find_globals(cs2: &CS2Handle) -> anyhow::Result<u64> {
-        cs2.resolve_signature(
-            Module::Client,
-            &Signature::relative_address(
-                obfstr!("client globals"),
-                obfstr!("48 89 15 ?? ?? ?? ?? 48 8D 05 ?? ?? ?? ?? 48 85 D2"),
-                0x03,
-                0x07,
+    /// All signatures used to resolve the offsets
+    pub fn signatures() -> Vec<(Module, Signature)> {
+        vec![
+            (Module::Client, Self::signature_globals()),
+            (
+                Module::Client,
+                Self::signature_local_player_controller_ptr(),
             ),
+            (Module::Client, Self::signature_entity_list()),
+            (Module::Client, Self::signature_view_matrix()),
+            (Module::Client, Self::signature_offset_crosshair_id()),
+            (
+                Module::Engine,
+                Self::signature_network_game_client_instance(),
+            ),
+        ]
+    }
+
+    fn signature_globals() -> Signature {
+        Signature::relative_address(
+            obfstr!("client globals"),
+            obfstr!("48 89 15 ?? ?? ?? ?? 48 8D 05 ?? ?? ?? ?? 48 85 D2"),
+            0x03,
+            0x07,
         )
     }
 
-    fn find_local_player_controller_ptr(cs2: &CS2Handle) -> anyhow::Result<u64> {
+    fn find_globals(cs2: &CS2Handle) -> anyhow::Result<u64> {
+        cs2.resolve_signature(Module::Client, &Self::signature_globals())
+    }
+
+    fn signature_local_player_controller_ptr() -> Signature {
         // 48 83 3D ? ? ? ? ? 0F 95 -> IsLocalPlayerControllerValid
+        Signature::relative_address(
+            obfstr!("local player controller ptr"),
+            obfstr!("48 83 3D ? ? ? ? ? 0F 95"),
+            0x03,
+            0x08,
+        )
+    }
+
+    fn find_local_player_controller_ptr(cs2: &CS2Handle) -> anyhow::Result<u64> {
         cs2.resolve_signature(
             Module::Client,
-            &Signature::relative_address(
-                obfstr!("local player controller ptr"),
-                obfstr!("48 83 3D ? ? ? ? ? 0F 95"),
-                0x03,
-                0x08,
-            ),
+            &Self::signature_local_player_controller_ptr(),
         )
     }
 
-    fn find_entity_list(cs2: &CS2Handle) -> anyhow::Result<u64> {
+    fn signature_entity_list() -> Signature {
         // 4C 8B 0D ? ? ? ? 48 89 5C 24 ? 8B -> Global entity list
-        cs2.resolve_signature(
-            Module::Client,
-            &Signature::relative_address(
-                obfstr!("global entity list"),
-                obfstr!("4C 8B 0D ? ? ? ? 48 89 5C 24 ? 8B"),
-                0x03,
-                0x07,
-            ),
+        Signature::relative_address(
+            obfstr!("global entity list"),
+            obfstr!("4C 8B 0D ? ? ? ? 48 89 5C 24 ? 8B"),
+            0x03,
+            0x07,
+        )
+    }
+
+    fn find_entity_list(cs2: &CS2Handle) -> anyhow::Result<u64> {
+        cs2.resolve_signature(Module::Client, &Self::signature_entity_list())
+    }
+
+    fn signature_view_matrix() -> Signature {
+        Signature::relative_address(
+            obfstr!("world view matrix"),
+            obfstr!("48 8D 0D ? ? ? ? 48 C1 E0 06"),
+            0x03,
+            0x07,
         )
     }
 
     fn find_view_matrix(cs2: &CS2Handle) -> anyhow::Result<u64> {
-        cs2.resolve_signature(
-            Module::Client,
-            &Signature::relative_address(
-                obfstr!("world view matrix"),
-                obfstr!("48 8D 0D ? ? ? ? 48 C1 E0 06"),
-                0x03,
-                0x07,
-            ),
+        cs2.resolve_signature(Module::Client, &Self::signature_view_matrix())
+    }
+
+    fn signature_offset_crosshair_id() -> Signature {
+        Signature::offset(
+            obfstr!("C_CSPlayerPawn crosshair id"),
+            obfstr!("41 89 86 ? ? ? ? 41 89 86"),
+            0x03,
         )
     }
 
     fn find_offset_crosshair_id(cs2: &CS2Handle) -> anyhow::Result<u64> {
-        cs2.resolve_signature(
-            Module::Client,
-            &Signature::offset(
-                obfstr!("C_CSPlayerPawn crosshair id"),
-                obfstr!("41 89 86 ? ? ? ? 41 89 86"),
-                0x03,
-            ),
+        cs2.resolve_signature(Module::Client, &Self::signature_offset_crosshair_id())
+    }
+
+    fn signature_network_game_client_instance() -> Signature {
+        Signature::relative_address(
+            obfstr!("network game client instance"),
+            obfstr!("48 83 3D ? ? ? ? ? 48 8B D9 8B 0D"),
+            0x03,
+            0x08,
         )
     }
 
     fn find_network_game_client_instance(cs2: &CS2Handle) -> anyhow::Result<u64> {
         cs2.resolve_signature(
             Module::Engine,
-            &Signature::relative_address(
-                obfstr!("network game client instance"),
-                obfstr!("48 83 3D ? ? ? ? ? 48 8B D9 8B 0D"),
-                0x03,
-                0x08,
-            ),
+            &Self::signature_network_game_client_instance(),
         )
     }
 }
@@ -0,0 +1,162 @@
+use std::{
+    collections::BTreeMap,
+    fs::{
+        self,
+        File,
+    },
+    io::{
+        BufReader,
+        BufWriter,
+        Write,
+    },
+    path::Path,
+};
+
+use anyhow::Context;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    CS2Handle,
+    Module,
+};
+
+/// Identifies the game build the cached offsets belong to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OffsetCacheKey {
+    pub revision: String,
+
+    /// Fingerprints of all modules by their name
+    pub module_hashes: BTreeMap<String, u64>,
+}
+
+impl OffsetCacheKey {
+    pub fn create(cs2: &CS2Handle, revision: &str) -> anyhow::Result<Self> {
+        let mut module_hashes = BTreeMap::new();
+        for module in Module::ALL {
+            module_hashes.insert(
+                module.get_module_name().to_string(),
+                cs2.module_fingerprint(module)
+                    .with_context(|| format!("fingerprint {}", module.get_module_name()))?,
+            );
+        }
+
+        Ok(Self {
+            revision: revision.to_string(),
+            module_hashes,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedSchemaOffset {
+    pub module: String,
+    pub class: String,
+    pub member: String,
+    pub offset: u32,
+}
+
+/// Resolved schema offsets and signatures of a specific game build.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OffsetCache {
+    pub key: OffsetCacheKey,
+
+    pub schema_offsets: Vec<CachedSchemaOffset>,
+
+    /// Signature instruction offsets (see `CS2Handle::signature_cache`)
+    pub signatures: BTreeMap<String, u64>,
+
+    /// The cache content has been resolved again and needs to be written
+    #[serde(skip)]
+    pub(crate) outdated: bool,
+}
+
+impl OffsetCache {
+    pub fn new(key: OffsetCacheKey) -> Self {
+        Self {
+            key,
+            schema_offsets: Default::default(),
+            signatures: Default::default(),
+            outdated: true,
+        }
+    }
+
+    /// Load the offset cache from disk.
+    /// Returns `None` if there is no cache or the cache belongs to a different game build.
+    pub fn load(path: &Path, key: &OffsetCacheKey) -> Option<Self> {
+        if !path.is_file() {
+            return None;
+        }
+
+        let cache: Self = match File::open(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|file| Ok(serde_json::from_reader(BufReader::new(file))?))
+        {
+            Ok(cache) => cache,
+            Err(error) => {
+                log::warn!(
+                    "Failed to load offset cache {}: {:#}",
+                    path.to_string_lossy(),
+                    error
+                );
+                return None;
+            }
+        };
+
+        if cache.key != *key {
+            log::debug!(
+                "Offset cache belongs to revision {}. Ignoring it.",
+                cache.key.revision
+            );
+            return None;
+        }
+
+        Some(cache)
+    }
+
+    /// Load the offset cache for the current game build or create an empty cache.
+    /// The cached signatures will be provided to the CS2 handle.
+    pub fn load_or_create(
+        cs2: &CS2Handle,
+        revision: &str,
+        path: &Path,
+        refresh: bool,
+    ) -> anyhow::Result<Self> {
+        let key = OffsetCacheKey::create(cs2, revision)?;
+        let cache = if refresh {
+            log::info!("Refreshing offset cache");
+            None
+        } else {
+            Self::load(path, &key)
+        };
+
+        let cache = cache.unwrap_or_else(|| Self::new(key));
+        cs2.load_signature_cache(cache.signatures.clone());
+        Ok(cache)
+    }
+
+    /// Collect the resolved signatures from the CS2 handle and write the cache to disk.
+    /// The cache will only be written if the offsets or signatures have been resolved again.
+    pub fn store(&mut self, cs2: &CS2Handle, path: &Path) -> anyhow::Result<()> {
+        let signatures = cs2.signature_cache();
+        if !self.outdated && signatures == self.signatures {
+            return Ok(());
+        }
+
+        self.signatures = signatures;
+
+        let temp_path = path.with_extension("tmp");
+        {
+            let file = File::create(&temp_path).context("create offset cache")?;
+            let mut writer = BufWriter::new(file);
+            serde_json::to_writer(&mut writer, self).context("write offset cache")?;
+            writer.flush().context("write offset cache")?;
+        }
+
+        fs::rename(&temp_path, path).context("replace offset cache")?;
+        self.outdated = false;
+        Ok(())
+    }
+}
@@ -14,9 +14,14 @@ use crate::{
     CS2Handle,
     CSchemaSystem,
     CSchemaTypeDeclaredClass,
+    CachedSchemaOffset,
     Module,
+    OffsetCache,
 };
 
+/// Amount of cached offsets which will be verified against the schema system
+const CACHE_VERIFY_SAMPLE_SIZE: usize = 16;
+
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
 struct RegisteredOffset {
     module: String,
@@ -138,3 +143,147 @@ pub fn setup_provider(cs2: &Arc<CS2Handle>) -> anyhow::Result<()> {
     cs2_schema_generated::setup_runtime_offset_provider(Box::new(CS2RuntimeOffsets { offsets }));
     Ok(())
 }
+
+/// Verify a sample of the offsets against the schema system.
+/// Returns false if any of the sampled offsets does not match.
+fn verify_runtime_offsets(
+    cs2: &Arc<CS2Handle>,
+    offsets: &BTreeMap<RegisteredOffset, Offset>,
+) -> anyhow::Result<bool> {
+    /* sampled members grouped by their module and class */
+    let sample_step = (offsets.len() / CACHE_VERIFY_SAMPLE_SIZE).max(1);
+    let mut samples = BTreeMap::<&str, BTreeMap<&str, Vec<(&str, Offset)>>>::new();
+    for (registered_offset, cached_offset) in offsets.iter().step_by(sample_step) {
+        samples
+            .entry(&registered_offset.module)
+            .or_default()
+            .entry(&registered_offset.class)
+            .or_default()
+            .push((&registered_offset.member, *cached_offset));
+    }
+
+    let schema_system_address = find_schema_system(cs2)?;
+    let schema_system = cs2.reference_schema::<CSchemaSystem>(&[schema_system_address])?;
+    let scopes = schema_system.scopes()?;
+
+    let mut verified_offsets = 0;
+    for scope_index in 0..scopes.element_count()? as usize {
+        let scope = scopes.reference_element(scope_index)?.read_schema()?;
+        let scope_name = scope.scope_name()?.to_string_lossy()?;
+        let Some(mut classes) = samples.remove(scope_name.as_str()) else {
+            /* no offsets of this scope have been sampled */
+            continue;
+        };
+
+        /* resolve all sampled classes with a single walk over the scopes bindings */
+        for entry in scope.class_bindings()?.iter()? {
+            let binding = entry?.value()?.read_schema()?;
+            let Some(members) = classes.remove(binding.name()?.read_string()?.as_str()) else {
+                continue;
+            };
+
+            let fields = binding
+                .fields()?
+                .read_entries(binding.field_size()? as usize)?;
+
+            let mut field_offsets = BTreeMap::new();
+            for field in fields {
+                field_offsets.insert(field.name()?.read_string()?, field.offset()?);
+            }
+
+            for (member, cached_offset) in members {
+                let current_offset = field_offsets.get(member).cloned();
+                if current_offset != Some(cached_offset) {
+                    log::debug!(
+                        "Cached offset {}::{} changed from {:X} to {:X?}",
+                        binding.name()?.read_string()?,
+                        member,
+                        cached_offset,
+                        current_offset
+                    );
+                    return Ok(false);
+                }
+
+                verified_offsets += 1;
+            }
+
+            if classes.is_empty() {
+                break;
+            }
+        }
+
+        if let Some(class_name) = classes.keys().next() {
+            log::debug!("Cached class {} does not exist any more", class_name);
+            return Ok(false);
+        }
+    }
+
+    if let Some(module) = samples.keys().next() {
+        log::debug!("Cached scope {} does not exist any more", module);
+        return Ok(false);
+    }
+
+    Ok(verified_offsets > 0)
+}
+
+/// Setup the runtime offset provider using the cached offsets.
+/// The cached offsets will only be used if a sample of them matches the schema system.
+/// Otherwise all offsets will be loaded and the cache updated.
+pub fn setup_provider_cached(cs2: &Arc<CS2Handle>, cache: &mut OffsetCache) -> anyhow::Result<()> {
+    let cached_offsets = cache
+        .schema_offsets
+        .iter()
+        .map(|entry| {
+            (
+                RegisteredOffset {
+                    module: entry.module.clone(),
+                    class: entry.class.clone(),
+                    member: entry.member.clone(),
+                },
+                entry.offset,
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let offsets = if cached_offsets.is_empty() {
+        None
+    } else {
+        match verify_runtime_offsets(cs2, &cached_offsets) {
+            Ok(true) => Some(cached_offsets),
+            Ok(false) => {
+                log::info!("Cached schema offsets are outdated. Reloading offsets.");
+                None
+            }
+            Err(error) => {
+                log::warn!("Failed to verify cached schema offsets: {:#}", error);
+                None
+            }
+        }
+    };
+
+    let offsets = match offsets {
+        Some(offsets) => {
+            log::debug!("Using {} cached schema offsets", offsets.len());
+            offsets
+        }
+        None => {
+            let offsets = load_runtime_offsets(cs2)?;
+            log::debug!("Loaded {} schema offsets", offsets.len());
+
+            cache.outdated = true;
+            cache.schema_offsets = offsets
+                .iter()
+                .map(|(registered_offset, offset)| CachedSchemaOffset {
+                    module: registered_offset.module.clone(),
+                    class: registered_offset.class.clone(),
+                    member: registered_offset.member.clone(),
+                    offset: *offset,
+                })
+                .collect();
+            offsets
+        }
+    };
+
+    cs2_schema_generated::setup_runtime_offset_provider(Box::new(CS2RuntimeOffsets { offsets }));
+    Ok(())
+}
@@ -0,0 +1,341 @@
+use std::{
+    collections::{
+        HashMap,
+        VecDeque,
+    },
+    sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Mutex,
+    },
+};
+
+use crate::MemoryBackend;
+
+pub const PAGE_SIZE: u64 = 0x1000;
+
+/// Default page cache capacity (8 MiB)
+pub const PAGE_CACHE_DEFAULT_CAPACITY: usize = 2048;
+
+/// Reads spanning more pages will bypass the cache
+/// as they most likely are bulk reads (e.g. entity lists).
+const MAX_CACHED_READ_PAGES: u64 = 4;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PageCacheStats {
+    pub hits: usize,
+    pub misses: usize,
+    pub evictions: usize,
+
+    pub cached_pages: usize,
+    pub capacity: usize,
+}
+
+#[derive(Default)]
+struct PageCacheInner {
+    capacity: usize,
+    pages: HashMap<u64, Box<[u8]>>,
+    insert_order: VecDeque<u64>,
+
+    /// Incremented on every invalidation, so pages read before can be discarded
+    generation: u64,
+}
+
+/// Cache for 4 KiB memory pages which have been read from the memory backend.
+///
+/// The cache is intended to be scoped to one frame and must be invalidated
+/// as soon the underlying memory might have changed (e.g. when the states get invalidated).
+/// A capacity of zero disables the cache.
+pub struct PageCache {
+    inner: Mutex<PageCacheInner>,
+
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+    evictions: AtomicUsize,
+}
+
+impl PageCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(PageCacheInner {
+                capacity,
+                ..Default::default()
+            }),
+
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+            evictions: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.inner.lock().unwrap().capacity
+    }
+
+    /// Set the max amount of cached pages.
+    /// Use zero to disable the cache.
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.capacity = capacity;
+        while inner.pages.len() > capacity {
+            self.evict_page(&mut inner);
+        }
+    }
+
+    /// Remove all cached pages
+    pub fn invalidate(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.pages.clear();
+        inner.insert_order.clear();
+        inner.generation += 1;
+    }
+
+    pub fn stats(&self) -> PageCacheStats {
+        let inner = self.inner.lock().unwrap();
+        PageCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+
+            cached_pages: inner.pages.len(),
+            capacity: inner.capacity,
+        }
+    }
+
+    fn evict_page(&self, inner: &mut PageCacheInner) {
+        if let Some(page) = inner.insert_order.pop_front() {
+            inner.pages.remove(&page);
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Read memory using the cached pages.
+    /// Missing pages will be read from the backend and cached.
+    /// The cache will not be locked while reading from the backend.
+    pub fn read(
+        &self,
+        backend: &dyn MemoryBackend,
+        address: u64,
+        buffer: &mut [u8],
+    ) -> anyhow::Result<()> {
+        if buffer.is_empty() {
+            return Ok(());
+        }
+
+        let first_page = address & !(PAGE_SIZE - 1);
+        let last_page = (address + buffer.len() as u64 - 1) & !(PAGE_SIZE - 1);
+
+        let mut missing_pages = Vec::new();
+        let generation = {
+            let inner = self.inner.lock().unwrap();
+            if inner.capacity == 0 || (last_page - first_page) / PAGE_SIZE >= MAX_CACHED_READ_PAGES
+            {
+                drop(inner);
+                return backend.read_slice(&[address], buffer);
+            }
+
+            for page in (first_page..=last_page).step_by(PAGE_SIZE as usize) {
+                match inner.pages.get(&page) {
+                    Some(page_data) => {
+                        self.hits.fetch_add(1, Ordering::Relaxed);
+                        copy_from_page(address, buffer, page, page_data);
+                    }
+                    None => missing_pages.push(page),
+                }
+            }
+
+            inner.generation
+        };
+
+        if missing_pages.is_empty() {
+            return Ok(());
+        }
+
+        self.misses
+            .fetch_add(missing_pages.len(), Ordering::Relaxed);
+
+        let mut loaded_pages = Vec::with_capacity(missing_pages.len());
+        for page in missing_pages {
+            let mut page_data = vec![0u8; PAGE_SIZE as usize].into_boxed_slice();
+            if backend.read_slice(&[page], &mut page_data).is_err() {
+                /* page is not fully readable, fall back to a direct read */
+                return backend.read_slice(&[address], buffer);
+            }
+
+            copy_from_page(address, buffer, page, &page_data);
+            loaded_pages.push((page, page_data));
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        if inner.generation != generation {
+            /* the cache has been invalidated while reading */
+            return Ok(());
+        }
+
+        for (page, page_data) in loaded_pages {
+            if inner.capacity == 0 || inner.pages.contains_key(&page) {
+                /* capacity has been changed or another thread cached the page already */
+                continue;
+            }
+
+            if inner.pages.len() >= inner.capacity {
+                self.evict_page(&mut inner);
+            }
+
+            inner.pages.insert(page, page_data);
+            inner.insert_order.push_back(page);
+        }
+
+        Ok(())
+    }
+}
+
+/// Copy the part of the page which overlaps with the target buffer
+fn copy_from_page(address: u64, buffer: &mut [u8], page: u64, page_data: &[u8]) {
+    let copy_start = address.max(page);
+    let copy_end = (address + buffer.len() as u64).min(page + PAGE_SIZE);
+    buffer[(copy_start - address) as usize..(copy_end - address) as usize]
+        .copy_from_slice(&page_data[(copy_start - page) as usize..(copy_end - page) as usize]);
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        PageCache,
+        PAGE_SIZE,
+    };
+    use crate::{
+        MemoryBackend,
+        MemorySnapshotBackend,
+    };
+
+    const REGION_ADDRESS: u64 = 0x10000;
+
+    fn create_backend(pages: usize) -> anyhow::Result<MemorySnapshotBackend> {
+        let data = (0..pages * PAGE_SIZE as usize)
+            .map(|index| (index % 0xFB) as u8)
+            .collect::<Vec<_>>();
+
+        let mut backend = MemorySnapshotBackend::new(1);
+        backend.add_region(REGION_ADDRESS, data)?;
+        Ok(backend)
+    }
+
+    fn expected_value(address: u64) -> u8 {
+        ((address - REGION_ADDRESS) % 0xFB) as u8
+    }
+
+    #[test]
+    fn test_hit_and_miss() -> anyhow::Result<()> {
+        let backend = create_backend(4)?;
+        let cache = PageCache::new(16);
+
+        /* spans two pages */
+        let address = REGION_ADDRESS + PAGE_SIZE - 4;
+        let mut buffer = [0u8; 8];
+        cache.read(&backend, address, &mut buffer)?;
+        for (index, value) in buffer.iter().enumerate() {
+            assert_eq!(*value, expected_value(address + index as u64));
+        }
+
+        let stats = cache.stats();
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.hits, 0);
+        assert_eq!(stats.cached_pages, 2);
+        assert_eq!(backend.total_read_calls(), 2);
+
+        let mut buffer = [0u8; 4];
+        cache.read(&backend, REGION_ADDRESS + PAGE_SIZE + 0x10, &mut buffer)?;
+        assert_eq!(buffer[0], expected_value(REGION_ADDRESS + PAGE_SIZE + 0x10));
+
+        let stats = cache.stats();
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(backend.total_read_calls(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_eviction() -> anyhow::Result<()> {
+        let backend = create_backend(4)?;
+        let cache = PageCache::new(2);
+
+        let mut buffer = [0u8; 4];
+        for page in 0..3 {
+            cache.read(&backend, REGION_ADDRESS + page * PAGE_SIZE, &mut buffer)?;
+        }
+
+        let stats = cache.stats();
+        assert_eq!(stats.evictions, 1);
+        assert_eq!(stats.cached_pages, 2);
+
+        /* the first page has been evicted */
+        cache.read(&backend, REGION_ADDRESS, &mut buffer)?;
+        assert_eq!(cache.stats().misses, 4);
+
+        cache.set_capacity(1);
+        let stats = cache.stats();
+        assert_eq!(stats.cached_pages, 1);
+        assert_eq!(stats.evictions, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalidate() -> anyhow::Result<()> {
+        let backend = create_backend(1)?;
+        let cache = PageCache::new(16);
+
+        let mut buffer = [0u8; 4];
+        cache.read(&backend, REGION_ADDRESS, &mut buffer)?;
+        assert_eq!(cache.stats().cached_pages, 1);
+
+        cache.invalidate();
+        assert_eq!(cache.stats().cached_pages, 0);
+
+        cache.read(&backend, REGION_ADDRESS, &mut buffer)?;
+        let stats = cache.stats();
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.hits, 0);
+        assert_eq!(backend.total_read_calls(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_disabled_and_bulk_reads() -> anyhow::Result<()> {
+        let backend = create_backend(8)?;
+
+        let cache = PageCache::new(0);
+        let mut buffer = [0u8; 4];
+        cache.read(&backend, REGION_ADDRESS, &mut buffer)?;
+        assert_eq!(cache.stats().cached_pages, 0);
+
+        let cache = PageCache::new(16);
+        let mut buffer = vec![0u8; 6 * PAGE_SIZE as usize];
+        cache.read(&backend, REGION_ADDRESS, &mut buffer)?;
+        assert_eq!(buffer[0x1234], expected_value(REGION_ADDRESS + 0x1234));
+
+        let stats = cache.stats();
+        assert_eq!(stats.cached_pages, 0);
+        assert_eq!(stats.misses, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_partial_page_fallback() -> anyhow::Result<()> {
+        let mut backend = MemorySnapshotBackend::new(1);
+        backend.add_region(REGION_ADDRESS + 0x10, vec![0xAA; 0x20])?;
+
+        let cache = PageCache::new(16);
+        let mut buffer = [0u8; 4];
+        cache.read(&backend, REGION_ADDRESS + 0x18, &mut buffer)?;
+        assert_eq!(buffer, [0xAA; 4]);
+        assert_eq!(cache.stats().cached_pages, 0);
+
+        assert!(cache
+            .read(&backend, REGION_ADDRESS + 0x100, &mut buffer)
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    fs,
+    path::Path,
+};
+
+use anyhow::Context;
+
+const IMAGE_DOS_SIGNATURE: &[u8; 2] = b"MZ";
+const IMAGE_NT_SIGNATURE: &[u8; 4] = b"PE\0\0";
+const IMAGE_NT_OPTIONAL_HDR64_MAGIC: u16 = 0x20B;
+
+const COFF_HEADER_SIZE: usize = 0x14;
+const SECTION_HEADER_SIZE: usize = 0x28;
+
+/// Memory images bigger than this are considered corrupted
+const MAX_IMAGE_SIZE: usize = 512 * 1024 * 1024;
+
+/// A section of a mapped PE image
+#[derive(Debug, Clone)]
+pub struct PeSection {
+    pub name: String,
+    pub virtual_address: u32,
+    pub virtual_size: u32,
+}
+
+/// A 64 bit PE file mapped like the Windows loader would map it (without relocations and imports).
+pub struct PeImage {
+    /// Preferred image base of the module
+    pub image_base: u64,
+    pub sections: Vec<PeSection>,
+
+    /// Memory image of the module
+    pub image: Vec<u8>,
+}
+
+fn read_u16(data: &[u8], offset: usize) -> anyhow::Result<u16> {
+    let bytes = data
+        .get(offset..offset + 2)
+        .context("unexpected end of file")?;
+    Ok(u16::from_le_bytes(bytes.try_into()?))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> anyhow::Result<u32> {
+    let bytes = data
+        .get(offset..offset + 4)
+        .context("unexpected end of file")?;
+    Ok(u32::from_le_bytes(bytes.try_into()?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> anyhow::Result<u64> {
+    let bytes = data
+        .get(offset..offset + 8)
+        .context("unexpected end of file")?;
+    Ok(u64::from_le_bytes(bytes.try_into()?))
+}
+
+impl PeImage {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let data = fs::read(path).with_context(|| format!("read {}", path.to_string_lossy()))?;
+        Self::parse(&data).with_context(|| format!("parse {}", path.to_string_lossy()))
+    }
+
+    pub fn parse(data: &[u8]) -> anyhow::Result<Self> {
+        if !data.starts_with(IMAGE_DOS_SIGNATURE) {
+            anyhow::bail!("invalid dos signature");
+        }
+
+        let nt_header = read_u32(data, 0x3C)? as usize;
+        if data.get(nt_header..nt_header + 4) != Some(IMAGE_NT_SIGNATURE) {
+            anyhow::bail!("invalid nt signature");
+        }
+
+        let coff_header = nt_header + 4;
+        let section_count = read_u16(data, coff_header + 0x02)? as usize;
+        let optional_header_size = read_u16(data, coff_header + 0x10)? as usize;
+
+        let optional_header = coff_header + COFF_HEADER_SIZE;
+        if read_u16(data, optional_header)? != IMAGE_NT_OPTIONAL_HDR64_MAGIC {
+            anyhow::bail!("only 64 bit images are supported");
+        }
+
+        let image_base = read_u64(data, optional_header + 0x18)?;
+        let image_size = read_u32(data, optional_header + 0x38)? as usize;
+        let headers_size = read_u32(data, optional_header + 0x3C)? as usize;
+        if image_size > MAX_IMAGE_SIZE {
+            anyhow::bail!("image size 0x{:X} exceeds the max image size", image_size);
+        }
+
+        let mut image = vec![0u8; image_size];
+        let headers_size = headers_size.min(image_size).min(data.len());
+        image[..headers_size].copy_from_slice(&data[..headers_size]);
+
+        let section_headers = optional_header + optional_header_size;
+        let mut sections = Vec::with_capacity(section_count);
+        for index in 0..section_count {
+            let header = section_headers + index * SECTION_HEADER_SIZE;
+            let name = data
+                .get(header..header + 8)
+                .context("unexpected end of file")?;
+            let name = String::from_utf8_lossy(name)
+                .trim_end_matches('\0')
+                .to_string();
+
+            let virtual_size = read_u32(data, header + 0x08)?;
+            let virtual_address = read_u32(data, header + 0x0C)?;
+            let raw_size = read_u32(data, header + 0x10)? as usize;
+            let raw_offset = read_u32(data, header + 0x14)? as usize;
+
+            let section_size = if virtual_size > 0 {
+                virtual_size as usize
+            } else {
+                raw_size
+            };
+            let copy_size = raw_size.min(section_size);
+            let target = image
+                .get_mut(virtual_address as usize..virtual_address as usize + copy_size)
+                .with_context(|| format!("section {} exceeds the image", name))?;
+            let source = data
+                .get(raw_offset..raw_offset + copy_size)
+                .with_context(|| format!("section {} exceeds the file", name))?;
+            target.copy_from_slice(source);
+
+            sections.push(PeSection {
+                name,
+                virtual_address,
+                virtual_size,
+            });
+        }
+
+        Ok(Self {
+            image_base,
+            sections,
+            image,
+        })
+    }
+}
@@ -0,0 +1,373 @@
+use std::{
+    any::Any,
+    io::{
+        self,
+        Read,
+        Write,
+    },
+    sync::Arc,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use anyhow::Context;
+use cs2_schema_declaration::{
+    MemoryDriver,
+    MemoryHandle,
+    SchemaValue,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use utils_state::StateRegistry;
+
+use crate::{
+    CurrentMapState,
+    Globals,
+    LocalCameraControllerTarget,
+    PlayerPawnEntities,
+    PlayerPawnState,
+    SpectatorList,
+    C4,
+};
+
+const RECORDING_MAGIC: [u8; 4] = *b"VSRC";
+const RECORDING_VERSION: u32 = 1;
+
+/// Frames bigger than this are considered corrupted
+const MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+
+/// Memory driver for replayed states.
+/// Replayed states only contain the recorded memory, every other read fails.
+struct DetachedMemoryDriver;
+
+impl MemoryDriver for DetachedMemoryDriver {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn read_slice(&self, address: u64, _slice: &mut [u8]) -> anyhow::Result<()> {
+        anyhow::bail!("memory at 0x{:X} is not part of the recording", address)
+    }
+
+    fn read_cstring(
+        &self,
+        address: u64,
+        _expected_length: Option<usize>,
+        _max_length: Option<usize>,
+    ) -> anyhow::Result<String> {
+        anyhow::bail!("memory at 0x{:X} is not part of the recording", address)
+    }
+}
+
+/// Raw memory of a schema value
+#[derive(Serialize, Deserialize)]
+pub struct RecordedMemory {
+    pub address: u64,
+    pub data: Vec<u8>,
+}
+
+impl RecordedMemory {
+    fn capture<T: SchemaValue>(memory: &MemoryHandle) -> anyhow::Result<Self> {
+        let size = T::value_size().context("could not record a dynamic sized schema")?;
+        let mut data = vec![0u8; size as usize];
+        memory.read_slice(0x00, &mut data)?;
+
+        Ok(Self {
+            address: memory.address,
+            data,
+        })
+    }
+
+    fn restore<T: SchemaValue>(self) -> anyhow::Result<T> {
+        let driver: Arc<dyn MemoryDriver> = Arc::new(DetachedMemoryDriver);
+        T::from_memory(MemoryHandle::from_buffer(&driver, self.address, self.data))
+    }
+}
+
+/// Recorded state values of a single frame
+#[derive(Default, Serialize, Deserialize)]
+pub struct StateFrame {
+    /// Time since the recording has been started
+    pub timestamp: Duration,
+
+    pub globals: Option<RecordedMemory>,
+    pub current_map: Option<CurrentMapState>,
+    pub bomb: Option<C4>,
+
+    /// Player pawn states by their pawn entity index
+    pub players: Vec<(u32, PlayerPawnState)>,
+
+    pub camera_target: Option<LocalCameraControllerTarget>,
+    pub spectators: Option<SpectatorList>,
+}
+
+impl StateFrame {
+    /// Capture the recorded states of the current frame.
+    /// States which could not be resolved will not be part of the frame.
+    pub fn capture(states: &StateRegistry) -> Self {
+        let mut frame = Self::default();
+
+        match states.resolve::<Globals>(()) {
+            Ok(globals) => match RecordedMemory::capture::<Globals>(&globals.memory) {
+                Ok(memory) => frame.globals = Some(memory),
+                Err(error) => log::warn!("Failed to record globals: {:#}", error),
+            },
+            Err(error) => log::warn!("Failed to record globals: {:#}", error),
+        }
+
+        if let Ok(map) = states.resolve::<CurrentMapState>(()) {
+            frame.current_map = Some(CurrentMapState {
+                current_map: map.current_map.clone(),
+            });
+        }
+
+        if let Ok(bomb) = states.resolve::<C4>(()) {
+            frame.bomb = Some(C4 {
+                state: bomb.state.clone(),
+                bomb_pos: bomb.bomb_pos,
+                bomb_site: bomb.bomb_site,
+                defuser: bomb.defuser.clone(),
+                owner: bomb.owner,
+            });
+        }
+
+        match states.resolve::<PlayerPawnEntities>(()) {
+            Ok(player_pawns) => {
+                for pawn_entity_index in player_pawns.entity_indices.iter() {
+                    match states.resolve::<PlayerPawnState>(*pawn_entity_index) {
+                        Ok(state) => frame.players.push((*pawn_entity_index, state.clone())),
+                        Err(error) => log::warn!(
+                            "Failed to record player pawn {}: {:#}",
+                            pawn_entity_index,
+                            error
+                        ),
+                    }
+                }
+            }
+            Err(error) => log::warn!("Failed to record player pawns: {:#}", error),
+        }
+
+        if let Ok(target) = states.resolve::<LocalCameraControllerTarget>(()) {
+            if let Some(target_entity_id) = target.target_entity_id {
+                if let Ok(spectators) = states.resolve::<SpectatorList>(target_entity_id) {
+                    frame.spectators = Some(spectators.clone());
+                }
+            }
+
+            frame.camera_target = Some(LocalCameraControllerTarget {
+                is_local_entity: target.is_local_entity,
+                target_entity_id: target.target_entity_id,
+            });
+        }
+
+        frame
+    }
+
+    /// Feed the recorded states into the registry
+    pub fn apply(self, states: &mut StateRegistry) -> anyhow::Result<()> {
+        if let Some(globals) = self.globals {
+            states.set(globals.restore::<Globals>()?, ())?;
+        }
+
+        if let Some(current_map) = self.current_map {
+            states.set(current_map, ())?;
+        }
+
+        if let Some(bomb) = self.bomb {
+            states.set(bomb, ())?;
+        }
+
+        /* only the recorded player pawns are known while replaying */
+        let entity_indices = self.players.iter().map(|(index, _)| *index).collect();
+        states.set(PlayerPawnEntities { entity_indices }, ())?;
+        for (pawn_entity_index, state) in self.players {
+            states.set(state, pawn_entity_index)?;
+        }
+
+        if let Some(camera_target) = self.camera_target {
+            states.set(camera_target, ())?;
+        }
+
+        if let Some(spectators) = self.spectators {
+            let target_entity_id = spectators.target_entity_id;
+            states.set(spectators, target_entity_id)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes captured frames into a compact binary log
+pub struct StateRecorder<W: Write> {
+    output: W,
+    start_time: Instant,
+    frame_count: usize,
+}
+
+impl<W: Write> StateRecorder<W> {
+    pub fn new(mut output: W) -> anyhow::Result<Self> {
+        output.write_all(&RECORDING_MAGIC)?;
+        output.write_all(&RECORDING_VERSION.to_le_bytes())?;
+
+        Ok(Self {
+            output,
+            start_time: Instant::now(),
+            frame_count: 0,
+        })
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    /// Capture the current frame and append it to the recording
+    pub fn record_frame(&mut self, states: &StateRegistry) -> anyhow::Result<()> {
+        let mut frame = StateFrame::capture(states);
+        frame.timestamp = self.start_time.elapsed();
+        self.write_frame(&frame)
+    }
+
+    pub fn write_frame(&mut self, frame: &StateFrame) -> anyhow::Result<()> {
+        let data = bincode::serialize(frame).context("serialize frame")?;
+        self.output.write_all(&(data.len() as u32).to_le_bytes())?;
+        self.output.write_all(&data)?;
+        self.frame_count += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> anyhow::Result<W> {
+        self.output.flush()?;
+        Ok(self.output)
+    }
+}
+
+/// Reads frames from a recording created by the `StateRecorder`
+pub struct StateReplay<R: Read> {
+    input: R,
+}
+
+impl<R: Read> StateReplay<R> {
+    pub fn new(mut input: R) -> anyhow::Result<Self> {
+        let mut header = [0u8; 8];
+        input
+            .read_exact(&mut header)
+            .context("read recording header")?;
+
+        if header[0..4] != RECORDING_MAGIC {
+            anyhow::bail!("invalid recording magic");
+        }
+
+        let version = u32::from_le_bytes(header[4..8].try_into()?);
+        if version != RECORDING_VERSION {
+            anyhow::bail!(
+                "unsupported recording version {} (expected {})",
+                version,
+                RECORDING_VERSION
+            );
+        }
+
+        Ok(Self { input })
+    }
+
+    /// Read the next frame.
+    /// Returns `None` if the recording has ended.
+    pub fn next_frame(&mut self) -> anyhow::Result<Option<StateFrame>> {
+        let mut length = [0u8; 4];
+        match self.input.read_exact(&mut length) {
+            Ok(_) => {}
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(error) => return Err(error.into()),
+        }
+
+        let length = u32::from_le_bytes(length) as usize;
+        if length > MAX_FRAME_SIZE {
+            anyhow::bail!("frame size {} exceeds the max frame size", length);
+        }
+
+        let mut data = vec![0u8; length];
+        self.input
+            .read_exact(&mut data)
+            .context("recording truncated")?;
+
+        Ok(Some(
+            bincode::deserialize(&data).context("deserialize frame")?,
+        ))
+    }
+
+    /// Invalidate the states and apply the next frame.
+    /// Returns the applied frames timestamp or `None` if the recording has ended.
+    pub fn replay_frame(&mut self, states: &mut StateRegistry) -> anyhow::Result<Option<Duration>> {
+        let Some(frame) = self.next_frame()? else {
+            return Ok(None);
+        };
+
+        let timestamp = frame.timestamp;
+        states.invalidate_states();
+        frame.apply(states)?;
+        Ok(Some(timestamp))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use utils_state::StateRegistry;
+
+    use super::{
+        StateFrame,
+        StateRecorder,
+        StateReplay,
+    };
+    use crate::{
+        CurrentMapState,
+        PlayerPawnEntities,
+        PlayerPawnState,
+    };
+
+    #[test]
+    fn test_record_replay() {
+        let mut recorder = StateRecorder::new(Vec::new()).unwrap();
+        recorder
+            .write_frame(&StateFrame {
+                current_map: Some(CurrentMapState {
+                    current_map: Some("de_mirage".to_string()),
+                }),
+                players: vec![(3, PlayerPawnState::Dead)],
+                ..Default::default()
+            })
+            .unwrap();
+        recorder.write_frame(&StateFrame::default()).unwrap();
+        let recording = recorder.finish().unwrap();
+
+        let mut states = StateRegistry::new(16);
+        let mut replay = StateReplay::new(recording.as_slice()).unwrap();
+        assert!(replay.replay_frame(&mut states).unwrap().is_some());
+
+        let map = states.resolve::<CurrentMapState>(()).unwrap();
+        assert_eq!(map.current_map.as_deref(), Some("de_mirage"));
+        drop(map);
+        assert!(matches!(
+            *states.resolve::<PlayerPawnState>(3).unwrap(),
+            PlayerPawnState::Dead
+        ));
+        assert_eq!(
+            states
+                .resolve::<PlayerPawnEntities>(())
+                .unwrap()
+                .entity_indices,
+            vec![3]
+        );
+
+        assert!(replay.replay_frame(&mut states).unwrap().is_some());
+        assert!(states.get::<CurrentMapState>(()).is_none());
+        assert!(states
+            .get::<PlayerPawnEntities>(())
+            .unwrap()
+            .entity_indices
+            .is_empty());
+        assert!(replay.replay_frame(&mut states).unwrap().is_none());
+    }
+}
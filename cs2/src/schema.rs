@@ -5,6 +5,7 @@ use std::collections::{
 
 use anyhow::Context;
 use cs2_schema_cutl::{
+    CUtlTSHash,
     CUtlVector,
     UtlRBTree,
 };
@@ -31,17 +32,18 @@ use crate::{
     Signature,
 };
 
+pub fn schema_system_signature() -> Signature {
+    Signature::relative_address(
+        obfstr!("schema system instance"),
+        obfstr!("48 8B 0D ? ? ? ? 48 8B 55 A0"),
+        0x03,
+        0x07,
+    )
+}
+
 // Returns SchemaSystem_001
 pub fn find_schema_system(cs2: &CS2Handle) -> anyhow::Result<u64> {
-    cs2.resolve_signature(
-        Module::Schemasystem,
-        &Signature::relative_address(
-            obfstr!("schema system instance"),
-            obfstr!("48 8B 0D ? ? ? ? 48 8B 55 A0"),
-            0x03,
-            0x07,
-        ),
-    )
+    cs2.resolve_signature(Module::Schemasystem, &schema_system_signature())
 }
 
 define_schema! {
@@ -96,8 +98,8 @@ define_schema! {
         // pub type_fixed_array: ??? = 0x558
         // pub type_bit_fields: ??? = 0x588
 
-        // pub class_bindings: CUtlTSHash<u64, Ptr<CSchemaClassBinding>> = 0x5C0,
-        // pub enum_bindings: CUtlTSHash<u64, Ptr<CSchemaEnumBinding>> = 0x2E50,
+        pub class_bindings: CUtlTSHash<u64, Ptr<CSchemaClassBinding>> = 0x5C0,
+        pub enum_bindings: CUtlTSHash<u64, Ptr<CSchemaEnumBinding>> = 0x2E50,
     }
 
     pub struct CSchemaType[0x20] {
@@ -213,6 +215,35 @@ define_schema! {
     }
 }
 
+impl CSchemaSystemTypeScope {
+    /// Find a class binding by its name using the scopes binding hash table.
+    pub fn find_class_binding(
+        &self,
+        name: &str,
+    ) -> anyhow::Result<Option<Ptr<CSchemaClassBinding>>> {
+        for entry in self.class_bindings()?.iter()? {
+            let binding = entry?.value()?;
+            if binding.reference_schema()?.name()?.read_string()? == name {
+                return Ok(Some(binding));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Find an enum binding by its name using the scopes binding hash table.
+    pub fn find_enum_binding(&self, name: &str) -> anyhow::Result<Option<Ptr<CSchemaEnumBinding>>> {
+        for entry in self.enum_bindings()?.iter()? {
+            let binding = entry?.value()?;
+            if binding.reference_schema()?.name()?.read_string()? == name {
+                return Ok(Some(binding));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
 fn parse_metadata(metadata: &CSchemaMetadataEntry) -> anyhow::Result<Metadata> {
     let name = metadata.name()?.read_string()?;
 
@@ -302,7 +333,7 @@ fn parse_type(cs2: &CS2Handle, schema_type: &CSchemaType) -> anyhow::Result<Opti
                             "CUtlSymbolLarge" => "PtrCStr",
                             "CUtlString" => "CUtlString",
                             "Vector" => "[f32; 0x03]",
-                            "QAngle" => "[f32; 0x04]",
+                            "QAngle" => "[f32; 0x03]",
 
                             "Color" => "Color", // TODO: What is this (3x or 4x f32?)?
 
@@ -314,7 +345,8 @@ fn parse_type(cs2: &CS2Handle, schema_type: &CSchemaType) -> anyhow::Result<Opti
                 AtomicCategory::CollectionOfT => {
                     let value = schema_type.var_type()?.read_string()?;
                     if !value.starts_with("CUtlVector<")
-                        || !value.starts_with("C_NetworkUtlVectorBase<")
+                        && !value.starts_with("CNetworkUtlVectorBase<")
+                        && !value.starts_with("C_NetworkUtlVectorBase<")
                     {
                         return Ok(None);
                     }
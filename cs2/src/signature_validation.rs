@@ -0,0 +1,229 @@
+use std::fmt;
+
+use crate::{
+    schema_system_signature,
+    BuildInfo,
+    CS2Handle,
+    CS2Offsets,
+    ConVars,
+    MemorySnapshotBackend,
+    Module,
+    PeImage,
+    Signature,
+    SignatureType,
+};
+
+/// Distance between the synthetic module base addresses.
+/// The preferred image bases of the game modules may overlap, therefore they are not used.
+const MODULE_BASE_STRIDE: u64 = 0x1_0000_0000;
+
+/// All signatures registered within this crate
+pub fn registered_signatures() -> Vec<(Module, Signature)> {
+    let mut signatures = Vec::new();
+    signatures.extend(CS2Offsets::signatures());
+    signatures.extend(BuildInfo::signatures());
+    signatures.extend(ConVars::signatures());
+    signatures.push((Module::Schemasystem, schema_system_signature()));
+    signatures
+}
+
+/// Result of validating a single signature
+#[derive(Debug, Clone)]
+pub struct SignatureReport {
+    pub module: Module,
+    pub name: String,
+
+    /// Number of locations the pattern matched
+    pub match_count: usize,
+
+    /// Resolved RVA for relative addresses or the raw value for offsets
+    pub value: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl SignatureReport {
+    pub fn is_valid(&self) -> bool {
+        self.match_count == 1 && self.value.is_some()
+    }
+}
+
+impl fmt::Display for SignatureReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self.match_count {
+            0 => "MISS",
+            1 if self.error.is_none() => "OK",
+            1 => "ERROR",
+            _ => "MULTIPLE",
+        };
+
+        write!(
+            f,
+            "[{:>8}] {:<16} {:<32}",
+            status,
+            self.module.get_module_name(),
+            self.name
+        )?;
+
+        if let Some(value) = &self.value {
+            write!(f, " 0x{:X}", value)?;
+        }
+
+        if self.match_count > 1 {
+            write!(f, " ({} matches)", self.match_count)?;
+        }
+
+        if let Some(error) = &self.error {
+            write!(f, " ({})", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn count_matches(image: &[u8], signature: &Signature) -> usize {
+    let pattern_length = signature.pattern.length();
+    if pattern_length == 0 || pattern_length > image.len() {
+        return 0;
+    }
+
+    image
+        .windows(pattern_length)
+        .filter(|window| signature.pattern.is_matching(window))
+        .count()
+}
+
+/// Resolve all registered signatures against the given module images.
+/// Signatures of modules which have not been provided will be reported as errors.
+pub fn validate_signatures(images: Vec<(Module, PeImage)>) -> anyhow::Result<Vec<SignatureReport>> {
+    let signatures = registered_signatures();
+    let mut reports = signatures
+        .iter()
+        .map(|(module, signature)| SignatureReport {
+            module: *module,
+            name: signature.debug_name.clone(),
+            match_count: 0,
+            value: None,
+            error: None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut backend = MemorySnapshotBackend::new(0);
+    let mut module_bases = Vec::with_capacity(images.len());
+    for (index, (module, image)) in images.into_iter().enumerate() {
+        let base_address = (index as u64 + 1) * MODULE_BASE_STRIDE;
+        log::debug!(
+            "Mapping {} (preferred base 0x{:X}) at 0x{:X}",
+            module.get_module_name(),
+            image.image_base,
+            base_address
+        );
+
+        for ((signature_module, signature), report) in signatures.iter().zip(reports.iter_mut()) {
+            if signature_module.get_module_name() == module.get_module_name() {
+                report.match_count = count_matches(&image.image, signature);
+            }
+        }
+
+        backend.add_module(module.get_module_name(), base_address, image.image)?;
+        module_bases.push((module, base_address));
+    }
+
+    let cs2 = CS2Handle::create_with_backend(Box::new(backend), false)?;
+    for ((module, signature), report) in signatures.iter().zip(reports.iter_mut()) {
+        let Some((_, base_address)) = module_bases
+            .iter()
+            .find(|(image_module, _)| image_module.get_module_name() == module.get_module_name())
+        else {
+            report.error = Some("module image not provided".to_string());
+            continue;
+        };
+
+        match cs2.resolve_signature(*module, signature) {
+            Ok(value) => {
+                report.value = Some(match &signature.value_type {
+                    SignatureType::Offset => value,
+                    SignatureType::RelativeAddress { .. } => value.wrapping_sub(*base_address),
+                });
+            }
+            Err(error) => report.error = Some(format!("{:#}", error)),
+        }
+    }
+
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod test {
+    use super::validate_signatures;
+    use crate::{
+        Module,
+        PeImage,
+    };
+
+    /// Build a minimal 64 bit PE file with a single section
+    fn build_image(section_data: &[u8]) -> Vec<u8> {
+        let mut data = vec![0u8; 0x400];
+        data[0..2].copy_from_slice(b"MZ");
+        data[0x3C..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        data[0x80..0x84].copy_from_slice(b"PE\0\0");
+        data[0x86..0x88].copy_from_slice(&1u16.to_le_bytes());
+        data[0x94..0x96].copy_from_slice(&0xF0u16.to_le_bytes());
+
+        let optional_header = 0x98;
+        data[optional_header..optional_header + 2].copy_from_slice(&0x20Bu16.to_le_bytes());
+        data[optional_header + 0x18..optional_header + 0x20]
+            .copy_from_slice(&0x180000000u64.to_le_bytes());
+        data[optional_header + 0x38..optional_header + 0x3C]
+            .copy_from_slice(&0x2000u32.to_le_bytes());
+        data[optional_header + 0x3C..optional_header + 0x40]
+            .copy_from_slice(&0x400u32.to_le_bytes());
+
+        let section = optional_header + 0xF0;
+        data[section..section + 5].copy_from_slice(b".text");
+        data[section + 0x08..section + 0x0C]
+            .copy_from_slice(&(section_data.len() as u32).to_le_bytes());
+        data[section + 0x0C..section + 0x10].copy_from_slice(&0x1000u32.to_le_bytes());
+        data[section + 0x10..section + 0x14]
+            .copy_from_slice(&(section_data.len() as u32).to_le_bytes());
+        data[section + 0x14..section + 0x18].copy_from_slice(&0x400u32.to_le_bytes());
+
+        data.extend_from_slice(section_data);
+        data
+    }
+
+    #[test]
+    fn test_validate_signatures() {
+        /* CCVars: lea r15, [rip + 0x20]; movaps (twice to cause multiple matches) */
+        let mut tier0 = vec![0xCCu8; 0x10];
+        tier0.extend_from_slice(&[0x4C, 0x8D, 0x3D, 0x20, 0x00, 0x00, 0x00, 0x0F, 0x28]);
+        let image = PeImage::parse(&build_image(&tier0)).unwrap();
+        assert_eq!(image.image_base, 0x180000000);
+        assert_eq!(image.sections.len(), 1);
+        assert_eq!(image.image.len(), 0x2000);
+
+        let reports = validate_signatures(vec![(Module::Tier0, image)]).unwrap();
+        let ccvars = reports
+            .iter()
+            .find(|report| matches!(report.module, Module::Tier0))
+            .unwrap();
+        assert!(ccvars.is_valid());
+        assert_eq!(ccvars.value, Some(0x1010 + 0x07 + 0x20));
+
+        let client = reports
+            .iter()
+            .find(|report| matches!(report.module, Module::Client))
+            .unwrap();
+        assert!(!client.is_valid());
+        assert_eq!(client.match_count, 0);
+
+        tier0.extend_from_slice(&[0x4C, 0x8D, 0x3D, 0x20, 0x00, 0x00, 0x00, 0x0F, 0x28]);
+        let image = PeImage::parse(&build_image(&tier0)).unwrap();
+        let reports = validate_signatures(vec![(Module::Tier0, image)]).unwrap();
+        let ccvars = reports
+            .iter()
+            .find(|report| matches!(report.module, Module::Tier0))
+            .unwrap();
+        assert_eq!(ccvars.match_count, 2);
+        assert!(!ccvars.is_valid());
+    }
+}
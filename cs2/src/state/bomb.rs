@@ -6,6 +6,10 @@ use cs2_schema_generated::cs2::client::{
     C_C4,
 };
 use obfstr::obfstr;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use utils_state::{
     State,
     StateCacheType,
@@ -19,7 +23,7 @@ use crate::{
     Globals,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BombDefuser {
     /// Total time remaining for a successfull bomb defuse
     pub time_remaining: f32,
@@ -28,7 +32,7 @@ pub struct BombDefuser {
     pub player_name: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum C4State {
     Active {
         /// Time remaining (in seconds) until detonation
@@ -41,6 +45,7 @@ pub enum C4State {
 }
 
 /// Information about the C4
+#[derive(Serialize, Deserialize)]
 pub struct C4 {
     /// Current state of C4
     pub state: Option<C4State>,
@@ -3,6 +3,10 @@ use std::ffi::CStr;
 use anyhow::Context;
 use cs2_schema_generated::cs2::client::C_CSObserverPawn;
 use obfstr::obfstr;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use utils_state::{
     State,
     StateCacheType,
@@ -15,10 +19,12 @@ use crate::{
     EntitySystem,
 };
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SpectatorInfo {
     pub spectator_name: String,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SpectatorList {
     pub target_entity_id: u32,
     pub spectators: Vec<SpectatorInfo>,
@@ -92,6 +98,7 @@ impl State for SpectatorList {
 }
 
 /// Get the entity id which we're currently following
+#[derive(Serialize, Deserialize)]
 pub struct LocalCameraControllerTarget {
     pub is_local_entity: bool,
     pub target_entity_id: Option<u32>,
@@ -18,18 +18,24 @@ use cs2_schema_generated::{
     EntityHandle,
 };
 use obfstr::obfstr;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 use utils_state::{
     State,
     StateCacheType,
 };
 
 use crate::{
+    CEntityIdentityEx,
     CS2Model,
+    ClassNameCache,
     EntitySystem,
     WeaponId,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerPawnInfo {
     pub controller_entity_id: u32,
     pub team_id: u8,
@@ -73,7 +79,7 @@ impl CModelStateEx for CModelState {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BoneStateData {
     pub position: nalgebra::Vector3<f32>,
 }
@@ -88,7 +94,7 @@ impl TryFrom<CBoneStateData> for BoneStateData {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PlayerPawnState {
     Alive(PlayerPawnInfo),
     Dead,
@@ -101,7 +107,8 @@ impl State for PlayerPawnState {
         states: &utils_state::StateRegistry,
         pawn_entity_index: Self::Parameter,
     ) -> anyhow::Result<Self> {
-        let entities = states.resolve::<EntitySystem>(())?;
+        /* pawns might be resolved on worker registries which share the entity system and models */
+        let entities = states.resolve_shared::<EntitySystem>(())?;
 
         let player_pawn = match entities
             .get_by_handle::<C_CSPlayerPawn>(&EntityHandle::from_index(pawn_entity_index))?
@@ -168,7 +175,7 @@ impl State for PlayerPawnState {
             .read_schema()?
             .address()?;
 
-        let model = states.resolve::<CS2Model>(model_address)?;
+        let model = states.resolve_shared::<CS2Model>(model_address)?;
         let bone_states = game_screen_node
             .m_modelState()?
             .bone_state_data()?
@@ -201,7 +208,7 @@ impl State for PlayerPawnState {
             player_name,
             player_has_defuser,
             player_health,
-            weapon: WeaponId::from_id(weapon_type).unwrap_or(WeaponId::Unknown),
+            weapon: WeaponId::from_id(weapon_type),
             player_flashtime,
 
             position,
@@ -216,3 +223,40 @@ impl State for PlayerPawnState {
         StateCacheType::Volatile
     }
 }
+
+/// Entity indices of all player pawns
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlayerPawnEntities {
+    pub entity_indices: Vec<u32>,
+}
+
+impl State for PlayerPawnEntities {
+    type Parameter = ();
+
+    fn create(
+        states: &utils_state::StateRegistry,
+        _param: Self::Parameter,
+    ) -> anyhow::Result<Self> {
+        let entities = states.resolve::<EntitySystem>(())?;
+        let class_name_cache = states.resolve::<ClassNameCache>(())?;
+
+        let mut entity_indices = Vec::with_capacity(16);
+        for entity_identity in entities.all_identities() {
+            let entity_class = class_name_cache.lookup(&entity_identity.entity_class_info()?)?;
+            if !entity_class
+                .map(|name| *name == "C_CSPlayerPawn")
+                .unwrap_or(false)
+            {
+                continue;
+            }
+
+            entity_indices.push(entity_identity.handle::<()>()?.get_entity_index());
+        }
+
+        Ok(Self { entity_indices })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Volatile
+    }
+}
@@ -0,0 +1,72 @@
+/* Generated by map-tracer/examples/generate_weapons.rs. Regenerate after game updates instead of editing it. */
+define_weapons! {
+    #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+    pub enum WeaponId {
+        Deagle { id: 1, name: "Desert Eagle", flags: WEAPON_FLAG_TYPE_PISTOL, token: "#SFUI_WPNHUD_DesertEagle" },
+        Elite { id: 2, name: "Dual Berettas", flags: WEAPON_FLAG_TYPE_PISTOL, token: "#SFUI_WPNHUD_Elite" },
+        FiveSeven { id: 3, name: "Five-SeveN", flags: WEAPON_FLAG_TYPE_PISTOL, token: "#SFUI_WPNHUD_FiveSeven" },
+        Glock { id: 4, name: "Glock-18", flags: WEAPON_FLAG_TYPE_PISTOL, token: "#SFUI_WPNHUD_Glock18" },
+        Ak47 { id: 7, name: "AK-47", flags: WEAPON_FLAG_TYPE_RIFLE, token: "#SFUI_WPNHUD_AK47" },
+        Aug { id: 8, name: "AUG", flags: WEAPON_FLAG_TYPE_RIFLE, token: "#SFUI_WPNHUD_Aug" },
+        AWP { id: 9, name: "AWP", flags: WEAPON_FLAG_TYPE_SNIPER_RIFLE, token: "#SFUI_WPNHUD_AWP" },
+        Famas { id: 10, name: "FAMAS", flags: WEAPON_FLAG_TYPE_RIFLE, token: "#SFUI_WPNHUD_Famas" },
+        G3SG1 { id: 11, name: "G3SG1", flags: WEAPON_FLAG_TYPE_SNIPER_RIFLE, token: "#SFUI_WPNHUD_G3SG1" },
+        Galilar { id: 13, name: "Galil AR", flags: WEAPON_FLAG_TYPE_RIFLE, token: "#SFUI_WPNHUD_GalilAR" },
+        M249 { id: 14, name: "M249", flags: WEAPON_FLAG_TYPE_MACHINE_GUN, token: "#SFUI_WPNHUD_M249" },
+        M4A4 { id: 16, name: "M4A4", flags: WEAPON_FLAG_TYPE_RIFLE, token: "#SFUI_WPNHUD_M4A1" },
+        Mac10 { id: 17, name: "MAC-10", flags: WEAPON_FLAG_TYPE_SMG, token: "#SFUI_WPNHUD_MAC10" },
+        P90 { id: 19, name: "P90", flags: WEAPON_FLAG_TYPE_SMG, token: "#SFUI_WPNHUD_P90" },
+        MP5SD { id: 23, name: "MP5-SD", flags: WEAPON_FLAG_TYPE_SMG, token: "#SFUI_WPNHUD_MP5SD" },
+        Ump45 { id: 24, name: "UMP-45", flags: WEAPON_FLAG_TYPE_SMG, token: "#SFUI_WPNHUD_UMP45" },
+        XM1014 { id: 25, name: "XM1014", flags: WEAPON_FLAG_TYPE_SHOTGUN, token: "#SFUI_WPNHUD_xm1014" },
+        Bizon { id: 26, name: "PP-Bizon", flags: WEAPON_FLAG_TYPE_SMG, token: "#SFUI_WPNHUD_Bizon" },
+        Mag7 { id: 27, name: "MAG-7", flags: WEAPON_FLAG_TYPE_SHOTGUN, token: "#SFUI_WPNHUD_Mag7" },
+        Negev { id: 28, name: "Negev", flags: WEAPON_FLAG_TYPE_MACHINE_GUN, token: "#SFUI_WPNHUD_Negev" },
+        SawedOff { id: 29, name: "Sawed-Off", flags: WEAPON_FLAG_TYPE_SHOTGUN, token: "#SFUI_WPNHUD_Sawedoff" },
+        Tec9 { id: 30, name: "Tec-9", flags: WEAPON_FLAG_TYPE_PISTOL, token: "#SFUI_WPNHUD_Tec9" },
+        Taser { id: 31, name: "Zeus x27", flags: 0, token: "#SFUI_WPNHUD_Taser" },
+        HKP200 { id: 32, name: "P2000", flags: WEAPON_FLAG_TYPE_PISTOL, token: "#SFUI_WPNHUD_HKP2000" },
+        MP7 { id: 33, name: "MP7", flags: WEAPON_FLAG_TYPE_SMG, token: "#SFUI_WPNHUD_MP7" },
+        MP9 { id: 34, name: "MP9", flags: WEAPON_FLAG_TYPE_SMG, token: "#SFUI_WPNHUD_MP9" },
+        Nova { id: 35, name: "Nova", flags: WEAPON_FLAG_TYPE_SHOTGUN, token: "#SFUI_WPNHUD_Nova" },
+        P250 { id: 36, name: "P250", flags: WEAPON_FLAG_TYPE_PISTOL, token: "#SFUI_WPNHUD_P250" },
+        Scar20 { id: 38, name: "SCAR-20", flags: WEAPON_FLAG_TYPE_SNIPER_RIFLE, token: "#SFUI_WPNHUD_SCAR20" },
+        Sg553 { id: 39, name: "SG 553", flags: WEAPON_FLAG_TYPE_RIFLE, token: "#SFUI_WPNHUD_SG556" },
+        Ssg08 { id: 40, name: "SSG 08", flags: WEAPON_FLAG_TYPE_SNIPER_RIFLE, token: "#SFUI_WPNHUD_SSG08" },
+        Knife { id: 42, name: "Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_Knife" },
+        Flashbang { id: 43, name: "Flashbang", flags: WEAPON_FLAG_TYPE_GRANADE, token: "#SFUI_WPNHUD_FLASHBANG" },
+        HZGranade { id: 44, name: "High Explosive Grenade", flags: WEAPON_FLAG_TYPE_GRANADE, token: "#SFUI_WPNHUD_HE_Grenade" },
+        SmokeGranade { id: 45, name: "Smoke Grenade", flags: WEAPON_FLAG_TYPE_GRANADE, token: "#SFUI_WPNHUD_Smoke_Grenade" },
+        Molotov { id: 46, name: "Molotov", flags: WEAPON_FLAG_TYPE_GRANADE, token: "#SFUI_WPNHUD_MOLOTOV" },
+        Decoy { id: 47, name: "Decoy Grenade", flags: WEAPON_FLAG_TYPE_GRANADE, token: "#SFUI_WPNHUD_DECOY" },
+        Incendiary { id: 48, name: "Incendiary Grenade", flags: WEAPON_FLAG_TYPE_GRANADE, token: "#SFUI_WPNHUD_IncGrenade" },
+        C4 { id: 49, name: "C4 Explosive", flags: 0, token: "#SFUI_WPNHUD_C4" },
+        Healthshot { id: 57, name: "Medi-Shot", flags: 0, token: "#SFUI_WPNHUD_Healthshot" },
+        KnifeT { id: 59, name: "Knife (T)", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_Knife_T" },
+        M4A1Silencer { id: 60, name: "M4A1-S", flags: WEAPON_FLAG_TYPE_RIFLE, token: "#SFUI_WPNHUD_M4_SILENCER" },
+        USPS { id: 61, name: "USP-S", flags: WEAPON_FLAG_TYPE_PISTOL, token: "#SFUI_WPNHUD_USP_SILENCER" },
+        CZ75a { id: 63, name: "CZ75-Auto", flags: WEAPON_FLAG_TYPE_PISTOL, token: "#SFUI_WPNHUD_CZ75" },
+        Revolver { id: 64, name: "R8 Revolver", flags: WEAPON_FLAG_TYPE_PISTOL, token: "#SFUI_WPNHUD_REVOLVER" },
+
+        KnifeBayonet { id: 500, name: "Bayonet", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_KnifeBayonet" },
+        KnifesClassic { id: 503, name: "Classic Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_knife_css" },
+        KnifeFlip { id: 505, name: "Flip Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_KnifeFlip" },
+        KnifeGut { id: 506, name: "Gut Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_KnifeGut" },
+        KnifeKarambit { id: 507, name: "Karambit", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_KnifeKaram" },
+        KnifeM9Bayonet { id: 508, name: "M9 Bayonet", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_KnifeM9" },
+        KnifeTactical { id: 509, name: "Huntsman Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_KnifeTactical" },
+        KnifeFalchion { id: 512, name: "Falchion Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_knife_falchion_advanced" },
+        KnifeSurvivalBowie { id: 514, name: "Bowie Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_knife_survival_bowie" },
+        KnifeButterfly { id: 515, name: "Butterfly Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_Knife_Butterfly" },
+        KnifePush { id: 516, name: "Shadow Daggers", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_knife_push" },
+        KnifeCord { id: 517, name: "Paracord Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_knife_cord" },
+        KnifeSurvival { id: 518, name: "Survival Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_knife_canis" },
+        KnifeUrsus { id: 519, name: "Ursus Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_Knife_Ursus" },
+        KnifesNavaja { id: 520, name: "Navaja Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_knife_gypsy_jackknife" },
+        KnifesNomad { id: 521, name: "Nomad Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_knife_outdoor" },
+        KnifesStiletto { id: 522, name: "Stiletto Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_knife_stiletto" },
+        KnifesTalon { id: 523, name: "Talon Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_knife_widowmaker" },
+        KnifesSkeleton { id: 525, name: "Skeleton Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_knife_skeleton" },
+        KnifeKukri { id: 526, name: "Kukri Knife", flags: WEAPON_FLAG_TYPE_KNIFE, token: "#SFUI_WPNHUD_knife_kukri" },
+    }
+}
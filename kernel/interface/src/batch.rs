@@ -0,0 +1,191 @@
+use valthrun_driver_shared::requests::{
+    DriverRequest,
+    MemoryAccessMode,
+};
+
+/// Maximum gap between two reads which still allows them to be merged into one request.
+const BATCH_MERGE_GAP: u64 = 0x400;
+
+/// Maximum size of a merged read request.
+const BATCH_MERGE_MAX_LENGTH: u64 = 0x10000;
+
+/// First driver version which supports `RequestReadBatch`
+pub const DRIVER_VERSION_READ_BATCH: u32 = 0x00_05_00_00;
+
+/// Maximum number of entries the driver accepts within one batch request
+pub(crate) const READ_BATCH_MAX_ENTRIES: usize = 0x400;
+
+/// A single read within a batched read
+pub struct BatchRead<'a> {
+    pub address: u64,
+    pub buffer: &'a mut [u8],
+
+    /// Will be set when the batch has been executed
+    pub success: bool,
+}
+
+/// Multiple batch reads which will be fetched with one request
+pub(crate) struct BatchReadChunk {
+    pub address: u64,
+    pub length: usize,
+
+    /// Indices of the reads covered by this chunk
+    pub reads: Vec<usize>,
+}
+
+/// Group reads which are close to each other into chunks.
+pub(crate) fn plan_batch_chunks(reads: &[BatchRead]) -> Vec<BatchReadChunk> {
+    let mut read_order = (0..reads.len()).collect::<Vec<_>>();
+    read_order.sort_by_key(|index| reads[*index].address);
+
+    let mut chunks = Vec::<BatchReadChunk>::new();
+    for index in read_order {
+        let read = &reads[index];
+        let read_end = read.address + read.buffer.len() as u64;
+
+        if let Some(chunk) = chunks.last_mut() {
+            let chunk_end = chunk.address + chunk.length as u64;
+            if read.address <= chunk_end + BATCH_MERGE_GAP
+                && read_end.max(chunk_end) - chunk.address <= BATCH_MERGE_MAX_LENGTH
+            {
+                chunk.length = (read_end.max(chunk_end) - chunk.address) as usize;
+                chunk.reads.push(index);
+                continue;
+            }
+        }
+
+        chunks.push(BatchReadChunk {
+            address: read.address,
+            length: read.buffer.len(),
+            reads: vec![index],
+        });
+    }
+
+    chunks
+}
+
+/// A single entry of a `RequestReadBatch`.
+/// The driver sets `success` if the memory has been read successfully.
+#[repr(C)]
+pub struct ReadBatchEntry {
+    pub address: u64,
+    pub buffer: *mut u8,
+    pub count: usize,
+
+    pub success: bool,
+}
+
+/// Read multiple memory regions with one IOCTL.
+/// Every entry will be read on its own, an invalid entry does not abort the request.
+#[repr(C)]
+pub struct RequestReadBatch {
+    pub process_id: i32,
+    pub mode: MemoryAccessMode,
+
+    pub entries: *mut ReadBatchEntry,
+    pub entry_count: usize,
+}
+
+#[derive(Default)]
+pub enum ResponseReadBatch {
+    #[default]
+    Success,
+    UnknownProcess,
+    AccessModeUnavailable,
+}
+
+impl DriverRequest for RequestReadBatch {
+    type Result = ResponseReadBatch;
+
+    fn function_code() -> u16 {
+        0x0A
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        plan_batch_chunks,
+        BatchRead,
+        BATCH_MERGE_GAP,
+        BATCH_MERGE_MAX_LENGTH,
+    };
+
+    fn create_reads<'a>(buffers: &'a mut [Vec<u8>], addresses: &[u64]) -> Vec<BatchRead<'a>> {
+        buffers
+            .iter_mut()
+            .zip(addresses)
+            .map(|(buffer, address)| BatchRead {
+                address: *address,
+                buffer: buffer.as_mut_slice(),
+                success: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_merge_adjacent() {
+        let mut buffers = vec![vec![0u8; 0x10], vec![0u8; 0x08], vec![0u8; 0x20]];
+        let reads = create_reads(&mut buffers, &[0x1000, 0x1010, 0x1018]);
+
+        let chunks = plan_batch_chunks(&reads);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].address, 0x1000);
+        assert_eq!(chunks[0].length, 0x38);
+        assert_eq!(chunks[0].reads, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_sort_and_overlap() {
+        let mut buffers = vec![vec![0u8; 0x08], vec![0u8; 0x40], vec![0u8; 0x08]];
+        let reads = create_reads(&mut buffers, &[0x2020, 0x2000, 0x2008]);
+
+        let chunks = plan_batch_chunks(&reads);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].address, 0x2000);
+        assert_eq!(chunks[0].length, 0x40);
+        assert_eq!(chunks[0].reads, vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn test_gap_split() {
+        let mut buffers = vec![vec![0u8; 0x08], vec![0u8; 0x08], vec![0u8; 0x08]];
+        let reads = create_reads(
+            &mut buffers,
+            &[
+                0x1000,
+                0x1008 + BATCH_MERGE_GAP,
+                0x1010 + BATCH_MERGE_GAP * 2 + 1,
+            ],
+        );
+
+        let chunks = plan_batch_chunks(&reads);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].reads, vec![0, 1]);
+        assert_eq!(chunks[0].length as u64, 0x10 + BATCH_MERGE_GAP);
+        assert_eq!(chunks[1].address, 0x1010 + BATCH_MERGE_GAP * 2 + 1);
+        assert_eq!(chunks[1].reads, vec![2]);
+    }
+
+    #[test]
+    fn test_max_length_split() {
+        let mut buffers = vec![
+            vec![0u8; BATCH_MERGE_MAX_LENGTH as usize - 0x10],
+            vec![0u8; 0x20],
+        ];
+        let reads = create_reads(
+            &mut buffers,
+            &[0x10000, 0x10000 + BATCH_MERGE_MAX_LENGTH - 0x10],
+        );
+
+        let chunks = plan_batch_chunks(&reads);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].reads, vec![0]);
+        assert_eq!(chunks[1].reads, vec![1]);
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(plan_batch_chunks(&[]).is_empty());
+    }
+}
@@ -2,6 +2,7 @@ use core::{
     mem,
     slice,
     sync::atomic::{
+        AtomicBool,
         AtomicUsize,
         Ordering,
     },
@@ -36,13 +37,22 @@ use valthrun_driver_shared::{
 };
 
 use crate::{
+    batch::{
+        plan_batch_chunks,
+        ReadBatchEntry,
+        RequestReadBatch,
+        ResponseReadBatch,
+        READ_BATCH_MAX_ENTRIES,
+    },
     com::{
         DriverInterface,
         IoctrlDriverInterface,
     },
+    BatchRead,
     KInterfaceError,
     KResult,
     SearchPattern,
+    DRIVER_VERSION_READ_BATCH,
 };
 
 /// Interface for our kernel driver
@@ -51,6 +61,9 @@ pub struct KernelInterface {
     driver_version: u32,
 
     read_calls: AtomicUsize,
+
+    /// Cleared if the driver rejects batch requests
+    read_batch_supported: AtomicBool,
 }
 
 fn driver_version_string(driver_version: u32) -> String {
@@ -69,6 +82,7 @@ impl KernelInterface {
             driver_version: 0,
 
             read_calls: AtomicUsize::new(0),
+            read_batch_supported: AtomicBool::new(false),
         };
         interface.initialize()?;
         Ok(interface)
@@ -136,6 +150,10 @@ impl KernelInterface {
         };
 
         self.driver_version = result.driver_version;
+        self.read_batch_supported.store(
+            self.driver_version >= DRIVER_VERSION_READ_BATCH,
+            Ordering::Relaxed,
+        );
         log::debug!(
             "Successfully initialized kernel interface with driver version: {}",
             driver_version_string(self.driver_version)
@@ -225,6 +243,112 @@ impl KernelInterface {
         }
     }
 
+    /// Execute multiple reads at once.
+    /// If the driver supports it, all reads will be executed with a single request.
+    /// Older drivers fall back to merging reads which are close to each other.
+    ///
+    /// Reads targeting an invalid address will be marked as failed,
+    /// all other errors abort the whole batch.
+    #[must_use]
+    pub fn read_batch(&self, process_id: i32, reads: &mut [BatchRead]) -> KResult<()> {
+        if self.read_batch_supported.load(Ordering::Relaxed) {
+            match self.read_batch_request(process_id, reads) {
+                Ok(_) => return Ok(()),
+                Err(KInterfaceError::RequestFailed) => {
+                    log::warn!(
+                        "Driver rejected the batch read request. Falling back to single reads."
+                    );
+                    self.read_batch_supported.store(false, Ordering::Relaxed);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        self.read_batch_chunked(process_id, reads)
+    }
+
+    fn read_batch_request(&self, process_id: i32, reads: &mut [BatchRead]) -> KResult<()> {
+        for reads in reads.chunks_mut(READ_BATCH_MAX_ENTRIES) {
+            let mut entries = reads
+                .iter_mut()
+                .map(|read| ReadBatchEntry {
+                    address: read.address,
+                    buffer: read.buffer.as_mut_ptr(),
+                    count: read.buffer.len(),
+
+                    success: false,
+                })
+                .collect::<Vec<_>>();
+
+            self.read_calls.fetch_add(1, Ordering::Relaxed);
+            let result = unsafe {
+                /*
+                 * Safety:
+                 * Every entry points to the buffer of its read which lives for the whole request.
+                 */
+                self.execute_request(&RequestReadBatch {
+                    process_id,
+                    mode: MemoryAccessMode::AttachProcess,
+
+                    entries: entries.as_mut_ptr(),
+                    entry_count: entries.len(),
+                })
+            }?;
+
+            match result {
+                ResponseReadBatch::Success => {}
+                ResponseReadBatch::UnknownProcess => {
+                    return Err(KInterfaceError::ProcessDoesNotExists)
+                }
+                ResponseReadBatch::AccessModeUnavailable => {
+                    return Err(KInterfaceError::AccessModeUnavailable)
+                }
+            }
+
+            for (read, entry) in reads.iter_mut().zip(entries) {
+                read.success = entry.success;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_batch_chunked(&self, process_id: i32, reads: &mut [BatchRead]) -> KResult<()> {
+        for chunk in plan_batch_chunks(reads) {
+            if chunk.reads.len() > 1 {
+                let mut buffer = vec![0u8; chunk.length];
+                match self.read_slice(process_id, &[chunk.address], &mut buffer) {
+                    Ok(_) => {
+                        for index in chunk.reads {
+                            let read = &mut reads[index];
+                            let offset = (read.address - chunk.address) as usize;
+                            read.buffer
+                                .copy_from_slice(&buffer[offset..offset + read.buffer.len()]);
+                            read.success = true;
+                        }
+
+                        continue;
+                    }
+                    Err(KInterfaceError::InvalidAddress { .. }) => {
+                        /* some parts of the chunk are invalid, read every entry on its own */
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+
+            for index in chunk.reads {
+                let read = &mut reads[index];
+                read.success = match self.read_slice(process_id, &[read.address], read.buffer) {
+                    Ok(_) => true,
+                    Err(KInterfaceError::InvalidAddress { .. }) => false,
+                    Err(err) => return Err(err),
+                };
+            }
+        }
+
+        Ok(())
+    }
+
     #[must_use]
     pub fn write<T: Copy>(&self, process_id: i32, address: u64, value: &T) -> KResult<()> {
         let buffer = unsafe {
@@ -1,6 +1,9 @@
 mod kinterface;
 pub use kinterface::*;
 
+mod batch;
+pub use batch::*;
+
 mod error;
 pub use error::*;
 pub use valthrun_driver_shared::*;
@@ -0,0 +1,246 @@
+//! Generate the weapon table of the cs2 crate (cs2/src/weapon_definitions.rs) from the games item schema.
+//!
+//! Usage: generate_weapons <pak01_dir.vpk> <output file> [localization file (e.g. csgo_english.txt)]
+use std::{
+    fs::File,
+    io::{
+        BufReader,
+        BufWriter,
+        Write,
+    },
+    path::Path,
+};
+
+use anyhow::Context;
+use map_tracer::{
+    parse_item_definitions,
+    ItemDefinition,
+    KV1Value,
+    Localization,
+    VPKArchiveReader,
+    LOCALIZATION_FALLBACK_LANGUAGE,
+};
+
+const ITEMS_GAME_PATH: &str = "scripts/items/items_game.txt";
+
+/// Variant names of weapons which already have been used before the table has been generated.
+/// Variant names are used as config keys and therefore must not change.
+const LEGACY_VARIANT_NAMES: &[(u16, &str)] = &[
+    (1, "Deagle"),
+    (2, "Elite"),
+    (3, "FiveSeven"),
+    (4, "Glock"),
+    (7, "Ak47"),
+    (8, "Aug"),
+    (9, "AWP"),
+    (10, "Famas"),
+    (11, "G3SG1"),
+    (13, "Galilar"),
+    (14, "M249"),
+    (16, "M4A4"),
+    (17, "Mac10"),
+    (19, "P90"),
+    (23, "MP5SD"),
+    (24, "Ump45"),
+    (25, "XM1014"),
+    (26, "Bizon"),
+    (27, "Mag7"),
+    (28, "Negev"),
+    (29, "SawedOff"),
+    (30, "Tec9"),
+    (31, "Taser"),
+    (32, "HKP200"),
+    (33, "MP7"),
+    (34, "MP9"),
+    (35, "Nova"),
+    (36, "P250"),
+    (38, "Scar20"),
+    (39, "Sg553"),
+    (40, "Ssg08"),
+    (42, "Knife"),
+    (43, "Flashbang"),
+    (44, "HZGranade"),
+    (45, "SmokeGranade"),
+    (46, "Molotov"),
+    (47, "Decoy"),
+    (48, "Incendiary"),
+    (49, "C4"),
+    (57, "Healthshot"),
+    (59, "KnifeT"),
+    (60, "M4A1Silencer"),
+    (61, "USPS"),
+    (63, "CZ75a"),
+    (64, "Revolver"),
+    (500, "KnifeBayonet"),
+    (503, "KnifesClassic"),
+    (505, "KnifeFlip"),
+    (506, "KnifeGut"),
+    (507, "KnifeKarambit"),
+    (508, "KnifeM9Bayonet"),
+    (509, "KnifeTactical"),
+    (512, "KnifeFalchion"),
+    (514, "KnifeSurvivalBowie"),
+    (515, "KnifeButterfly"),
+    (516, "KnifePush"),
+    (517, "KnifeCord"),
+    (518, "KnifeSurvival"),
+    (519, "KnifeUrsus"),
+    (520, "KnifesNavaja"),
+    (521, "KnifesNomad"),
+    (522, "KnifesStiletto"),
+    (523, "KnifesTalon"),
+    (525, "KnifesSkeleton"),
+];
+
+/// Display names for weapons which would otherwise share their localized name with another weapon
+const DISPLAY_NAME_OVERRIDES: &[(u16, &str)] = &[(59, "Knife (T)")];
+
+fn read_items_game(vpk_path: &Path) -> anyhow::Result<Vec<ItemDefinition>> {
+    let archive_file =
+        File::open(vpk_path).with_context(|| format!("open {}", vpk_path.to_string_lossy()))?;
+    let mut archive = VPKArchiveReader::new(BufReader::new(archive_file))?;
+
+    let archive_name = vpk_path
+        .file_name()
+        .context("missing archive file name")?
+        .to_string_lossy()
+        .to_string();
+    let archive_prefix = archive_name
+        .strip_suffix("_dir.vpk")
+        .context("expected a directory archive (*_dir.vpk)")?
+        .to_string();
+
+    let items_game = archive.read_entry_with(ITEMS_GAME_PATH, |archive_index| {
+        let path = vpk_path.with_file_name(format!("{}_{:03}.vpk", archive_prefix, archive_index));
+        Ok(BufReader::new(File::open(path)?))
+    })?;
+
+    let items_game = String::from_utf8_lossy(&items_game);
+    let root = KV1Value::parse(&items_game)?;
+    let (_, items_game) = root
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("items_game"))
+        .context("missing items_game root")?;
+
+    Ok(parse_item_definitions(items_game))
+}
+
+/// weapon_knife_flip -> KnifeFlip
+fn variant_name(item: &ItemDefinition) -> String {
+    if let Some((_, name)) = LEGACY_VARIANT_NAMES.iter().find(|(id, _)| *id == item.id) {
+        return name.to_string();
+    }
+
+    item.name
+        .trim_start_matches("weapon_")
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn weapon_flags(item: &ItemDefinition) -> &'static str {
+    match item.weapon_type.as_deref() {
+        Some("Knife") => "WEAPON_FLAG_TYPE_KNIFE",
+        Some("Pistol") => "WEAPON_FLAG_TYPE_PISTOL",
+        Some("Shotgun") => "WEAPON_FLAG_TYPE_SHOTGUN",
+        Some("SubMachinegun") => "WEAPON_FLAG_TYPE_SMG",
+        Some("Rifle") => "WEAPON_FLAG_TYPE_RIFLE",
+        Some("SniperRifle") => "WEAPON_FLAG_TYPE_SNIPER_RIFLE",
+        Some("Machinegun") => "WEAPON_FLAG_TYPE_MACHINE_GUN",
+        Some("Grenade") => "WEAPON_FLAG_TYPE_GRANADE",
+        _ => "0",
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .parse_default_env()
+        .init();
+
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.len() < 3 {
+        anyhow::bail!(
+            "Usage: {} <pak01_dir.vpk> <output file> [localization file]",
+            args[0]
+        );
+    }
+
+    let items = read_items_game(Path::new(&args[1]))?;
+    let localization = match args.get(3) {
+        Some(path) => {
+            let mut localization = Localization::new(LOCALIZATION_FALLBACK_LANGUAGE);
+            localization
+                .load_file(Path::new(path))
+                .with_context(|| format!("load {}", path))?;
+            localization
+        }
+        None => {
+            log::warn!("No localization file provided. Using the internal names as display names.");
+            Localization::default()
+        }
+    };
+
+    let mut output = BufWriter::new(File::create(&args[2])?);
+    writeln!(
+        output,
+        "/* Generated by map-tracer/examples/generate_weapons.rs. Regenerate after game updates instead of editing it. */"
+    )?;
+    writeln!(output, "define_weapons! {{")?;
+    writeln!(
+        output,
+        "    #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]"
+    )?;
+    writeln!(output, "    pub enum WeaponId {{")?;
+
+    let mut weapon_count = 0;
+    let mut previous_id = 0;
+    for item in items.iter().filter(|item| item.is_weapon()) {
+        let variant_name = variant_name(item);
+        let display_name = DISPLAY_NAME_OVERRIDES
+            .iter()
+            .find(|(id, _)| *id == item.id)
+            .map(|(_, name)| *name)
+            .or_else(|| {
+                item.item_name
+                    .as_ref()
+                    .and_then(|token| localization.lookup(token))
+            })
+            .unwrap_or(variant_name.as_str())
+            .to_string();
+
+        if previous_id < 500 && item.id >= 500 {
+            /* knifes */
+            writeln!(output)?;
+        }
+        previous_id = item.id;
+
+        write!(
+            output,
+            "        {} {{ id: {}, name: {:?}, flags: {}",
+            variant_name,
+            item.id,
+            display_name,
+            weapon_flags(item)
+        )?;
+        if let Some(token) = &item.item_name {
+            write!(output, ", token: {:?}", token)?;
+        }
+        writeln!(output, " }},")?;
+        weapon_count += 1;
+    }
+
+    writeln!(output, "    }}")?;
+    writeln!(output, "}}")?;
+    output.flush()?;
+
+    log::info!("Generated {} weapons into {}", weapon_count, args[2]);
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use crate::KV1Value;
+
+/// Max depth of nested prefabs
+const MAX_PREFAB_DEPTH: usize = 16;
+
+/// An item of the games item schema (scripts/items/items_game.txt)
+#[derive(Debug, Clone)]
+pub struct ItemDefinition {
+    /// Item definition index
+    pub id: u16,
+
+    /// Internal name (e.g. weapon_ak47)
+    pub name: String,
+
+    /// Localization token of the display name (e.g. #SFUI_WPNHUD_AK47)
+    pub item_name: Option<String>,
+    pub item_class: Option<String>,
+
+    /// Weapon category (e.g. Rifle or SubMachinegun)
+    pub weapon_type: Option<String>,
+}
+
+impl ItemDefinition {
+    pub fn is_weapon(&self) -> bool {
+        self.name.starts_with("weapon_")
+    }
+}
+
+struct ItemSchemaLookup<'a> {
+    prefabs: HashMap<String, &'a KV1Value>,
+}
+
+impl<'a> ItemSchemaLookup<'a> {
+    /// Lookup a value of the item or one of its prefabs
+    fn lookup(&self, item: &'a KV1Value, path: &[&str], depth: usize) -> Option<&'a str> {
+        let mut value = Some(item);
+        for key in path {
+            value = value.and_then(|value| value.get(key));
+        }
+
+        if let Some(value) = value.and_then(KV1Value::as_str) {
+            return Some(value);
+        }
+
+        if depth >= MAX_PREFAB_DEPTH {
+            log::warn!("Prefab depth exceeded while resolving {}", path.join("/"));
+            return None;
+        }
+
+        item.get_str("prefab")?
+            .split_whitespace()
+            .filter_map(|prefab| self.prefabs.get(&prefab.to_ascii_lowercase()))
+            .find_map(|prefab| self.lookup(prefab, path, depth + 1))
+    }
+}
+
+/// Read all item definitions of the item schema.
+/// `items_game` must be the value of the `items_game` root key.
+pub fn parse_item_definitions(items_game: &KV1Value) -> Vec<ItemDefinition> {
+    let lookup = ItemSchemaLookup {
+        prefabs: items_game
+            .get_all("prefabs")
+            .flat_map(KV1Value::entries)
+            .map(|(name, prefab)| (name.to_ascii_lowercase(), prefab))
+            .collect(),
+    };
+
+    let mut result = Vec::new();
+    for (id, item) in items_game.get_all("items").flat_map(KV1Value::entries) {
+        let Ok(id) = id.parse::<u16>() else {
+            continue;
+        };
+
+        let Some(name) = lookup.lookup(item, &["name"], 0) else {
+            continue;
+        };
+
+        result.push(ItemDefinition {
+            id,
+            name: name.to_string(),
+            item_name: lookup.lookup(item, &["item_name"], 0).map(str::to_string),
+            item_class: lookup.lookup(item, &["item_class"], 0).map(str::to_string),
+            weapon_type: lookup
+                .lookup(item, &["visuals", "weapon_type"], 0)
+                .map(str::to_string),
+        });
+    }
+
+    result.sort_by_key(|item| item.id);
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_item_definitions;
+    use crate::KV1Value;
+
+    const ITEMS_GAME: &str = r##"
+"items_game"
+{
+    "prefabs"
+    {
+        "weapon_base"
+        {
+            "item_class" "weapon_base"
+        }
+        "Primary"
+        {
+            "prefab" "weapon_base"
+        }
+        "rifle"
+        {
+            "prefab" "primary"
+            "visuals" { "weapon_type" "Rifle" }
+        }
+        "statted_item_base"
+        {
+            "item_name" "#prefab_name"
+        }
+    }
+    "items"
+    {
+        "default" { "name" "default" }
+        "7"
+        {
+            "name" "weapon_ak47"
+            "prefab" "rifle statted_item_base"
+            "item_name" "#SFUI_WPNHUD_AK47"
+            "item_class" "weapon_ak47"
+        }
+        "1"
+        {
+            "name" "weapon_deagle"
+            "prefab" "missing statted_item_base"
+            "visuals" { "weapon_type" "Pistol" }
+        }
+        "9" { "prefab" "rifle" }
+    }
+    "items"
+    {
+        "1201" { "name" "sticker_capsule" }
+    }
+}
+"##;
+
+    #[test]
+    fn test_item_definitions() {
+        let entries = KV1Value::parse(ITEMS_GAME).unwrap();
+        let items = parse_item_definitions(&entries[0].1);
+
+        /* items without a numeric id or a name are skipped */
+        assert_eq!(
+            items.iter().map(|item| item.id).collect::<Vec<_>>(),
+            vec![1, 7, 1201]
+        );
+
+        let deagle = &items[0];
+        assert_eq!(deagle.name, "weapon_deagle");
+        assert_eq!(deagle.item_name.as_deref(), Some("#prefab_name"));
+        assert_eq!(deagle.item_class, None);
+        assert_eq!(deagle.weapon_type.as_deref(), Some("Pistol"));
+        assert!(deagle.is_weapon());
+
+        /* values of the item take precedence over its (nested) prefabs */
+        let ak47 = &items[1];
+        assert_eq!(ak47.name, "weapon_ak47");
+        assert_eq!(ak47.item_name.as_deref(), Some("#SFUI_WPNHUD_AK47"));
+        assert_eq!(ak47.item_class.as_deref(), Some("weapon_ak47"));
+        assert_eq!(ak47.weapon_type.as_deref(), Some("Rifle"));
+
+        let capsule = &items[2];
+        assert_eq!(capsule.item_class, None);
+        assert!(!capsule.is_weapon());
+    }
+
+    #[test]
+    fn test_prefab_inheritance() {
+        let entries = KV1Value::parse(
+            r#"items_game { prefabs { a { prefab b } b { item_class from_b } } items { 1 { name weapon_test prefab A } } }"#,
+        )
+        .unwrap();
+        let items = parse_item_definitions(&entries[0].1);
+        assert_eq!(items[0].item_class.as_deref(), Some("from_b"));
+
+        /* recursive prefabs must not overflow the stack */
+        let entries = KV1Value::parse(
+            r#"items_game { prefabs { a { prefab a } } items { 1 { name weapon_test prefab a } } }"#,
+        )
+        .unwrap();
+        let items = parse_item_definitions(&entries[0].1);
+        assert_eq!(items[0].item_class, None);
+    }
+}
@@ -0,0 +1,303 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum KV1Error {
+    #[error("unexpected end of file")]
+    UnexpectedEof,
+
+    #[error("unterminated string starting at line {0}")]
+    UnterminatedString(usize),
+
+    #[error("unexpected token {token:?} at line {line}")]
+    UnexpectedToken { token: String, line: usize },
+}
+
+/// Text based KeyValues value (e.g. items_game.txt or localization files).
+/// Keys may occur multiple times within an object, therefore the entries are kept in order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KV1Value {
+    String(String),
+    Object(Vec<(String, KV1Value)>),
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    String(String),
+    ObjectStart,
+    ObjectEnd,
+}
+
+struct Tokenizer<'a> {
+    input: &'a str,
+    position: usize,
+    line: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input: input.trim_start_matches('\u{FEFF}'),
+            position: 0,
+            line: 1,
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        let bytes = self.input.as_bytes();
+        while self.position < bytes.len() {
+            match bytes[self.position] {
+                b'\n' => {
+                    self.line += 1;
+                    self.position += 1;
+                }
+                b' ' | b'\t' | b'\r' => self.position += 1,
+                b'/' if bytes.get(self.position + 1) == Some(&b'/') => {
+                    while self.position < bytes.len() && bytes[self.position] != b'\n' {
+                        self.position += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    /// Returns the next token and the line it started on
+    fn next_token(&mut self) -> Result<Option<(Token, usize)>, KV1Error> {
+        loop {
+            self.skip_whitespace_and_comments();
+
+            let bytes = self.input.as_bytes();
+            let Some(current) = bytes.get(self.position) else {
+                return Ok(None);
+            };
+
+            let line = self.line;
+            match current {
+                b'{' => {
+                    self.position += 1;
+                    return Ok(Some((Token::ObjectStart, line)));
+                }
+                b'}' => {
+                    self.position += 1;
+                    return Ok(Some((Token::ObjectEnd, line)));
+                }
+                b'[' => {
+                    /* conditionals like [$WIN32] are not evaluated */
+                    while self.position < bytes.len() && bytes[self.position] != b']' {
+                        self.position += 1;
+                    }
+                    self.position += 1;
+                }
+                b'"' => return self.read_quoted(line).map(|value| Some((value, line))),
+                _ => {
+                    let start = self.position;
+                    while self.position < bytes.len()
+                        && !matches!(
+                            bytes[self.position],
+                            b' ' | b'\t' | b'\r' | b'\n' | b'"' | b'{' | b'}'
+                        )
+                    {
+                        self.position += 1;
+                    }
+
+                    let value = self.input[start..self.position].to_string();
+                    return Ok(Some((Token::String(value), line)));
+                }
+            }
+        }
+    }
+
+    fn read_quoted(&mut self, line: usize) -> Result<Token, KV1Error> {
+        /* skip the opening quote */
+        self.position += 1;
+
+        let mut value = String::new();
+        let mut chars = self.input[self.position..].char_indices();
+        while let Some((offset, char)) = chars.next() {
+            match char {
+                '"' => {
+                    self.position += offset + 1;
+                    return Ok(Token::String(value));
+                }
+                '\\' => {
+                    let Some((_, escaped)) = chars.next() else {
+                        break;
+                    };
+
+                    match escaped {
+                        'n' => value.push('\n'),
+                        't' => value.push('\t'),
+                        '\\' => value.push('\\'),
+                        '"' => value.push('"'),
+                        escaped => {
+                            value.push('\\');
+                            value.push(escaped);
+                        }
+                    }
+                }
+                '\n' => {
+                    self.line += 1;
+                    value.push(char);
+                }
+                char => value.push(char),
+            }
+        }
+
+        Err(KV1Error::UnterminatedString(line))
+    }
+}
+
+impl KV1Value {
+    /// Parse a KeyValues document.
+    /// Returns all root entries (usually only one).
+    pub fn parse(input: &str) -> Result<Vec<(String, KV1Value)>, KV1Error> {
+        let mut tokenizer = Tokenizer::new(input);
+        Self::parse_entries(&mut tokenizer, true)
+    }
+
+    fn parse_entries(
+        tokenizer: &mut Tokenizer,
+        root: bool,
+    ) -> Result<Vec<(String, KV1Value)>, KV1Error> {
+        let mut entries = Vec::new();
+        loop {
+            let key = match tokenizer.next_token()? {
+                Some((Token::String(key), _)) => key,
+                Some((Token::ObjectEnd, _)) if !root => return Ok(entries),
+                None if root => return Ok(entries),
+                None => return Err(KV1Error::UnexpectedEof),
+                Some((token, line)) => {
+                    return Err(KV1Error::UnexpectedToken {
+                        token: format!("{:?}", token),
+                        line,
+                    })
+                }
+            };
+
+            let value = match tokenizer.next_token()? {
+                Some((Token::String(value), _)) => KV1Value::String(value),
+                Some((Token::ObjectStart, _)) => {
+                    KV1Value::Object(Self::parse_entries(tokenizer, false)?)
+                }
+                None => return Err(KV1Error::UnexpectedEof),
+                Some((token, line)) => {
+                    return Err(KV1Error::UnexpectedToken {
+                        token: format!("{:?}", token),
+                        line,
+                    })
+                }
+            };
+
+            entries.push((key, value));
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            Self::Object(_) => None,
+        }
+    }
+
+    pub fn entries(&self) -> &[(String, KV1Value)] {
+        match self {
+            Self::String(_) => &[],
+            Self::Object(entries) => entries,
+        }
+    }
+
+    /// Get the first value of the key (keys are case insensitive)
+    pub fn get(&self, key: &str) -> Option<&KV1Value> {
+        self.entries()
+            .iter()
+            .find(|(entry_key, _)| entry_key.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    /// Get all values of the key (keys are case insensitive)
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a KV1Value> + 'a {
+        self.entries()
+            .iter()
+            .filter(move |(entry_key, _)| entry_key.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key)?.as_str()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        KV1Error,
+        KV1Value,
+    };
+
+    fn string(value: &str) -> KV1Value {
+        KV1Value::String(value.to_string())
+    }
+
+    #[test]
+    fn test_parse_values() {
+        let entries = KV1Value::parse(
+            "\u{FEFF}\"root\"\n{\n    // a comment\n    \"quoted\" \"value with spaces\"\n    unquoted value\n    \"escaped\" \"a\\\"b\\\\c\\nd\\x\"\n    \"conditional\" \"1\" [$WIN32]\n    \"nested\" { \"key\" \"value\" }\n}\n",
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 1);
+        let (key, root) = &entries[0];
+        assert_eq!(key, "root");
+        assert_eq!(
+            root,
+            &KV1Value::Object(vec![
+                ("quoted".to_string(), string("value with spaces")),
+                ("unquoted".to_string(), string("value")),
+                ("escaped".to_string(), string("a\"b\\c\nd\\x")),
+                ("conditional".to_string(), string("1")),
+                (
+                    "nested".to_string(),
+                    KV1Value::Object(vec![("key".to_string(), string("value"))])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_lookup() {
+        let entries = KV1Value::parse("root { Key 1 other 2 KEY 3 child { value 4 } }").unwrap();
+        let root = &entries[0].1;
+
+        assert_eq!(root.get_str("key"), Some("1"));
+        assert_eq!(
+            root.get_all("key")
+                .filter_map(KV1Value::as_str)
+                .collect::<Vec<_>>(),
+            vec!["1", "3"]
+        );
+        assert_eq!(root.get_str("child"), None);
+        assert_eq!(root.get("CHILD").unwrap().get_str("value"), Some("4"));
+        assert!(root.get("missing").is_none());
+        assert!(string("value").entries().is_empty());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            KV1Value::parse("root {\n key value\n"),
+            Err(KV1Error::UnexpectedEof)
+        ));
+        assert!(matches!(
+            KV1Value::parse("root"),
+            Err(KV1Error::UnexpectedEof)
+        ));
+        assert!(matches!(
+            KV1Value::parse("root {\n key \"value\n}"),
+            Err(KV1Error::UnterminatedString(2))
+        ));
+        assert!(matches!(
+            KV1Value::parse("root {\n }\n}"),
+            Err(KV1Error::UnexpectedToken { line: 3, .. })
+        ));
+    }
+}
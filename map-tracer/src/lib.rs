@@ -31,6 +31,15 @@ pub use resource::*;
 mod kv3;
 pub use kv3::*;
 
+mod kv1;
+pub use kv1::*;
+
+mod item_schema;
+pub use item_schema::*;
+
+mod localization;
+pub use localization::*;
+
 mod util;
 
 pub struct VPKArchiveReader<R> {
@@ -113,4 +122,40 @@ where
         }
         Ok(buffer)
     }
+
+    /// Read an entry which may be stored in one of the numbered archives (e.g. pak01_003.vpk).
+    /// `open_archive` will be called with the archive index if the entry is not stored in this archive.
+    pub fn read_entry_with<A: Read + Seek>(
+        &mut self,
+        name: &str,
+        open_archive: impl FnOnce(u16) -> VResult<A>,
+    ) -> VResult<Vec<u8>> {
+        let entry = self.entries.get(name).ok_or(VPKError::EntryUnknown)?;
+
+        let mut buffer = entry.preload_bytes.clone().unwrap_or_default();
+        let preload_length = buffer.len();
+        buffer.resize(preload_length + entry.entry_length as usize, 0u8);
+
+        if entry.entry_length > 0 {
+            let target = &mut buffer[preload_length..];
+            if entry.archive_index == 0x7FFF {
+                let position = self.data_offset + entry.entry_offset as u64;
+                self.reader.seek(std::io::SeekFrom::Start(position))?;
+                self.reader.read_exact(target)?;
+            } else {
+                let mut archive = open_archive(entry.archive_index)?;
+                archive.seek(std::io::SeekFrom::Start(entry.entry_offset as u64))?;
+                archive.read_exact(target)?;
+            }
+        }
+
+        let buffer_crc = VALVE_CRC32.checksum(&buffer);
+        if buffer_crc != entry.crc {
+            return Err(VPKError::EntryCrcMissmatch {
+                expected: entry.crc,
+                calculated: buffer_crc,
+            });
+        }
+        Ok(buffer)
+    }
 }
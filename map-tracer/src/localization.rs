@@ -0,0 +1,240 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+};
+
+use thiserror::Error;
+
+use crate::{
+    KV1Error,
+    KV1Value,
+};
+
+/// Language the game falls back to if a token has not been translated
+pub const LOCALIZATION_FALLBACK_LANGUAGE: &str = "english";
+
+#[derive(Debug, Error)]
+pub enum LocalizationError {
+    #[error("io error")]
+    IOError(#[from] std::io::Error),
+
+    #[error("failed to parse localization file: {0}")]
+    ParseError(#[from] KV1Error),
+
+    #[error("missing lang/Tokens section")]
+    MissingTokens,
+}
+
+/// Localization tokens of the game (resource/*_<language>.txt).
+#[derive(Debug, Clone, Default)]
+pub struct Localization {
+    language: String,
+
+    /// Lower case token name (without the leading #) to the localized value
+    tokens: HashMap<String, String>,
+}
+
+/// Decode a localization file. The game ships them as UTF-16LE or UTF-8 with BOM.
+fn decode_localization_file(data: &[u8]) -> String {
+    if data.starts_with(&[0xFF, 0xFE]) {
+        let data = data[2..]
+            .chunks_exact(2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+            .collect::<Vec<_>>();
+        String::from_utf16_lossy(&data)
+    } else {
+        String::from_utf8_lossy(data).to_string()
+    }
+}
+
+fn normalize_token(token: &str) -> String {
+    token.trim_start_matches('#').to_ascii_lowercase()
+}
+
+impl Localization {
+    pub fn new(language: &str) -> Self {
+        Self {
+            language: language.to_ascii_lowercase(),
+            tokens: Default::default(),
+        }
+    }
+
+    /// Load all localization files of the resource directory for the target language.
+    /// Tokens which have not been translated will fall back to english.
+    pub fn load_resource_directory(
+        resource_directory: &Path,
+        language: &str,
+    ) -> Result<Self, LocalizationError> {
+        let mut result = Self::new(language);
+
+        let mut languages = vec![LOCALIZATION_FALLBACK_LANGUAGE.to_string()];
+        if result.language != LOCALIZATION_FALLBACK_LANGUAGE {
+            languages.push(result.language.clone());
+        }
+
+        for language in languages.iter() {
+            let suffix = format!("_{}.txt", language);
+            for entry in fs::read_dir(resource_directory)? {
+                let path = entry?.path();
+                let is_language_file = path
+                    .file_name()
+                    .map(|name| {
+                        name.to_string_lossy()
+                            .to_ascii_lowercase()
+                            .ends_with(&suffix)
+                    })
+                    .unwrap_or(false);
+
+                if !is_language_file || !path.is_file() {
+                    continue;
+                }
+
+                log::debug!("Loading localization file {}", path.to_string_lossy());
+                if let Err(error) = result.load_file(&path) {
+                    log::warn!(
+                        "Failed to load localization file {}: {}",
+                        path.to_string_lossy(),
+                        error
+                    );
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// List all languages which have localization files within the resource directory
+    pub fn available_languages(
+        resource_directory: &Path,
+    ) -> Result<Vec<String>, LocalizationError> {
+        let mut languages = Vec::new();
+        for entry in fs::read_dir(resource_directory)? {
+            let file_name = entry?.file_name().to_string_lossy().to_ascii_lowercase();
+            let Some(language) = file_name
+                .strip_suffix(".txt")
+                .and_then(|name| name.rsplit_once('_'))
+                .map(|(_, language)| language)
+            else {
+                continue;
+            };
+
+            if !languages.iter().any(|known| known == language) {
+                languages.push(language.to_string());
+            }
+        }
+
+        languages.sort();
+        Ok(languages)
+    }
+
+    /// Load a single localization file.
+    /// Already known tokens will be overridden.
+    pub fn load_file(&mut self, path: &Path) -> Result<(), LocalizationError> {
+        let data = fs::read(path)?;
+        self.load_data(&data)
+    }
+
+    pub fn load_data(&mut self, data: &[u8]) -> Result<(), LocalizationError> {
+        let root = KV1Value::parse(&decode_localization_file(data))?;
+        let tokens = root
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("lang"))
+            .and_then(|(_, lang)| lang.get("Tokens"))
+            .ok_or(LocalizationError::MissingTokens)?;
+
+        for (key, value) in tokens.entries() {
+            let Some(value) = value.as_str() else {
+                continue;
+            };
+
+            self.tokens.insert(normalize_token(key), value.to_string());
+        }
+
+        Ok(())
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn token_count(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Lookup a token (e.g. #SFUI_WPNHUD_AK47). Tokens are case insensitive.
+    pub fn lookup(&self, token: &str) -> Option<&str> {
+        self.tokens.get(&normalize_token(token)).map(String::as_str)
+    }
+
+    /// Lookup a token and use the fallback if the token is unknown
+    pub fn lookup_or<'a>(&'a self, token: &str, fallback: &'a str) -> &'a str {
+        self.lookup(token).unwrap_or(fallback)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        Localization,
+        LocalizationError,
+    };
+
+    const ENGLISH: &str = "\"lang\"\n{\n    \"Language\" \"English\"\n    \"Tokens\"\n    {\n        \"SFUI_WPNHUD_AK47\" \"AK-47\"\n        \"SFUI_Map_de_dust2\" \"Dust II\"\n    }\n}\n";
+
+    fn utf16_with_bom(value: &str) -> Vec<u8> {
+        let mut data = vec![0xFF, 0xFE];
+        for char in value.encode_utf16() {
+            data.extend_from_slice(&char.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_load_utf16() {
+        let mut localization = Localization::new("German");
+        assert_eq!(localization.language(), "german");
+
+        localization
+            .load_data(&utf16_with_bom(&ENGLISH.replace("AK-47", "AK-47 Größe")))
+            .unwrap();
+        assert_eq!(localization.token_count(), 2);
+
+        /* tokens are case insensitive and the leading # is optional */
+        assert_eq!(
+            localization.lookup("#SFUI_WPNHUD_AK47"),
+            Some("AK-47 Größe")
+        );
+        assert_eq!(localization.lookup("sfui_wpnhud_ak47"), Some("AK-47 Größe"));
+        assert_eq!(localization.lookup("#SFUI_MAP_DE_DUST2"), Some("Dust II"));
+        assert_eq!(localization.lookup("#SFUI_WPNHUD_M4A1"), None);
+        assert_eq!(localization.lookup_or("#SFUI_WPNHUD_M4A1", "M4A4"), "M4A4");
+    }
+
+    #[test]
+    fn test_load_utf8_override() {
+        let mut localization = Localization::new("english");
+        localization
+            .load_data(format!("\u{FEFF}{}", ENGLISH).as_bytes())
+            .unwrap();
+        localization
+            .load_data(b"lang { Tokens { sfui_wpnhud_ak47 \"AK\" } }")
+            .unwrap();
+
+        assert_eq!(localization.token_count(), 2);
+        assert_eq!(localization.lookup("#SFUI_WPNHUD_AK47"), Some("AK"));
+    }
+
+    #[test]
+    fn test_missing_tokens() {
+        let mut localization = Localization::new("english");
+        assert!(matches!(
+            localization.load_data(b"lang { Language English }"),
+            Err(LocalizationError::MissingTokens)
+        ));
+        assert!(matches!(
+            localization.load_data(b"lang {"),
+            Err(LocalizationError::ParseError(_))
+        ));
+    }
+}
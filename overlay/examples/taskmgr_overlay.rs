@@ -1,5 +1,23 @@
+use std::{
+    cell::Cell,
+    rc::Rc,
+};
+
+use imgui::TextureId;
 use overlay::OverlayTarget;
 
+const GRADIENT_SIZE: u32 = 64;
+
+fn create_gradient() -> Vec<u8> {
+    let mut data = Vec::with_capacity((GRADIENT_SIZE * GRADIENT_SIZE * 4) as usize);
+    for y in 0..GRADIENT_SIZE {
+        for x in 0..GRADIENT_SIZE {
+            data.extend_from_slice(&[(x * 4) as u8, (y * 4) as u8, 0xFF, 0xFF]);
+        }
+    }
+    data
+}
+
 fn main() -> anyhow::Result<()> {
     env_logger::builder()
         .filter_level(log::LevelFilter::Trace)
@@ -7,26 +25,40 @@ fn main() -> anyhow::Result<()> {
         .init();
 
     log::info!("Initialize overlay");
-    let overlay = overlay::init(&overlay::OverlayOptions {
+    #[allow(deprecated)]
+    let overlay_options = overlay::OverlayOptions {
         title: "Task Manager Overlay".to_string(),
         target: OverlayTarget::WindowTitle("Task Manager".into()),
-        font_init: Some(Box::new(|_imgui| {
-            // imgui.fonts().add_font(font_sources)
-            // imgui.fonts().add_font(&[FontSource::TtfData {
-            //     data: include_bytes!("../resources/unifont-15.1.03.otf"),
-            //     size_pixels: 16.0,
-            //     config: Some(FontConfig {
-            //         glyph_ranges: FontGlyphRanges::from_slice(&[0x0001, 0xFFFF, 0x0000]),
-            //         ..FontConfig::default()
-            //     }),
-            // }]);
-        })),
-    })?;
+        font_init: None,
+    };
+    let overlay = overlay::init(&overlay_options)?;
     let mut text_input = Default::default();
+
+    /* textures will be released when the swapchain gets recreated */
+    let gradient_texture = Rc::new(Cell::new(None::<(u64, TextureId)>));
     overlay.main_loop(
-        |controller| {
-            controller.toggle_debug_overlay(true);
-            true
+        {
+            let gradient_texture = gradient_texture.clone();
+            move |controller| {
+                controller.toggle_debug_overlay(true);
+
+                let generation = controller.textures.generation();
+                let texture_valid = gradient_texture
+                    .get()
+                    .map(|(texture_generation, _)| texture_generation == generation)
+                    .unwrap_or(false);
+                if !texture_valid {
+                    match controller.textures.upload_rgba8(
+                        GRADIENT_SIZE,
+                        GRADIENT_SIZE,
+                        &create_gradient(),
+                    ) {
+                        Ok(texture_id) => gradient_texture.set(Some((generation, texture_id))),
+                        Err(error) => log::warn!("Failed to upload texture: {}", error),
+                    }
+                }
+                true
+            }
         },
         move |ui| {
             ui.window("Dummy Window")
@@ -47,6 +79,10 @@ fn main() -> anyhow::Result<()> {
                     ui.text("﷽, ♛ LAZ ♛,  ♛ ॐ,  ♛ ॐ");
                     ui.text(" ♣▄♠░ ");
                     ui.text("♣♠░:D ︻デ── ");
+
+                    if let Some((_, texture_id)) = gradient_texture.get() {
+                        imgui::Image::new(texture_id, [64.0, 64.0]).build(ui);
+                    }
                 });
             true
         },
@@ -53,4 +53,16 @@ pub enum OverlayError {
 
     #[error("failed to create a vulkan surface: {0}")]
     VulkanSurfaceCreationFailed(VkResult),
+
+    #[error("invalid texture data size (expected {expected} bytes, got {actual})")]
+    TextureInvalidDataSize { expected: usize, actual: usize },
+
+    #[error("the texture does not exist")]
+    TextureUnknown,
+
+    #[error("the max number of textures has been reached")]
+    TextureLimitReached,
+
+    #[error("failed to write trace: {0}")]
+    TraceIoError(std::io::Error),
 }
@@ -0,0 +1,291 @@
+use std::{
+    cell::Cell,
+    fs,
+    path::Path,
+    rc::Rc,
+    sync::Arc,
+};
+
+use imgui::{
+    Context,
+    FontConfig,
+    FontGlyphRanges,
+    FontId,
+    FontSource,
+};
+
+/// Font size of the default font.
+/// Note: imgui_winit_support uses "logical pixels", therefore this value does not need to be scaled by the devices scaling factor.
+pub const DEFAULT_FONT_SIZE: f32 = 18.0;
+
+/// Glyph ranges of the default font (the whole BMP)
+const DEFAULT_FONT_GLYPH_RANGES: &[u32] = &[0x0001, 0xFFFF, 0];
+
+/// Glyph ranges of the default font when using `FontManager::set_compact_default_font`.
+/// Rasterizing the whole BMP of unifont results in a huge font atlas, especially when scaled.
+/// Fonts for other scripts (e.g. CJK) can be merged into the default font using `FontManager::merge_font`.
+pub const COMPACT_FONT_GLYPH_RANGES: &[u32] = &[
+    0x0020, 0x024F, // Basic Latin, Latin-1 Supplement, Latin Extended-A and B
+    0x0370, 0x03FF, // Greek and Coptic
+    0x0400, 0x052F, // Cyrillic and Cyrillic Supplement
+    0x0590, 0x06FF, // Hebrew and Arabic
+    0x0E00, 0x0E7F, // Thai
+    0x1E00, 0x1EFF, // Latin Extended Additional
+    0x2000, 0x206F, // General Punctuation
+    0x20A0, 0x20CF, // Currency Symbols
+    0x2100, 0x21FF, // Letterlike Symbols, Number Forms and Arrows
+    0x2200, 0x22FF, // Mathematical Operators
+    0x2500, 0x25FF, // Box Drawing, Block Elements and Geometric Shapes
+    0x2600, 0x26FF, // Miscellaneous Symbols
+    0xFFFD, 0xFFFD, // Replacement Character
+    0,
+];
+
+/// TTF/OTF font data
+#[derive(Clone)]
+pub enum FontData {
+    Static(&'static [u8]),
+    Shared(Arc<[u8]>),
+}
+
+impl FontData {
+    pub fn from_file(path: &Path) -> std::io::Result<Self> {
+        Ok(Self::Shared(fs::read(path)?.into()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Static(data) => data,
+            Self::Shared(data) => data,
+        }
+    }
+}
+
+/// A single font file which will be rasterized into the font atlas
+#[derive(Clone)]
+pub struct OverlayFontSource {
+    pub data: FontData,
+    pub config: FontConfig,
+}
+
+impl OverlayFontSource {
+    pub fn new(data: FontData) -> Self {
+        Self {
+            data,
+            config: FontConfig::default(),
+        }
+    }
+
+    pub fn with_config(data: FontData, config: FontConfig) -> Self {
+        Self { data, config }
+    }
+}
+
+/// Reference to a registered font.
+/// The actual font id changes every time the font atlas gets rebuild.
+#[derive(Clone, Default)]
+pub struct FontReference {
+    font_id: Rc<Cell<Option<FontId>>>,
+}
+
+impl FontReference {
+    /// Current font id or None if the font atlas has not yet been build
+    pub fn font_id(&self) -> Option<FontId> {
+        self.font_id.get()
+    }
+}
+
+struct RegisteredFont {
+    /// All sources will be merged into one font (e.g. a text font and an icon font)
+    sources: Vec<OverlayFontSource>,
+    size: f32,
+    reference: FontReference,
+}
+
+/// Fonts of the overlay.
+/// Changes will be applied by rebuilding the font atlas before the next frame.
+pub struct FontManager {
+    fonts: Vec<RegisteredFont>,
+    default_font: FontReference,
+    compact_default_font: bool,
+
+    scale: f32,
+    dirty: bool,
+
+    /// Fonts have been added using the deprecated `OverlayOptions::font_init`
+    legacy_fonts: bool,
+}
+
+impl FontManager {
+    pub(crate) fn new() -> Self {
+        let mut result = Self {
+            fonts: Vec::new(),
+            default_font: Default::default(),
+            compact_default_font: false,
+
+            scale: 1.0,
+            dirty: true,
+
+            legacy_fonts: false,
+        };
+
+        result.default_font = result.register_font(
+            vec![OverlayFontSource::with_config(
+                FontData::Static(include_bytes!("../resources/unifont-15.1.03.otf")),
+                FontConfig {
+                    glyph_ranges: FontGlyphRanges::from_slice(DEFAULT_FONT_GLYPH_RANGES),
+                    ..FontConfig::default()
+                },
+            )],
+            DEFAULT_FONT_SIZE,
+        );
+        result
+    }
+
+    /// The first registered font which will be used by default
+    pub fn default_font(&self) -> &FontReference {
+        &self.default_font
+    }
+
+    pub fn is_compact_default_font(&self) -> bool {
+        self.compact_default_font
+    }
+
+    /// Only rasterize the glyphs of common scripts for the default font (see `COMPACT_FONT_GLYPH_RANGES`).
+    /// This reduces the size of the font atlas, but characters of other scripts will not be rendered.
+    pub fn set_compact_default_font(&mut self, compact: bool) {
+        if self.compact_default_font == compact {
+            return;
+        }
+
+        let glyph_ranges = if compact {
+            COMPACT_FONT_GLYPH_RANGES
+        } else {
+            DEFAULT_FONT_GLYPH_RANGES
+        };
+
+        let default_font = self.default_font.clone();
+        if let Some(source) = self
+            .find_font_mut(&default_font)
+            .and_then(|font| font.sources.first_mut())
+        {
+            source.config.glyph_ranges = FontGlyphRanges::from_slice(glyph_ranges);
+        }
+
+        self.compact_default_font = compact;
+        self.dirty = true;
+    }
+
+    /// Register a font with the given size (in unscaled pixels).
+    /// Register the same sources multiple times to use the font with different sizes.
+    pub fn register_font(&mut self, sources: Vec<OverlayFontSource>, size: f32) -> FontReference {
+        let reference = FontReference::default();
+        self.fonts.push(RegisteredFont {
+            sources,
+            size,
+            reference: reference.clone(),
+        });
+        self.dirty = true;
+        reference
+    }
+
+    /// Register a TTF/OTF font file from disk
+    pub fn register_font_file(&mut self, path: &Path, size: f32) -> std::io::Result<FontReference> {
+        let data = FontData::from_file(path)?;
+        Ok(self.register_font(vec![OverlayFontSource::new(data)], size))
+    }
+
+    /// Merge an additional font (e.g. an icon font) into an already registered font
+    pub fn merge_font(&mut self, target: &FontReference, source: OverlayFontSource) -> bool {
+        let Some(font) = self.find_font_mut(target) else {
+            return false;
+        };
+
+        font.sources.push(source);
+        self.dirty = true;
+        true
+    }
+
+    pub fn unregister_font(&mut self, target: &FontReference) {
+        if Rc::ptr_eq(&target.font_id, &self.default_font.font_id) {
+            log::warn!("The default font can not be unregistered");
+            return;
+        }
+
+        self.fonts
+            .retain(|font| !Rc::ptr_eq(&font.reference.font_id, &target.font_id));
+        target.font_id.set(None);
+        self.dirty = true;
+    }
+
+    pub fn set_font_size(&mut self, target: &FontReference, size: f32) {
+        if let Some(font) = self.find_font_mut(target) {
+            if font.size != size {
+                font.size = size;
+                self.dirty = true;
+            }
+        }
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Scale all font sizes (e.g. for high resolution monitors)
+    pub fn set_scale(&mut self, scale: f32) {
+        let scale = scale.clamp(0.25, 4.0);
+        if self.scale != scale {
+            self.scale = scale;
+            self.dirty = true;
+        }
+    }
+
+    /// Returns true if the font atlas needs to be rebuild
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn find_font_mut(&mut self, target: &FontReference) -> Option<&mut RegisteredFont> {
+        self.fonts
+            .iter_mut()
+            .find(|font| Rc::ptr_eq(&font.reference.font_id, &target.font_id))
+    }
+
+    pub(crate) fn mark_legacy_fonts(&mut self) {
+        self.legacy_fonts = true;
+    }
+
+    /// Recreate the font atlas with all registered fonts.
+    /// The font texture must be uploaded again afterwards.
+    pub(crate) fn rebuild_atlas(&mut self, imgui: &mut Context) {
+        if self.legacy_fonts {
+            log::warn!("Rebuilding the font atlas discards all fonts added by OverlayOptions::font_init. Register them using the FontManager instead.");
+            self.legacy_fonts = false;
+        }
+
+        let atlas = imgui.fonts();
+        atlas.clear();
+
+        for font in self.fonts.iter() {
+            let sources = font
+                .sources
+                .iter()
+                .map(|source| FontSource::TtfData {
+                    data: source.data.as_bytes(),
+                    size_pixels: font.size * self.scale,
+                    config: Some(source.config.clone()),
+                })
+                .collect::<Vec<_>>();
+
+            let font_id = atlas.add_font(&sources);
+            font.reference.font_id.set(Some(font_id));
+        }
+
+        log::debug!(
+            "Rebuild font atlas with {} fonts (scale {:.2})",
+            self.fonts.len(),
+            self.scale
+        );
+        self.dirty = false;
+    }
+}
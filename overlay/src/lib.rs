@@ -1,5 +1,6 @@
 use std::{
     ffi::CString,
+    path::PathBuf,
     time::Instant,
 };
 
@@ -8,9 +9,6 @@ use clipboard::ClipboardSupport;
 use copypasta::ClipboardContext;
 use imgui::{
     Context,
-    FontConfig,
-    FontGlyphRanges,
-    FontSource,
     Io,
 };
 use imgui_rs_vulkan_renderer::{
@@ -104,6 +102,15 @@ mod vulkan;
 mod perf;
 pub use perf::PerfTracker;
 
+mod trace;
+pub use trace::*;
+
+mod font;
+pub use font::*;
+
+mod texture;
+pub use texture::*;
+
 mod vulkan_render;
 use vulkan_render::*;
 
@@ -170,10 +177,17 @@ fn create_window(event_loop: &EventLoop<()>, title: &str) -> Result<Window> {
 pub struct OverlayOptions {
     pub title: String,
     pub target: OverlayTarget,
-    pub font_init: Option<Box<dyn Fn(&mut imgui::Context) -> ()>>,
+
+    /// Add fonts to the initial font atlas.
+    /// Fonts added by this callback will be lost when the font atlas gets rebuild.
+    #[deprecated(note = "register fonts using System::fonts instead")]
+    pub font_init: Option<Box<dyn Fn(&mut imgui::Context)>>,
 }
 
-fn create_imgui_context(options: &OverlayOptions) -> Result<(WinitPlatform, imgui::Context)> {
+fn create_imgui_context(
+    options: &OverlayOptions,
+    fonts: &mut FontManager,
+) -> Result<(WinitPlatform, imgui::Context)> {
     let mut imgui = Context::create();
     imgui.set_ini_filename(None);
 
@@ -184,38 +198,13 @@ fn create_imgui_context(options: &OverlayOptions) -> Result<(WinitPlatform, imgu
         Err(error) => log::warn!("Failed to initialize clipboard: {}", error),
     };
 
-    // Fixed font size. Note imgui_winit_support uses "logical
-    // pixels", which are physical pixels scaled by the devices
-    // scaling factor. Meaning, 13.0 pixels should look the same size
-    // on two different screens, and thus we do not need to scale this
-    // value (as the scaling is handled by winit)
-    let font_size = 18.0;
-    // imgui.fonts().add_font(&[FontSource::TtfData {
-    //     data: include_bytes!("../resources/Roboto-Regular.ttf"),
-    //     size_pixels: font_size,
-    //     config: Some(FontConfig {
-    //         // As imgui-glium-renderer isn't gamma-correct with
-    //         // it's font rendering, we apply an arbitrary
-    //         // multiplier to make the font a bit "heavier". With
-    //         // default imgui-glow-renderer this is unnecessary.
-    //         rasterizer_multiply: 1.5,
-    //         // Oversampling font helps improve text rendering at
-    //         // expense of larger font atlas texture.
-    //         oversample_h: 4,
-    //         oversample_v: 4,
-    //         ..FontConfig::default()
-    //     }),
-    // }]);
-    imgui.fonts().add_font(&[FontSource::TtfData {
-        data: include_bytes!("../resources/unifont-15.1.03.otf"),
-        size_pixels: font_size,
-        config: Some(FontConfig {
-            glyph_ranges: FontGlyphRanges::from_slice(&[0x0001, 0xFFFF, 0x0000]),
-            ..FontConfig::default()
-        }),
-    }]);
+    /* the initial font texture will be uploaded when creating the renderer */
+    fonts.rebuild_atlas(&mut imgui);
+
+    #[allow(deprecated)]
     if let Some(callback) = &options.font_init {
         callback(&mut imgui);
+        fonts.mark_legacy_fonts();
     }
 
     Ok((platform, imgui))
@@ -236,6 +225,8 @@ pub struct System {
 
     pub imgui: Context,
     pub renderer: Renderer,
+    pub fonts: FontManager,
+    pub textures: TextureRegistry,
 
     pub window_tracker: WindowTracker,
 }
@@ -282,7 +273,8 @@ pub fn init(options: &OverlayOptions) -> Result<System> {
         unsafe { vulkan_context.device.create_fence(&fence_info, None)? }
     };
 
-    let (mut platform, mut imgui) = create_imgui_context(&options)?;
+    let mut fonts = FontManager::new();
+    let (mut platform, mut imgui) = create_imgui_context(options, &mut fonts)?;
     platform.attach_window(imgui.io_mut(), &window, HiDpiMode::Default);
 
     let renderer = Renderer::with_default_allocator(
@@ -299,6 +291,8 @@ pub fn init(options: &OverlayOptions) -> Result<System> {
         }),
     )?;
 
+    let textures = TextureRegistry::new(&vulkan_context)?;
+
     /* The Vulkan backend can handle 32bit vertex offsets, but forgets to insert that flag... */
     imgui
         .io_mut()
@@ -319,6 +313,8 @@ pub fn init(options: &OverlayOptions) -> Result<System> {
         imgui,
         platform,
         renderer,
+        fonts,
+        textures,
 
         window_tracker,
     })
@@ -384,15 +380,24 @@ impl System {
             imgui,
             mut platform,
             mut renderer,
+            fonts,
+            textures,
 
             window_tracker,
             ..
         } = self;
         let mut last_frame = Instant::now();
 
+        /* the trace history will be enabled from the debug overlay */
+        let trace = TraceRecorder::new(0);
+        trace.set_thread_name("overlay");
+
         let mut runtime_controller = SystemRuntimeController {
             hwnd: HWND(window.hwnd() as isize),
             imgui,
+            fonts,
+            textures,
+            trace: trace.clone(),
 
             active_tracker: OverlayActiveTracker::new(),
             key_input_system: KeyboardInputSystem::new(),
@@ -405,7 +410,7 @@ impl System {
 
         let mut dirty_swapchain = false;
 
-        let mut perf = PerfTracker::new(PERF_RECORDS);
+        let mut perf = PerfTracker::with_trace(PERF_RECORDS, trace);
         event_loop.run(move |event, _, control_flow| {
             *control_flow = ControlFlow::Poll;
             platform.handle_event(runtime_controller.imgui.io_mut(), &window, &event);
@@ -442,6 +447,32 @@ impl System {
                         perf.mark("update");
                     }
 
+                    /* rebuild the font atlas (must be done outside of a frame) */
+                    if runtime_controller.fonts.is_dirty() {
+                        /* the current font texture might still be in use by the last frame */
+                        unsafe {
+                            vulkan_context
+                                .device
+                                .wait_for_fences(&[fence], true, std::u64::MAX)
+                                .expect("Failed to wait ")
+                        };
+
+                        runtime_controller
+                            .fonts
+                            .rebuild_atlas(&mut runtime_controller.imgui);
+                        if let Err(error) = renderer.update_fonts_texture(
+                            vulkan_context.graphics_queue,
+                            vulkan_context.command_pool,
+                            &mut runtime_controller.imgui,
+                        ) {
+                            *control_flow = ControlFlow::ExitWithCode(1);
+                            log::error!("Failed to upload the font texture: {}", error);
+                            return;
+                        }
+
+                        perf.mark("fonts");
+                    }
+
                     /* render */
                     {
                         // If swapchain must be recreated wait for windows to not be minimized anymore
@@ -451,6 +482,9 @@ impl System {
                                 swapchain
                                     .recreate(&vulkan_context)
                                     .expect("Failed to recreate swapchain");
+
+                                /* the device is idle after recreating the swapchain */
+                                runtime_controller.textures.release_all(renderer.textures());
                                 renderer
                                     .set_render_pass(swapchain.render_pass)
                                     .expect("Failed to rebuild renderer pipeline");
@@ -469,7 +503,10 @@ impl System {
                         }
 
                         let ui = runtime_controller.imgui.frame();
-                        let run = render(ui);
+                        let run = {
+                            let _span = perf.span("render ui");
+                            render(ui)
+                        };
                         if !run {
                             *control_flow = ControlFlow::ExitWithCode(0);
                             return;
@@ -498,6 +535,7 @@ impl System {
                                     {
                                         perf.set_history_length(history_length);
                                     }
+                                    render_trace_controls(ui, perf.trace());
                                     perf.render(ui, ui.content_region_avail());
                                 });
                         }
@@ -513,6 +551,10 @@ impl System {
                                 .expect("Failed to wait ")
                         };
 
+                        runtime_controller
+                            .textures
+                            .apply_changes(renderer.textures());
+
                         perf.mark("fence");
                         let next_image_result = unsafe {
                             swapchain.loader.acquire_next_image(
@@ -605,16 +647,74 @@ impl System {
                     event: WindowEvent::CloseRequested,
                     ..
                 } => *control_flow = ControlFlow::Exit,
+                Event::LoopDestroyed => {
+                    runtime_controller.textures.destroy(renderer.textures());
+                }
                 _ => {}
             }
         })
     }
 }
 
+fn trace_file_name(prefix: &str) -> String {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    format!("{}_{}.json", prefix, timestamp)
+}
+
+fn render_trace_controls(ui: &imgui::Ui, trace: &TraceRecorder) {
+    let mut record_history = trace.capacity() > 0;
+    if ui.checkbox("Record trace", &mut record_history) {
+        trace.set_capacity(if record_history {
+            TRACE_DEFAULT_CAPACITY
+        } else {
+            0
+        });
+    }
+
+    if record_history {
+        ui.same_line();
+        if ui.button("Export trace") {
+            let path = PathBuf::from(trace_file_name("overlay_trace"));
+            match trace.save_chrome_trace(&path) {
+                Ok(_) => log::info!("Exported overlay trace to {}", path.display()),
+                Err(error) => log::error!("Failed to export overlay trace: {}", error),
+            }
+        }
+    }
+
+    ui.same_line();
+    if let Some(capture_path) = trace.capture_path() {
+        if ui.button("Stop capture") {
+            match trace.stop_capture() {
+                Ok(_) => log::info!("Overlay trace written to {}", capture_path.display()),
+                Err(error) => log::error!("Failed to finish overlay trace capture: {}", error),
+            }
+        }
+
+        ui.same_line();
+        ui.text(format!("Capturing to {}", capture_path.display()));
+    } else if ui.button("Start capture") {
+        let path = PathBuf::from(trace_file_name("overlay_capture"));
+        match trace.start_capture(&path) {
+            Ok(_) => log::info!("Capturing overlay trace to {}", path.display()),
+            Err(error) => log::error!("Failed to start overlay trace capture: {}", error),
+        }
+    }
+}
+
 pub struct SystemRuntimeController {
     pub hwnd: HWND,
 
     pub imgui: imgui::Context,
+    pub fonts: FontManager,
+    pub textures: TextureRegistry,
+
+    /// Trace of the overlay render loop, can be used to record spans from other threads
+    pub trace: TraceRecorder,
     debug_overlay_shown: bool,
 
     active_tracker: OverlayActiveTracker,
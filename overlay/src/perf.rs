@@ -1,10 +1,20 @@
-use std::time::Instant;
+use std::{
+    borrow::Cow,
+    time::Instant,
+};
 
 use imgui::{
     ImColor32,
     StyleColor,
 };
 
+use crate::{
+    TraceRecorder,
+    TraceSpan,
+};
+
+const TRACE_CATEGORY: &str = "perf";
+
 pub struct PerfTracker {
     history_length: usize,
 
@@ -20,6 +30,8 @@ pub struct PerfTracker {
     marker_step_begin: Instant,
 
     initial_perf: bool,
+
+    trace: TraceRecorder,
 }
 
 impl PerfTracker {
@@ -29,7 +41,14 @@ impl PerfTracker {
         buffer.into_boxed_slice()
     }
 
+    /// Create a tracker without a trace history.
+    /// Trace events will only be recorded once the history or a capture has been enabled.
     pub fn new(history_length: usize) -> Self {
+        Self::with_trace(history_length, TraceRecorder::new(0))
+    }
+
+    /// Create a tracker which records its markers into an existing trace recorder
+    pub fn with_trace(history_length: usize, trace: TraceRecorder) -> Self {
         Self {
             history_length,
 
@@ -45,6 +64,8 @@ impl PerfTracker {
             marker_step_begin: Instant::now(),
 
             initial_perf: true,
+
+            trace,
         }
     }
 
@@ -66,8 +87,13 @@ impl PerfTracker {
             assert_eq!(self.marker_names.get(self.marker_index), Some(&label));
         }
 
-        let elapsed = self.marker_step_begin.elapsed();
-        self.marker_step_begin = Instant::now();
+        let now = Instant::now();
+        let elapsed = now - self.marker_step_begin;
+        if self.trace.is_enabled() {
+            self.trace
+                .record(label, TRACE_CATEGORY, self.marker_step_begin, now);
+        }
+        self.marker_step_begin = now;
 
         self.markers[self.marker_index][self.buffer_index] = elapsed.as_micros() as f32 / 1000.0;
         self.marker_index += 1;
@@ -79,6 +105,22 @@ impl PerfTracker {
             self.marker_begin.elapsed().as_micros() as f32 / 1000.0;
         self.initial_perf = false;
         self.finished_buffer_index = self.buffer_index;
+
+        if self.trace.is_enabled() {
+            self.trace
+                .record("frame", TRACE_CATEGORY, self.marker_begin, Instant::now());
+        }
+    }
+
+    /// Start a nested span within the current marker step
+    pub fn span(&self, name: impl Into<Cow<'static, str>>) -> TraceSpan {
+        self.trace.span(name).with_category(TRACE_CATEGORY)
+    }
+
+    /// Recorder containing the marker history as trace events.
+    /// Clones of the recorder can be used to record spans on other threads.
+    pub fn trace(&self) -> &TraceRecorder {
+        &self.trace
     }
 
     pub fn history_length(&self) -> usize {
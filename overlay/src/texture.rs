@@ -0,0 +1,307 @@
+use std::collections::BTreeMap;
+
+use ash::{
+    vk,
+    Device,
+};
+use imgui::{
+    TextureId,
+    Textures,
+};
+
+use crate::{
+    vulkan::Texture,
+    OverlayError,
+    Result,
+    VulkanContext,
+};
+
+/// Max number of textures which can be registered at the same time
+const MAX_USER_TEXTURES: u32 = 1024;
+
+struct UserTexture {
+    texture: Texture,
+    descriptor_set: vk::DescriptorSet,
+    width: u32,
+    height: u32,
+}
+
+/// RGBA textures uploaded by the application which can be drawn using imgui (e.g. `draw_list.add_image`).
+///
+/// All textures will be released when the swapchain gets recreated.
+/// Check `generation` to detect when textures have to be uploaded again.
+/// The registry will be destroyed by the overlay when the main loop exits.
+pub struct TextureRegistry {
+    device: Device,
+    transfer_queue: vk::Queue,
+    command_pool: vk::CommandPool,
+    mem_properties: vk::PhysicalDeviceMemoryProperties,
+
+    descriptor_set_layout: vk::DescriptorSetLayout,
+    descriptor_pool: vk::DescriptorPool,
+
+    textures: BTreeMap<usize, UserTexture>,
+    next_texture_id: usize,
+    generation: u64,
+
+    /// Changes which have not yet been applied to the renderer
+    pending_changes: Vec<(TextureId, Option<vk::DescriptorSet>)>,
+
+    /// Textures which might still be used by the last frame
+    pending_destroy: Vec<UserTexture>,
+}
+
+impl TextureRegistry {
+    pub(crate) fn new(vulkan_context: &VulkanContext) -> Result<Self> {
+        let device = vulkan_context.device.clone();
+        let mem_properties = unsafe {
+            vulkan_context
+                .instance
+                .get_physical_device_memory_properties(vulkan_context.physical_device)
+        };
+
+        /* must be compatible with the descriptor set layout of the imgui renderer */
+        let descriptor_set_layout = {
+            let bindings = [vk::DescriptorSetLayoutBinding::builder()
+                .binding(0)
+                .descriptor_count(1)
+                .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+                .stage_flags(vk::ShaderStageFlags::FRAGMENT)
+                .build()];
+
+            let layout_info = vk::DescriptorSetLayoutCreateInfo::builder().bindings(&bindings);
+            unsafe { device.create_descriptor_set_layout(&layout_info, None)? }
+        };
+
+        let descriptor_pool = {
+            let pool_sizes = [vk::DescriptorPoolSize {
+                ty: vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+                descriptor_count: MAX_USER_TEXTURES,
+            }];
+
+            let pool_info = vk::DescriptorPoolCreateInfo::builder()
+                .flags(vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET)
+                .pool_sizes(&pool_sizes)
+                .max_sets(MAX_USER_TEXTURES);
+            unsafe { device.create_descriptor_pool(&pool_info, None)? }
+        };
+
+        Ok(Self {
+            device,
+            transfer_queue: vulkan_context.graphics_queue,
+            command_pool: vulkan_context.command_pool,
+            mem_properties,
+
+            descriptor_set_layout,
+            descriptor_pool,
+
+            textures: Default::default(),
+            /* usize::MAX is used by the font atlas */
+            next_texture_id: 1,
+            generation: 0,
+
+            pending_changes: Vec::new(),
+            pending_destroy: Vec::new(),
+        })
+    }
+
+    fn create_texture(&self, width: u32, height: u32, data: &[u8]) -> Result<UserTexture> {
+        let expected_size = width as usize * height as usize * 4;
+        if width == 0 || height == 0 || data.len() != expected_size {
+            return Err(OverlayError::TextureInvalidDataSize {
+                expected: expected_size,
+                actual: data.len(),
+            });
+        }
+
+        if self.textures.len() + self.pending_destroy.len() >= MAX_USER_TEXTURES as usize {
+            return Err(OverlayError::TextureLimitReached);
+        }
+
+        let mut texture = Texture::from_rgba8(
+            &self.device,
+            self.transfer_queue,
+            self.command_pool,
+            self.mem_properties,
+            width,
+            height,
+            data,
+        )?;
+
+        let descriptor_set = {
+            let set_layouts = [self.descriptor_set_layout];
+            let allocate_info = vk::DescriptorSetAllocateInfo::builder()
+                .descriptor_pool(self.descriptor_pool)
+                .set_layouts(&set_layouts);
+
+            match unsafe { self.device.allocate_descriptor_sets(&allocate_info) } {
+                Ok(sets) => sets[0],
+                Err(error) => {
+                    texture.destroy(&self.device);
+                    return Err(error.into());
+                }
+            }
+        };
+
+        let image_info = [vk::DescriptorImageInfo {
+            sampler: texture.sampler,
+            image_view: texture.image_view,
+            image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+        }];
+        let writes = [vk::WriteDescriptorSet::builder()
+            .dst_set(descriptor_set)
+            .dst_binding(0)
+            .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .image_info(&image_info)
+            .build()];
+        unsafe { self.device.update_descriptor_sets(&writes, &[]) };
+
+        Ok(UserTexture {
+            texture,
+            descriptor_set,
+            width,
+            height,
+        })
+    }
+
+    /// Upload a new texture. `data` must contain `width * height` RGBA8 pixels.
+    pub fn upload_rgba8(&mut self, width: u32, height: u32, data: &[u8]) -> Result<TextureId> {
+        let texture = self.create_texture(width, height, data)?;
+
+        let texture_id = TextureId::new(self.next_texture_id);
+        self.next_texture_id += 1;
+
+        self.pending_changes
+            .push((texture_id, Some(texture.descriptor_set)));
+        self.textures.insert(texture_id.id(), texture);
+        Ok(texture_id)
+    }
+
+    /// Replace the contents of a texture. The size of the texture may change.
+    pub fn update_rgba8(
+        &mut self,
+        texture_id: TextureId,
+        width: u32,
+        height: u32,
+        data: &[u8],
+    ) -> Result<()> {
+        if !self.textures.contains_key(&texture_id.id()) {
+            return Err(OverlayError::TextureUnknown);
+        }
+
+        let texture = self.create_texture(width, height, data)?;
+        self.pending_changes
+            .push((texture_id, Some(texture.descriptor_set)));
+        if let Some(old_texture) = self.textures.insert(texture_id.id(), texture) {
+            self.pending_destroy.push(old_texture);
+        }
+
+        Ok(())
+    }
+
+    /// Free a texture. The texture id must not be used afterwards.
+    pub fn free(&mut self, texture_id: TextureId) -> bool {
+        let Some(texture) = self.textures.remove(&texture_id.id()) else {
+            return false;
+        };
+
+        self.pending_changes.push((texture_id, None));
+        self.pending_destroy.push(texture);
+        true
+    }
+
+    /// Size of the texture in pixels
+    pub fn texture_size(&self, texture_id: TextureId) -> Option<[u32; 2]> {
+        self.textures
+            .get(&texture_id.id())
+            .map(|texture| [texture.width, texture.height])
+    }
+
+    pub fn texture_count(&self) -> usize {
+        self.textures.len()
+    }
+
+    /// Incremented every time all textures have been released
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn destroy_texture(&self, mut texture: UserTexture) {
+        unsafe {
+            if let Err(error) = self
+                .device
+                .free_descriptor_sets(self.descriptor_pool, &[texture.descriptor_set])
+            {
+                log::warn!("Failed to free texture descriptor set: {}", error);
+            }
+        }
+
+        texture.texture.destroy(&self.device);
+    }
+
+    /// Apply all changes to the renderer and destroy textures which are no longer in use.
+    /// Must be called after the last frame has been finished and before recording the next frame.
+    pub(crate) fn apply_changes(&mut self, renderer_textures: &mut Textures<vk::DescriptorSet>) {
+        for (texture_id, descriptor_set) in self.pending_changes.drain(..) {
+            match descriptor_set {
+                Some(descriptor_set) => {
+                    renderer_textures.replace(texture_id, descriptor_set);
+                }
+                None => {
+                    renderer_textures.remove(texture_id);
+                }
+            }
+        }
+
+        for texture in std::mem::take(&mut self.pending_destroy) {
+            self.destroy_texture(texture);
+        }
+    }
+
+    /// Release all textures. The device must be idle.
+    pub(crate) fn release_all(&mut self, renderer_textures: &mut Textures<vk::DescriptorSet>) {
+        if self.textures.is_empty() && self.pending_destroy.is_empty() {
+            return;
+        }
+
+        log::debug!("Releasing {} user textures", self.textures.len());
+        for texture_id in self.textures.keys() {
+            renderer_textures.remove(TextureId::new(*texture_id));
+        }
+
+        self.pending_changes.clear();
+        let textures = std::mem::take(&mut self.textures)
+            .into_values()
+            .chain(std::mem::take(&mut self.pending_destroy))
+            .collect::<Vec<_>>();
+        for texture in textures {
+            self.destroy_texture(texture);
+        }
+
+        self.generation += 1;
+    }
+
+    /// Release all textures and the descriptor pool.
+    /// Waits for the device to be idle. The registry can not be used afterwards.
+    pub(crate) fn destroy(&mut self, renderer_textures: &mut Textures<vk::DescriptorSet>) {
+        if self.descriptor_pool == vk::DescriptorPool::null() {
+            return;
+        }
+
+        if let Err(error) = unsafe { self.device.device_wait_idle() } {
+            log::warn!("Failed to wait for the device to be idle: {}", error);
+        }
+
+        self.release_all(renderer_textures);
+        unsafe {
+            /* all remaining descriptor sets are freed implicitly */
+            self.device
+                .destroy_descriptor_pool(self.descriptor_pool, None);
+            self.device
+                .destroy_descriptor_set_layout(self.descriptor_set_layout, None);
+        }
+
+        self.descriptor_pool = vk::DescriptorPool::null();
+        self.descriptor_set_layout = vk::DescriptorSetLayout::null();
+    }
+}
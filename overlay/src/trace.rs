@@ -0,0 +1,593 @@
+use std::{
+    borrow::Cow,
+    collections::{
+        HashMap,
+        HashSet,
+        VecDeque,
+    },
+    fs::File,
+    io::{
+        BufWriter,
+        Write,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::{
+        atomic::{
+            AtomicBool,
+            AtomicU64,
+            Ordering,
+        },
+        Arc,
+        Mutex,
+        MutexGuard,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use serde::Serialize;
+
+use crate::{
+    OverlayError,
+    Result,
+};
+
+/// Default number of trace events kept in memory
+pub const TRACE_DEFAULT_CAPACITY: usize = 64 * 1024;
+
+/// Interval in which captured events will be written to disk
+const TRACE_CAPTURE_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+thread_local! {
+    /* std::thread::ThreadId can not be converted into a number on stable */
+    static THREAD_ID: u64 = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+}
+
+fn current_thread_id() -> u64 {
+    THREAD_ID.with(|id| *id)
+}
+
+/// A completed span on a specific thread
+#[derive(Debug, Clone)]
+pub struct TraceEvent {
+    pub name: Cow<'static, str>,
+    pub category: &'static str,
+    pub thread_id: u64,
+
+    /// Start time relative to the creation of the recorder
+    pub start: Duration,
+    pub duration: Duration,
+}
+
+/// Event in the Chrome Trace Event format
+#[derive(Serialize)]
+struct ChromeTraceEvent<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    cat: &'a str,
+    ph: &'static str,
+    pid: u32,
+    tid: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ts: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dur: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    args: Option<ChromeTraceMetadata<'a>>,
+}
+
+#[derive(Serialize)]
+struct ChromeTraceMetadata<'a> {
+    name: &'a str,
+}
+
+impl<'a> ChromeTraceEvent<'a> {
+    fn complete(event: &'a TraceEvent) -> Self {
+        Self {
+            name: &event.name,
+            cat: event.category,
+            ph: "X",
+            pid: std::process::id(),
+            tid: event.thread_id,
+
+            ts: Some(event.start.as_secs_f64() * 1_000_000.0),
+            dur: Some(event.duration.as_secs_f64() * 1_000_000.0),
+
+            args: None,
+        }
+    }
+
+    fn metadata(name: &'static str, thread_id: u64, value: &'a str) -> Self {
+        Self {
+            name,
+            cat: "",
+            ph: "M",
+            pid: std::process::id(),
+            tid: thread_id,
+
+            ts: None,
+            dur: None,
+
+            args: Some(ChromeTraceMetadata { name: value }),
+        }
+    }
+}
+
+/// Continuous capture into a file using the JSON array format.
+/// The closing bracket is optional for trace viewers, hence the file stays loadable
+/// even if the process terminates while capturing.
+struct TraceCapture {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    known_threads: HashSet<u64>,
+    event_written: bool,
+    last_flush: Instant,
+}
+
+impl TraceCapture {
+    fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path).map_err(OverlayError::TraceIoError)?;
+        let mut capture = Self {
+            path: path.to_path_buf(),
+            writer: BufWriter::new(file),
+            known_threads: Default::default(),
+            event_written: false,
+            last_flush: Instant::now(),
+        };
+
+        capture
+            .writer
+            .write_all(b"[")
+            .map_err(OverlayError::TraceIoError)?;
+        capture.write_event(&ChromeTraceEvent::metadata("process_name", 0, "overlay"))?;
+        Ok(capture)
+    }
+
+    fn write_event(&mut self, event: &ChromeTraceEvent) -> Result<()> {
+        let separator: &[u8] = if self.event_written { b",\n" } else { b"\n" };
+        self.writer
+            .write_all(separator)
+            .map_err(OverlayError::TraceIoError)?;
+        self.event_written = true;
+
+        serde_json::to_writer(&mut self.writer, event)
+            .map_err(|error| OverlayError::TraceIoError(error.into()))
+    }
+
+    fn write_trace_event(&mut self, event: &TraceEvent, thread_name: &str) -> Result<()> {
+        if self.known_threads.insert(event.thread_id) {
+            self.write_event(&ChromeTraceEvent::metadata(
+                "thread_name",
+                event.thread_id,
+                thread_name,
+            ))?;
+        }
+
+        self.write_event(&ChromeTraceEvent::complete(event))
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.last_flush = Instant::now();
+        self.writer.flush().map_err(OverlayError::TraceIoError)
+    }
+
+    fn flush_if_due(&mut self) -> Result<()> {
+        if self.last_flush.elapsed() < TRACE_CAPTURE_FLUSH_INTERVAL {
+            return Ok(());
+        }
+
+        self.flush()
+    }
+
+    fn finish(mut self) -> Result<()> {
+        self.writer
+            .write_all(b"\n]\n")
+            .map_err(OverlayError::TraceIoError)?;
+        self.writer.flush().map_err(OverlayError::TraceIoError)
+    }
+}
+
+struct TraceState {
+    capacity: usize,
+    events: VecDeque<TraceEvent>,
+    thread_names: HashMap<u64, String>,
+
+    capture: Option<TraceCapture>,
+}
+
+impl TraceState {
+    fn thread_name(&mut self, thread_id: u64) -> &str {
+        self.thread_names.entry(thread_id).or_insert_with(|| {
+            std::thread::current()
+                .name()
+                .map(ToString::to_string)
+                .unwrap_or_else(|| format!("thread {}", thread_id))
+        })
+    }
+
+    fn push_event(&mut self, event: TraceEvent) {
+        if self.capture.is_some() {
+            let thread_name = self.thread_name(event.thread_id).to_string();
+            let capture = self.capture.as_mut().unwrap();
+            let result = capture
+                .write_trace_event(&event, &thread_name)
+                .and_then(|_| capture.flush_if_due());
+            if let Err(error) = result {
+                log::warn!(
+                    "Failed to write trace event to {}: {}. Stopping capture.",
+                    capture.path.display(),
+                    error
+                );
+                self.capture = None;
+            }
+        } else {
+            /* register the thread name while still on the recording thread */
+            self.thread_name(event.thread_id);
+        }
+
+        if self.capacity == 0 {
+            return;
+        }
+
+        while self.events.len() >= self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+}
+
+/// Records timing spans from any thread.
+/// Cloning the recorder yields a handle to the same event history.
+///
+/// Events are only recorded if the history has a capacity or a capture is running.
+#[derive(Clone)]
+pub struct TraceRecorder {
+    epoch: Instant,
+    state: Arc<Mutex<TraceState>>,
+
+    /// Allows checking if events should be recorded without locking the state
+    enabled: Arc<AtomicBool>,
+}
+
+impl TraceRecorder {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            epoch: Instant::now(),
+            state: Arc::new(Mutex::new(TraceState {
+                capacity,
+                events: VecDeque::with_capacity(capacity.min(TRACE_DEFAULT_CAPACITY)),
+                thread_names: Default::default(),
+
+                capture: None,
+            })),
+            enabled: Arc::new(AtomicBool::new(capacity > 0)),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, TraceState> {
+        /* the state stays consistent even if a thread panicked while recording */
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn update_enabled(&self, state: &TraceState) {
+        self.enabled.store(
+            state.capacity > 0 || state.capture.is_some(),
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Returns true if recorded events will be kept in memory or captured into a file
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.state().capacity
+    }
+
+    /// Set the number of events kept in memory for exporting.
+    /// A capacity of zero disables the history.
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut state = self.state();
+        state.capacity = capacity;
+        while state.events.len() > capacity {
+            state.events.pop_front();
+        }
+        self.update_enabled(&state);
+    }
+
+    /// Record a span on the current thread
+    pub fn record(
+        &self,
+        name: impl Into<Cow<'static, str>>,
+        category: &'static str,
+        begin: Instant,
+        end: Instant,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let event = TraceEvent {
+            name: name.into(),
+            category,
+            thread_id: current_thread_id(),
+
+            start: begin.saturating_duration_since(self.epoch),
+            duration: end.saturating_duration_since(begin),
+        };
+
+        let mut state = self.state();
+        state.push_event(event);
+        /* the capture might have been stopped due to an error */
+        self.update_enabled(&state);
+    }
+
+    /// Start a span on the current thread which will be recorded once the returned guard is dropped.
+    /// Spans may be nested.
+    pub fn span(&self, name: impl Into<Cow<'static, str>>) -> TraceSpan {
+        TraceSpan {
+            recorder: self.clone(),
+            name: Some(name.into()),
+            category: "",
+            begin: Instant::now(),
+        }
+    }
+
+    /// Override the track name of the current thread
+    pub fn set_thread_name(&self, name: impl Into<String>) {
+        self.state()
+            .thread_names
+            .insert(current_thread_id(), name.into());
+    }
+
+    pub fn events(&self) -> Vec<TraceEvent> {
+        self.state().events.iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.state().events.clear();
+    }
+
+    /// Export the recorded history in the Chrome Trace Event format (loadable in Perfetto or chrome://tracing)
+    pub fn write_chrome_trace(&self, mut output: impl Write) -> Result<()> {
+        let state = self.state();
+
+        let mut events = Vec::with_capacity(state.events.len() + state.thread_names.len() + 1);
+        events.push(ChromeTraceEvent::metadata("process_name", 0, "overlay"));
+
+        let mut thread_ids = state
+            .events
+            .iter()
+            .map(|event| event.thread_id)
+            .collect::<Vec<_>>();
+        thread_ids.sort_unstable();
+        thread_ids.dedup();
+        for thread_id in thread_ids {
+            if let Some(thread_name) = state.thread_names.get(&thread_id) {
+                events.push(ChromeTraceEvent::metadata(
+                    "thread_name",
+                    thread_id,
+                    thread_name,
+                ));
+            }
+        }
+        events.extend(state.events.iter().map(ChromeTraceEvent::complete));
+
+        serde_json::to_writer(
+            &mut output,
+            &serde_json::json!({
+                "traceEvents": events,
+                "displayTimeUnit": "ms",
+            }),
+        )
+        .map_err(|error| OverlayError::TraceIoError(error.into()))?;
+        output.flush().map_err(OverlayError::TraceIoError)
+    }
+
+    pub fn save_chrome_trace(&self, path: &Path) -> Result<()> {
+        let file = File::create(path).map_err(OverlayError::TraceIoError)?;
+        self.write_chrome_trace(BufWriter::new(file))
+    }
+
+    /// Continuously write all new events into the target file
+    pub fn start_capture(&self, path: &Path) -> Result<()> {
+        let capture = TraceCapture::create(path)?;
+        let mut state = self.state();
+        let previous = state.capture.replace(capture);
+        self.update_enabled(&state);
+        drop(state);
+
+        if let Some(previous) = previous {
+            previous.finish()?;
+        }
+
+        Ok(())
+    }
+
+    /// Stop the current capture and return the path of the written file
+    pub fn stop_capture(&self) -> Result<Option<PathBuf>> {
+        let mut state = self.state();
+        let capture = state.capture.take();
+        self.update_enabled(&state);
+        drop(state);
+
+        let Some(capture) = capture else {
+            return Ok(None);
+        };
+
+        let path = capture.path.clone();
+        capture.finish()?;
+        Ok(Some(path))
+    }
+
+    pub fn capture_path(&self) -> Option<PathBuf> {
+        self.state()
+            .capture
+            .as_ref()
+            .map(|capture| capture.path.clone())
+    }
+
+    pub fn is_capturing(&self) -> bool {
+        self.state().capture.is_some()
+    }
+
+    /// Flush buffered capture events to disk.
+    /// Captured events are flushed periodically while recording.
+    pub fn flush_capture(&self) {
+        let mut state = self.state();
+        let Some(capture) = state.capture.as_mut() else {
+            return;
+        };
+
+        if let Err(error) = capture.flush() {
+            log::warn!(
+                "Failed to flush trace capture {}: {}. Stopping capture.",
+                capture.path.display(),
+                error
+            );
+            state.capture = None;
+            self.update_enabled(&state);
+        }
+    }
+}
+
+/// Guard for an active span, see [TraceRecorder::span]
+pub struct TraceSpan {
+    recorder: TraceRecorder,
+    name: Option<Cow<'static, str>>,
+    category: &'static str,
+    begin: Instant,
+}
+
+impl TraceSpan {
+    pub fn with_category(mut self, category: &'static str) -> Self {
+        self.category = category;
+        self
+    }
+}
+
+impl Drop for TraceSpan {
+    fn drop(&mut self) {
+        if let Some(name) = self.name.take() {
+            self.recorder
+                .record(name, self.category, self.begin, Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use serde_json::Value;
+
+    use super::TraceRecorder;
+
+    fn chrome_trace(recorder: &TraceRecorder) -> Value {
+        let mut output = Vec::new();
+        recorder.write_chrome_trace(&mut output).unwrap();
+        serde_json::from_slice(&output).unwrap()
+    }
+
+    #[test]
+    fn test_chrome_trace_export() {
+        let recorder = TraceRecorder::new(16);
+        recorder.set_thread_name("main");
+
+        let begin = recorder.epoch + Duration::from_millis(2);
+        recorder.record("update", "perf", begin, begin + Duration::from_micros(1500));
+        drop(recorder.span("render").with_category("perf"));
+
+        let trace = chrome_trace(&recorder);
+        assert_eq!(trace["displayTimeUnit"], "ms");
+
+        let events = trace["traceEvents"].as_array().unwrap();
+        assert_eq!(events.len(), 4);
+
+        assert_eq!(events[0]["ph"], "M");
+        assert_eq!(events[0]["name"], "process_name");
+        assert_eq!(events[0]["args"]["name"], "overlay");
+
+        assert_eq!(events[1]["ph"], "M");
+        assert_eq!(events[1]["name"], "thread_name");
+        assert_eq!(events[1]["args"]["name"], "main");
+        assert!(events[1].get("ts").is_none());
+
+        let update = &events[2];
+        assert_eq!(update["ph"], "X");
+        assert_eq!(update["name"], "update");
+        assert_eq!(update["cat"], "perf");
+        assert_eq!(update["tid"], events[1]["tid"]);
+        assert_eq!(update["ts"].as_f64(), Some(2000.0));
+        assert_eq!(update["dur"].as_f64(), Some(1500.0));
+        assert!(update.get("args").is_none());
+
+        assert_eq!(events[3]["name"], "render");
+    }
+
+    #[test]
+    fn test_capacity() {
+        let recorder = TraceRecorder::new(0);
+        assert!(!recorder.is_enabled());
+
+        recorder.record("ignored", "", recorder.epoch, recorder.epoch);
+        assert!(recorder.events().is_empty());
+
+        recorder.set_capacity(2);
+        assert!(recorder.is_enabled());
+        for name in ["first", "second", "third"] {
+            recorder.record(name, "", recorder.epoch, recorder.epoch);
+        }
+
+        let names = recorder
+            .events()
+            .into_iter()
+            .map(|event| event.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["second", "third"]);
+    }
+
+    #[test]
+    fn test_capture() {
+        let path =
+            std::env::temp_dir().join(format!("overlay-trace-capture-{}.json", std::process::id()));
+
+        let recorder = TraceRecorder::new(0);
+        recorder.start_capture(&path).unwrap();
+        assert!(recorder.is_enabled());
+
+        recorder.set_thread_name("worker");
+        recorder.record("frame", "perf", recorder.epoch, recorder.epoch);
+        assert_eq!(
+            recorder.stop_capture().unwrap().as_deref(),
+            Some(path.as_path())
+        );
+        assert!(!recorder.is_enabled());
+
+        let capture = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let events: Value = serde_json::from_slice(&capture).unwrap();
+        let names = events
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|event| event["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["process_name", "thread_name", "frame"]);
+
+        /* capture events are not kept in memory without a capacity */
+        assert!(recorder.events().is_empty());
+    }
+}
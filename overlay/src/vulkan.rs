@@ -85,6 +85,8 @@ mod buffer {
     }
 }
 
+pub use texture::Texture;
+
 mod texture {
 
     use ash::{
@@ -1,12 +1,24 @@
+use std::{
+    path::PathBuf,
+    time::Duration,
+};
+
 use anyhow::Context;
 use clap::Parser;
 use cs2::{
     offsets_runtime,
+    BuildInfo,
     CS2Handle,
     CS2HandleState,
+    CS2Offsets,
+    OffsetCache,
+    PAGE_CACHE_DEFAULT_CAPACITY,
 };
 use radar_client::{
     CS2RadarGenerator,
+    PublishSessionOptions,
+    RadarGenerator,
+    ReplayRadarGenerator,
     WebRadarPublisher,
 };
 use url::Url;
@@ -20,6 +32,33 @@ struct Args {
     /// Use ws://127.0.0.1:7229/publish for local development.
     #[arg(short, long, default_value = "wss://radar.valth.run/publish")]
     publish_url: String,
+
+    /// Password viewers have to enter to see the radar
+    #[arg(long)]
+    password: Option<String>,
+
+    /// Close the session after the given amount of minutes
+    #[arg(long)]
+    max_lifetime: Option<u64>,
+
+    /// Record the game states into the given file
+    #[arg(long)]
+    record: Option<PathBuf>,
+
+    /// Replay a recording instead of reading the game states
+    #[arg(long, conflicts_with = "record")]
+    replay: Option<PathBuf>,
+
+    /// Ignore the offset cache and scan for all offsets again
+    #[arg(long)]
+    refresh_offsets: bool,
+}
+
+fn get_offset_cache_path() -> anyhow::Result<PathBuf> {
+    let exe_file = std::env::current_exe().context("missing current exe path")?;
+    let base_dir = exe_file.parent().context("could not get exe directory")?;
+
+    Ok(base_dir.join("offsets_cache.json"))
 }
 
 #[tokio::main]
@@ -33,16 +72,56 @@ async fn main() -> anyhow::Result<()> {
 
     let url = Url::parse(&args.publish_url).context("invalid target server address")?;
 
-    let radar_generator = {
+    let radar_generator: Box<dyn RadarGenerator> = if let Some(replay) = args.replay {
+        log::info!("Replaying {}", replay.to_string_lossy());
+        Box::new(ReplayRadarGenerator::new(replay)?)
+    } else {
         let cs2 = CS2Handle::create(true)?;
-        offsets_runtime::setup_provider(&cs2)?;
+        cs2.page_cache().set_capacity(PAGE_CACHE_DEFAULT_CAPACITY);
 
         let mut states = StateRegistry::new(1024 * 8);
-        states.set(CS2HandleState::new(cs2), ())?;
+        states.set(CS2HandleState::new(cs2.clone()), ())?;
+
+        let cs2_revision = states
+            .resolve::<BuildInfo>(())
+            .context("failed to load CS2 build info")?
+            .revision
+            .clone();
+
+        let offset_cache_path = get_offset_cache_path()?;
+        let mut offset_cache = OffsetCache::load_or_create(
+            &cs2,
+            &cs2_revision,
+            &offset_cache_path,
+            args.refresh_offsets,
+        )?;
+        offsets_runtime::setup_provider_cached(&cs2, &mut offset_cache)?;
+        states
+            .resolve::<CS2Offsets>(())
+            .context("failed to load CS2 offsets")?;
 
-        Box::new(CS2RadarGenerator::new(states)?)
+        /* the signatures have been resolved while loading the offsets */
+        if let Err(error) = offset_cache.store(&cs2, &offset_cache_path) {
+            log::warn!("Failed to store the offset cache: {:#}", error);
+        }
+
+        states.add_invalidation_hook(move || cs2.page_cache().invalidate());
+
+        let mut generator = CS2RadarGenerator::new(states)?;
+        if let Some(record) = &args.record {
+            log::info!("Recording game states to {}", record.to_string_lossy());
+            generator.record_to(record)?;
+        }
+
+        Box::new(generator)
+    };
+    let options = PublishSessionOptions {
+        password: args.password.clone(),
+        max_lifetime: args
+            .max_lifetime
+            .map(|minutes| Duration::from_secs(minutes * 60)),
     };
-    let radar_client = WebRadarPublisher::connect(radar_generator, &url).await?;
+    let radar_client = WebRadarPublisher::connect(radar_generator, &url, options).await?;
 
     let mut radar_url = url.clone();
     radar_url.set_path(&format!("/session/{}", radar_client.session_id));
@@ -1,23 +1,24 @@
-use std::ffi::CStr;
+use std::{
+    fs::File,
+    io::{
+        BufReader,
+        BufWriter,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+};
 
 use anyhow::Context;
 use cs2::{
-    CEntityIdentityEx,
-    ClassNameCache,
     CurrentMapState,
-    EntitySystem,
-    Globals,
+    PlayerPawnEntities,
     PlayerPawnState,
+    StateRecorder,
+    StateReplay,
+    C4,
 };
-use cs2_schema_generated::cs2::{
-    client::{
-        CEntityIdentity,
-        C_PlantedC4,
-        C_C4,
-    },
-    globals::CSWeaponState_t,
-};
-use obfstr::obfstr;
 use radar_shared::{
     BombDefuser,
     C4State,
@@ -32,132 +33,112 @@ pub trait RadarGenerator: Send {
     fn generate_state(&mut self, settings: &RadarSettings) -> anyhow::Result<RadarState>;
 }
 
-trait BombData {
-    fn read_bomb_data(&self, generator: &CS2RadarGenerator) -> anyhow::Result<RadarBombInfo>;
-}
+fn generate_player_info(
+    states: &StateRegistry,
+    pawn_entity_index: u32,
+) -> anyhow::Result<Option<RadarPlayerInfo>> {
+    let player_info = states.resolve::<PlayerPawnState>(pawn_entity_index)?;
 
-impl BombData for C_C4 {
-    fn read_bomb_data(&self, _generator: &CS2RadarGenerator) -> anyhow::Result<RadarBombInfo> {
-        let position = self.m_pGameSceneNode()?.read_schema()?.m_vecAbsOrigin()?;
+    match &*player_info {
+        PlayerPawnState::Alive(info) => Ok(Some(RadarPlayerInfo {
+            controller_entity_id: info.controller_entity_id,
 
-        if self.m_iState()? as u32 == CSWeaponState_t::WEAPON_NOT_CARRIED as u32 {
-            return Ok(RadarBombInfo {
-                position,
-                state: C4State::Dropped,
-                bomb_site: None,
-            });
-        }
+            player_name: info.player_name.clone(),
+            player_flashtime: info.player_flashtime,
+            player_has_defuser: info.player_has_defuser,
+            player_health: info.player_health,
 
-        Ok(RadarBombInfo {
-            position,
-            state: C4State::Carried,
-            bomb_site: None,
-        })
+            position: [info.position.x, info.position.y, info.position.z],
+            rotation: info.rotation,
+
+            team_id: info.team_id,
+            weapon: info.weapon.id(),
+        })),
+        _ => Ok(None),
     }
 }
 
-impl BombData for C_PlantedC4 {
-    fn read_bomb_data(&self, generator: &CS2RadarGenerator) -> anyhow::Result<RadarBombInfo> {
-        let globals = generator.states.resolve::<Globals>(())?;
-        let entities = generator.states.resolve::<EntitySystem>(())?;
-
-        let position = self.m_pGameSceneNode()?.read_schema()?.m_vecAbsOrigin()?;
-        let bomb_site = Some(self.m_nBombSite()? as u8);
-
-        if self.m_bBombDefused()? {
-            return Ok(RadarBombInfo {
-                position,
-                state: C4State::Defused,
-                bomb_site,
-            });
-        }
+fn generate_bomb_info(bomb: &C4) -> Option<RadarBombInfo> {
+    let position = bomb.bomb_pos?;
+    let state = match bomb.state.as_ref()? {
+        cs2::C4State::Active { time_detonation } => C4State::Active {
+            time_detonation: *time_detonation,
+            defuse: bomb.defuser.as_ref().map(|defuser| BombDefuser {
+                time_remaining: defuser.time_remaining,
+                player_name: defuser.player_name.clone(),
+            }),
+        },
+        cs2::C4State::Detonated => C4State::Detonated,
+        cs2::C4State::Defused => C4State::Defused,
+        cs2::C4State::Dropped => C4State::Dropped,
+        cs2::C4State::Carried => C4State::Carried,
+    };
+
+    Some(RadarBombInfo {
+        position: [position.x, position.y, position.z],
+        state,
+        bomb_site: bomb.bomb_site,
+    })
+}
 
-        let time_blow = self.m_flC4Blow()?.m_Value()?;
-        if time_blow <= globals.time_2()? {
-            return Ok(RadarBombInfo {
-                position,
-                bomb_site,
-                state: C4State::Detonated,
-            });
+/// Generate the radar state from the states of the current frame.
+/// Only states which are part of a recording will be used,
+/// therefore the states may originate from the game or from a replay.
+fn generate_radar_state(states: &StateRegistry) -> anyhow::Result<RadarState> {
+    let current_map = states.resolve::<CurrentMapState>(())?;
+    let mut radar_state = RadarState {
+        players: Vec::with_capacity(16),
+        world_name: current_map
+            .current_map
+            .as_ref()
+            .map(|v| v.as_str())
+            .unwrap_or("<empty>")
+            .to_string(),
+        bomb: None,
+    };
+
+    let player_pawns = states.resolve::<PlayerPawnEntities>(())?;
+    for pawn_entity_index in player_pawns.entity_indices.iter() {
+        match generate_player_info(states, *pawn_entity_index) {
+            Ok(Some(info)) => radar_state.players.push(info),
+            Ok(None) => {}
+            Err(error) => {
+                log::warn!(
+                    "Failed to generate player pawn ESP info for {}: {:#}",
+                    pawn_entity_index,
+                    error
+                );
+            }
         }
+    }
 
-        let is_defusing = self.m_bBeingDefused()?;
-        let defusing = if is_defusing {
-            let time_defuse = self.m_flDefuseCountDown()?.m_Value()?;
-
-            let handle_defuser = self.m_hBombDefuser()?;
-            let defuser = entities
-                .get_by_handle(&handle_defuser)?
-                .with_context(|| obfstr!("missing bomb defuser player pawn").to_string())?
-                .entity()?
-                .reference_schema()?;
-
-            let defuser_controller = defuser.m_hController()?;
-            let defuser_controller = entities
-                .get_by_handle(&defuser_controller)?
-                .with_context(|| obfstr!("missing bomb defuser controller").to_string())?
-                .entity()?
-                .reference_schema()?;
-
-            let defuser_name = CStr::from_bytes_until_nul(&defuser_controller.m_iszPlayerName()?)
-                .ok()
-                .map(CStr::to_string_lossy)
-                .unwrap_or("Name Error".into())
-                .to_string();
-
-            Some(BombDefuser {
-                time_remaining: time_defuse - globals.time_2()?,
-                player_name: defuser_name,
-            })
-        } else {
-            None
-        };
-
-        Ok(RadarBombInfo {
-            position,
-            state: C4State::Active {
-                time_detonation: time_blow - globals.time_2()?,
-                defuse: defusing,
-            },
-            bomb_site,
-        })
+    match states.resolve::<C4>(()) {
+        Ok(bomb) => radar_state.bomb = generate_bomb_info(&bomb),
+        Err(error) => log::warn!("Failed to generate bomb info: {:#}", error),
     }
+
+    Ok(radar_state)
 }
 
 pub struct CS2RadarGenerator {
     states: StateRegistry,
+    recorder: Option<StateRecorder<BufWriter<File>>>,
 }
 
 impl CS2RadarGenerator {
     pub fn new(states: StateRegistry) -> anyhow::Result<Self> {
-        Ok(Self { states })
+        Ok(Self {
+            states,
+            recorder: None,
+        })
     }
 
-    fn generate_player_info(
-        &self,
-        player_pawn: &CEntityIdentity,
-    ) -> anyhow::Result<Option<RadarPlayerInfo>> {
-        let player_info = self
-            .states
-            .resolve::<PlayerPawnState>(player_pawn.handle::<()>()?.get_entity_index())?;
-
-        match &*player_info {
-            PlayerPawnState::Alive(info) => Ok(Some(RadarPlayerInfo {
-                controller_entity_id: info.controller_entity_id,
-
-                player_name: info.player_name.clone(),
-                player_flashtime: info.player_flashtime,
-                player_has_defuser: info.player_has_defuser,
-                player_health: info.player_health,
-
-                position: [info.position.x, info.position.y, info.position.z],
-                rotation: info.rotation,
-
-                team_id: info.team_id,
-                weapon: info.weapon.id(),
-            })),
-            _ => Ok(None),
-        }
+    /// Record the states of every generated radar state into the given file
+    pub fn record_to(&mut self, path: &Path) -> anyhow::Result<()> {
+        let output = File::create(path)
+            .with_context(|| format!("create recording {}", path.to_string_lossy()))?;
+        self.recorder = Some(StateRecorder::new(BufWriter::new(output))?);
+        Ok(())
     }
 }
 
@@ -165,63 +146,52 @@ impl RadarGenerator for CS2RadarGenerator {
     fn generate_state(&mut self, _settings: &RadarSettings) -> anyhow::Result<RadarState> {
         self.states.invalidate_states();
 
-        let current_map = self.states.resolve::<CurrentMapState>(())?;
-        let mut radar_state = RadarState {
-            players: Vec::with_capacity(16),
-            world_name: current_map
-                .current_map
-                .as_ref()
-                .map(|v| v.as_str())
-                .unwrap_or("<empty>")
-                .to_string(),
-            bomb: None,
-        };
-
-        let entities = self.states.resolve::<EntitySystem>(())?;
-        let class_name_cache = self.states.resolve::<ClassNameCache>(())?;
-
-        for entity_identity in entities.all_identities() {
-            let entity_class =
-                match class_name_cache.lookup(&entity_identity.entity_class_info()?)? {
-                    Some(entity_class) => entity_class,
-                    None => {
-                        log::warn!(
-                            "Failed to get entity class info {:X}",
-                            entity_identity.memory.address,
-                        );
-                        continue;
-                    }
-                };
-
-            match entity_class.as_str() {
-                "C_CSPlayerPawn" => match self.generate_player_info(entity_identity) {
-                    Ok(Some(info)) => radar_state.players.push(info),
-                    Ok(None) => {}
-                    Err(error) => {
-                        log::warn!(
-                            "Failed to generate player pawn ESP info for {}: {:#}",
-                            entity_identity.handle::<()>()?.get_entity_index(),
-                            error
-                        );
-                    }
-                },
-                "C_C4" | "C_PlantedC4" => {
-                    let bomb_ptr: Box<dyn BombData> = match entity_class.as_str() {
-                        "C_C4" => Box::new(entity_identity.entity_ptr::<C_C4>()?.read_schema()?),
-                        "C_PlantedC4" => {
-                            Box::new(entity_identity.entity_ptr::<C_PlantedC4>()?.read_schema()?)
-                        }
-                        _ => unreachable!(),
-                    };
-
-                    if let Ok(bomb_data) = bomb_ptr.read_bomb_data(self) {
-                        radar_state.bomb = Some(bomb_data);
-                    }
-                }
-                _ => {}
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(error) = recorder.record_frame(&self.states) {
+                log::warn!("Failed to record frame, stopping recording: {:#}", error);
+                self.recorder = None;
+            }
+        }
+
+        generate_radar_state(&self.states)
+    }
+}
+
+/// Generates the radar states from a recording.
+/// The recording will be restarted once it has ended.
+pub struct ReplayRadarGenerator {
+    path: PathBuf,
+    states: StateRegistry,
+    replay: StateReplay<BufReader<File>>,
+}
+
+impl ReplayRadarGenerator {
+    pub fn new(path: PathBuf) -> anyhow::Result<Self> {
+        let replay = Self::open_replay(&path)?;
+        Ok(Self {
+            path,
+            states: StateRegistry::new(1024),
+            replay,
+        })
+    }
+
+    fn open_replay(path: &Path) -> anyhow::Result<StateReplay<BufReader<File>>> {
+        let input = File::open(path)
+            .with_context(|| format!("open recording {}", path.to_string_lossy()))?;
+        StateReplay::new(BufReader::new(input))
+    }
+}
+
+impl RadarGenerator for ReplayRadarGenerator {
+    fn generate_state(&mut self, _settings: &RadarSettings) -> anyhow::Result<RadarState> {
+        if self.replay.replay_frame(&mut self.states)?.is_none() {
+            log::info!("Recording ended. Restarting replay.");
+            self.replay = Self::open_replay(&self.path)?;
+            if self.replay.replay_frame(&mut self.states)?.is_none() {
+                anyhow::bail!("the recording does not contain any frames");
             }
         }
 
-        Ok(radar_state)
+        generate_radar_state(&self.states)
     }
 }
@@ -3,7 +3,10 @@ use std::{
     future::Future,
     pin::Pin,
     task::Poll,
-    time::Duration,
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
 use anyhow::{
@@ -38,8 +41,40 @@ use crate::{
     RadarGenerator,
 };
 
+#[derive(Clone, Debug, Default)]
+pub struct PublishSessionOptions {
+    /// Password viewers have to enter
+    pub password: Option<String>,
+
+    /// Lifetime after which the server closes the session
+    pub max_lifetime: Option<Duration>,
+}
+
+/// Handle to manage the published session while the publisher is running
+#[derive(Clone)]
+pub struct PublishSessionController {
+    transport_tx: Sender<C2SMessage>,
+}
+
+impl PublishSessionController {
+    /// Change the session password. Already connected viewers stay connected.
+    pub fn set_password(&self, password: Option<String>) {
+        let _ = self
+            .transport_tx
+            .try_send(C2SMessage::SessionSetPassword { password });
+    }
+
+    /// Disconnect all current viewers
+    pub fn kick_subscribers(&self) {
+        let _ = self
+            .transport_tx
+            .try_send(C2SMessage::SessionKickSubscribers);
+    }
+}
+
 pub struct WebRadarPublisher {
     pub session_id: String,
+    pub session_expires_at: Option<Instant>,
 
     generator: RefCell<Box<dyn RadarGenerator>>,
     generate_interval: Pin<Box<Interval>>,
@@ -51,17 +86,28 @@ pub struct WebRadarPublisher {
 }
 
 impl WebRadarPublisher {
-    pub async fn connect(generator: Box<dyn RadarGenerator>, url: &Url) -> anyhow::Result<Self> {
+    pub async fn connect(
+        generator: Box<dyn RadarGenerator>,
+        url: &Url,
+        options: PublishSessionOptions,
+    ) -> anyhow::Result<Self> {
         let (tx, rx) = create_ws_connection(url).await?;
-        Self::create_from_transport(generator, tx, rx).await
+        Self::create_from_transport(generator, tx, rx, options).await
     }
 
     pub async fn create_from_transport(
         generator: Box<dyn RadarGenerator>,
         tx: Sender<C2SMessage>,
         mut rx: Receiver<ClientEvent<S2CMessage>>,
+        options: PublishSessionOptions,
     ) -> anyhow::Result<Self> {
-        let _ = tx.send(C2SMessage::InitializePublish { version: 1 }).await;
+        let _ = tx
+            .send(C2SMessage::InitializePublish {
+                version: 1,
+                password: options.password,
+                max_lifetime: options.max_lifetime.map(|lifetime| lifetime.as_secs()),
+            })
+            .await;
         let event = tokio::select! {
             message = rx.recv() => message.context("unexpected client disconnect")?,
             _ = time::sleep(Duration::from_secs(5)) => {
@@ -69,12 +115,16 @@ impl WebRadarPublisher {
             }
         };
 
-        let session_id = match event {
+        let (session_id, expires_in) = match event {
             ClientEvent::RecvMessage(message) => match message {
                 S2CMessage::ResponseError { error } => {
                     anyhow::bail!("server error: {}", error)
                 }
-                S2CMessage::ResponseInitializePublish { session_id, .. } => session_id,
+                S2CMessage::ResponseInitializePublish {
+                    session_id,
+                    expires_in,
+                    ..
+                } => (session_id, expires_in),
                 _ => anyhow::bail!("invalid response"),
             },
             ClientEvent::RecvError(err) => anyhow::bail!("recv err: {:#}", err),
@@ -84,6 +134,9 @@ impl WebRadarPublisher {
         log::debug!("Connected with session id {}", session_id);
         Ok(Self {
             session_id,
+            session_expires_at: expires_in
+                .map(|expires_in| Instant::now() + Duration::from_secs(expires_in)),
+
             generator: RefCell::new(generator),
 
             transport_rx: rx,
@@ -98,6 +151,12 @@ impl WebRadarPublisher {
         })
     }
 
+    pub fn session_controller(&self) -> PublishSessionController {
+        PublishSessionController {
+            transport_tx: self.transport_tx.clone(),
+        }
+    }
+
     fn send_message(&self, message: C2SMessage) {
         let _ = self.transport_tx.try_send(message);
     }
@@ -131,6 +190,12 @@ impl Future for WebRadarPublisher {
                             log::debug!("Send error: {}", err);
                             return Poll::Ready(Some(err));
                         }
+                        ClientEvent::RecvMessage(S2CMessage::NotifySessionExpired) => {
+                            return Poll::Ready(Some(anyhow!("session expired")));
+                        }
+                        ClientEvent::RecvMessage(S2CMessage::ResponseError { error }) => {
+                            log::warn!("Web radar server error: {}", error);
+                        }
                         ClientEvent::RecvMessage(_message) => { /* TODO? */ }
                     }
                 }
@@ -19,7 +19,8 @@ struct Args {
     #[arg(short, long, default_value = "0.0.0.0:7229")]
     address: String,
 
-    /// Static HTML file directory (optional)
+    /// Static HTML file directory (optional).
+    /// Overrides the bundled web files if the server has been built with the static-bundle feature.
     #[arg(long)]
     static_dir: Option<PathBuf>,
 }
@@ -46,6 +47,8 @@ async fn main() -> anyhow::Result<()> {
                     .context("invalid bind address")?,
                 if let Some(path) = args.static_dir.as_ref() {
                     HttpServeDirectory::Disk { path: path.clone() }
+                } else if cfg!(feature = "static-bundle") {
+                    HttpServeDirectory::Bundled
                 } else {
                     HttpServeDirectory::None
                 },
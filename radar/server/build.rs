@@ -0,0 +1,140 @@
+#[cfg(feature = "static-bundle")]
+mod static_bundle {
+    use std::{
+        env,
+        fmt::Write as _,
+        fs,
+        io::Write,
+        path::{
+            Path,
+            PathBuf,
+        },
+    };
+
+    use flate2::{
+        write::GzEncoder,
+        Compression,
+    };
+    use sha1::{
+        Digest,
+        Sha1,
+    };
+
+    /// Files smaller than this will not be compressed
+    const COMPRESSION_MIN_SIZE: usize = 1024;
+
+    fn collect_files(directory: &Path, result: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        for entry in fs::read_dir(directory)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                collect_files(&path, result)?;
+            } else {
+                result.push(path);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_compressible(mime: &str) -> bool {
+        mime.starts_with("text/")
+            || mime.ends_with("+xml")
+            || matches!(
+                mime,
+                "application/javascript"
+                    | "application/json"
+                    | "application/wasm"
+                    | "application/xml"
+                    | "image/x-icon"
+                    | "image/vnd.microsoft.icon"
+            )
+    }
+
+    pub fn generate() -> anyhow::Result<()> {
+        println!("cargo:rerun-if-env-changed=RADAR_WEB_DIST");
+        let web_dist = match env::var_os("RADAR_WEB_DIST") {
+            Some(path) => PathBuf::from(path),
+            None => PathBuf::from(env::var("CARGO_MANIFEST_DIR")?).join("../web/dist"),
+        };
+        println!("cargo:rerun-if-changed={}", web_dist.display());
+
+        if !web_dist.join("index.html").is_file() {
+            anyhow::bail!(
+                "missing the web radar build at {}. Build it with \"yarn build\" in radar/web or set RADAR_WEB_DIST.",
+                web_dist.display()
+            );
+        }
+
+        let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+        let compressed_dir = out_dir.join("static-bundle");
+        fs::create_dir_all(&compressed_dir)?;
+
+        let mut files = Vec::new();
+        collect_files(&web_dist, &mut files)?;
+        files.sort();
+
+        let mut output = String::new();
+        writeln!(output, "pub static BUNDLED_FILES: &[BundledFile] = &[")?;
+        for (index, file) in files.iter().enumerate() {
+            println!("cargo:rerun-if-changed={}", file.display());
+
+            let path = file
+                .strip_prefix(&web_dist)?
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let content = fs::read(file)?;
+
+            let mime = mime_guess::from_path(file)
+                .first_or_octet_stream()
+                .essence_str()
+                .to_string();
+            let etag =
+                Sha1::digest(&content)
+                    .iter()
+                    .take(10)
+                    .fold(String::new(), |mut result, byte| {
+                        let _ = write!(result, "{:02x}", byte);
+                        result
+                    });
+
+            let mut content_gzip = "None".to_string();
+            if content.len() >= COMPRESSION_MIN_SIZE && is_compressible(&mime) {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+                encoder.write_all(&content)?;
+                let compressed = encoder.finish()?;
+
+                /* only keep the compressed variant if it's worth it */
+                if compressed.len() < content.len() * 9 / 10 {
+                    let compressed_file = compressed_dir.join(format!("{}.gz", index));
+                    fs::write(&compressed_file, compressed)?;
+                    content_gzip = format!("Some(include_bytes!({:?}))", compressed_file);
+                }
+            }
+
+            writeln!(output, "    BundledFile {{")?;
+            writeln!(output, "        path: {:?},", path)?;
+            writeln!(output, "        mime: {:?},", mime)?;
+            writeln!(output, "        etag: {:?},", etag)?;
+            writeln!(
+                output,
+                "        content: include_bytes!({:?}),",
+                fs::canonicalize(file)?
+            )?;
+            writeln!(output, "        content_gzip: {},", content_gzip)?;
+            writeln!(output, "    }},")?;
+        }
+        writeln!(output, "];")?;
+
+        fs::write(out_dir.join("static_bundle.rs"), output)?;
+        Ok(())
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    #[cfg(feature = "static-bundle")]
+    static_bundle::generate()?;
+
+    Ok(())
+}
@@ -0,0 +1,200 @@
+use std::borrow::Cow;
+
+use percent_encoding::percent_decode_str;
+use warp::{
+    filters::BoxedFilter,
+    http::{
+        header,
+        Response,
+        StatusCode,
+    },
+    hyper::Body,
+    Filter,
+    Reply,
+};
+
+/// A static web file embedded at build time
+pub struct BundledFile {
+    /// Path relative to the web root (e.g. assets/web-radar.js)
+    pub path: &'static str,
+    pub mime: &'static str,
+    pub etag: &'static str,
+
+    pub content: &'static [u8],
+    pub content_gzip: Option<&'static [u8]>,
+}
+
+include!(concat!(env!("OUT_DIR"), "/static_bundle.rs"));
+
+const INDEX_FILE: &str = "index.html";
+
+/// Files with a content hash in their name can be cached forever
+const ASSET_DIRECTORY: &str = "assets/";
+
+fn find_file(path: &str) -> Option<&'static BundledFile> {
+    BUNDLED_FILES.iter().find(|file| file.path == path)
+}
+
+/// Decode the requested path (e.g. assets/my%20file.js).
+/// Returns None if the path is not valid UTF-8 after decoding.
+fn decode_path(tail: &str) -> Option<Cow<'_, str>> {
+    let path = percent_decode_str(tail).decode_utf8().ok()?;
+    Some(if path.is_empty() {
+        Cow::Borrowed(INDEX_FILE)
+    } else {
+        path
+    })
+}
+
+/// Check if the client accepts a gzip encoded response
+fn accepts_gzip(accept_encoding: &str) -> bool {
+    accept_encoding.split(',').any(|encoding| {
+        let mut parts = encoding.split(';');
+        let name = parts.next().unwrap_or_default().trim();
+        if !name.eq_ignore_ascii_case("gzip") && name != "*" {
+            return false;
+        }
+
+        /* an encoding with q=0 is explicitly not acceptable */
+        !parts.any(|param| {
+            param
+                .trim()
+                .strip_prefix("q=")
+                .and_then(|value| value.parse::<f32>().ok())
+                .is_some_and(|value| value <= 0.0)
+        })
+    })
+}
+
+fn matches_etag(if_none_match: &str, etag: &str) -> bool {
+    if_none_match.split(',').any(|value| {
+        let value = value.trim();
+        value == "*" || value.trim_start_matches("W/") == etag
+    })
+}
+
+fn file_response(
+    file: &BundledFile,
+    if_none_match: Option<String>,
+    accept_encoding: Option<String>,
+) -> warp::http::Result<Response<Body>> {
+    let gzip = file
+        .content_gzip
+        .filter(|_| accept_encoding.as_deref().is_some_and(accepts_gzip));
+
+    /* every encoding of the content needs its own strong ETag */
+    let etag = if gzip.is_some() {
+        format!("\"{}-gzip\"", file.etag)
+    } else {
+        format!("\"{}\"", file.etag)
+    };
+
+    let cache_control = if file.path.starts_with(ASSET_DIRECTORY) {
+        "public, max-age=31536000, immutable"
+    } else {
+        "no-cache"
+    };
+
+    let mut response = Response::builder()
+        .header(header::ETAG, &etag)
+        .header(header::CACHE_CONTROL, cache_control);
+    if file.content_gzip.is_some() {
+        response = response.header(header::VARY, "Accept-Encoding");
+    }
+
+    if if_none_match.is_some_and(|value| matches_etag(&value, &etag)) {
+        return response
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Body::empty());
+    }
+
+    response = response.header(header::CONTENT_TYPE, file.mime);
+    if let Some(content) = gzip {
+        response
+            .header(header::CONTENT_ENCODING, "gzip")
+            .body(Body::from(content))
+    } else {
+        response.body(Body::from(file.content))
+    }
+}
+
+/// Serve the bundled web files.
+/// Unknown paths fall back to the index file, so the client side routing works.
+pub fn bundled_files() -> BoxedFilter<(Box<dyn Reply>,)> {
+    warp::get()
+        .and(warp::path::tail())
+        .and(warp::header::optional::<String>("if-none-match"))
+        .and(warp::header::optional::<String>("accept-encoding"))
+        .map(
+            |tail: warp::path::Tail,
+             if_none_match: Option<String>,
+             accept_encoding: Option<String>| {
+                let file = decode_path(tail.as_str())
+                    .and_then(|path| find_file(&path))
+                    .or_else(|| find_file(INDEX_FILE))
+                    .expect("the bundle to contain the index file");
+
+                match file_response(file, if_none_match, accept_encoding) {
+                    Ok(response) => Box::new(response) as Box<dyn Reply>,
+                    Err(error) => {
+                        log::warn!("Failed to build response for {}: {}", file.path, error);
+                        Box::new(StatusCode::INTERNAL_SERVER_ERROR) as Box<dyn Reply>
+                    }
+                }
+            },
+        )
+        .boxed()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        accepts_gzip,
+        decode_path,
+        matches_etag,
+        INDEX_FILE,
+    };
+
+    #[test]
+    fn test_decode_path() {
+        assert_eq!(decode_path("").as_deref(), Some(INDEX_FILE));
+        assert_eq!(
+            decode_path("assets/web-radar.js").as_deref(),
+            Some("assets/web-radar.js")
+        );
+        assert_eq!(
+            decode_path("assets/my%20file.js").as_deref(),
+            Some("assets/my file.js")
+        );
+        assert_eq!(decode_path("%C3%A4.png").as_deref(), Some("\u{e4}.png"));
+        assert_eq!(decode_path("%FF.png"), None);
+    }
+
+    #[test]
+    fn test_accepts_gzip() {
+        assert!(accepts_gzip("gzip"));
+        assert!(accepts_gzip("GZIP"));
+        assert!(accepts_gzip("deflate, gzip, br"));
+        assert!(accepts_gzip("br;q=1.0, gzip;q=0.5"));
+        assert!(accepts_gzip("*"));
+
+        assert!(!accepts_gzip(""));
+        assert!(!accepts_gzip("deflate, br"));
+        assert!(!accepts_gzip("gzip;q=0"));
+        assert!(!accepts_gzip("gzip; q=0.0, br"));
+        assert!(!accepts_gzip("x-gzip"));
+    }
+
+    #[test]
+    fn test_matches_etag() {
+        assert!(matches_etag("\"abc\"", "\"abc\""));
+        assert!(matches_etag("W/\"abc\"", "\"abc\""));
+        assert!(matches_etag("\"xyz\", \"abc\"", "\"abc\""));
+        assert!(matches_etag("*", "\"abc\""));
+
+        assert!(!matches_etag("\"abc\"", "\"abc-gzip\""));
+        assert!(!matches_etag("\"abc-gzip\"", "\"abc\""));
+        assert!(!matches_etag("abc", "\"abc\""));
+        assert!(!matches_etag("", "\"abc\""));
+    }
+}
@@ -1,4 +1,7 @@
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::Duration,
+};
 
 use radar_shared::protocol::{
     C2SMessage,
@@ -9,10 +12,29 @@ use tokio::sync::RwLock;
 use crate::{
     ClientState,
     PubClient,
+    PubSessionOptions,
     PubSessionSubscribeResult,
     RadarServer,
 };
 
+const SESSION_PASSWORD_MAX_LENGTH: usize = 128;
+
+/// Treat empty passwords as no password
+fn validate_password(password: Option<String>) -> Result<Option<String>, S2CMessage> {
+    match password {
+        Some(password) if password.len() > SESSION_PASSWORD_MAX_LENGTH => {
+            Err(S2CMessage::ResponseError {
+                error: format!(
+                    "the password must not be longer than {} characters",
+                    SESSION_PASSWORD_MAX_LENGTH
+                ),
+            })
+        }
+        Some(password) if password.is_empty() => Ok(None),
+        password => Ok(password),
+    }
+}
+
 pub struct ServerCommandHandler {
     pub server: Arc<RwLock<RadarServer>>,
     pub client: Arc<RwLock<PubClient>>,
@@ -20,23 +42,57 @@ pub struct ServerCommandHandler {
 }
 
 impl ServerCommandHandler {
+    /// Session id of the session published by this client
+    async fn published_session_id(&self) -> Option<String> {
+        let client = self.client.read().await;
+        match &client.state {
+            ClientState::Publisher { session_id } => Some(session_id.clone()),
+            _ => None,
+        }
+    }
+
     pub async fn handle_command(&self, command: C2SMessage) -> S2CMessage {
         match command {
-            C2SMessage::InitializePublish { .. } => {
+            C2SMessage::InitializePublish {
+                password,
+                max_lifetime,
+                ..
+            } => {
+                let password = match validate_password(password) {
+                    Ok(password) => password,
+                    Err(response) => return response,
+                };
+
+                if max_lifetime == Some(0) {
+                    return S2CMessage::ResponseError {
+                        error: "the session lifetime must be greater than zero".to_string(),
+                    };
+                }
+
+                let options = PubSessionOptions {
+                    password,
+                    max_lifetime: max_lifetime.map(Duration::from_secs),
+                };
+
                 let mut server = self.server.write().await;
-                let Some(session) = server.pub_session_create(self.client_id).await else {
+                let Some(session) = server.pub_session_create(self.client_id, options).await else {
                     return S2CMessage::ResponseInvalidClientState;
                 };
 
                 S2CMessage::ResponseInitializePublish {
                     session_id: session.session_id.clone(),
                     version: 1,
+                    expires_in: max_lifetime,
                 }
             }
-            C2SMessage::InitializeSubscribe { session_id, .. } => {
+            C2SMessage::InitializeSubscribe {
+                session_id,
+                password,
+                ..
+            } => {
                 let mut server = self.server.write().await;
                 match server
-                    .pub_session_subscribe(&session_id, self.client_id)
+                    .pub_session_subscribe(&session_id, self.client_id, password.as_deref())
                     .await
                 {
                     PubSessionSubscribeResult::Success => S2CMessage::ResponseSubscribeSuccess,
@@ -49,6 +105,15 @@ impl ServerCommandHandler {
                     PubSessionSubscribeResult::InvalidSessionId => {
                         S2CMessage::ResponseSessionInvalidId
                     }
+                    PubSessionSubscribeResult::PasswordRequired => {
+                        S2CMessage::ResponseSessionPasswordRequired
+                    }
+                    PubSessionSubscribeResult::InvalidPassword => {
+                        S2CMessage::ResponseSessionPasswordInvalid
+                    }
+                    PubSessionSubscribeResult::TooManyAuthFailures => S2CMessage::ResponseError {
+                        error: "too many invalid password attempts, try again later".to_string(),
+                    },
                 }
             }
             C2SMessage::RadarUpdate { update } => {
@@ -77,6 +142,36 @@ impl ServerCommandHandler {
 
                 S2CMessage::ResponseSuccess
             }
+            C2SMessage::SessionSetPassword { password } => {
+                let Some(session_id) = self.published_session_id().await else {
+                    return S2CMessage::ResponseInvalidClientState;
+                };
+
+                let password = match validate_password(password) {
+                    Ok(password) => password,
+                    Err(response) => return response,
+                };
+
+                let mut server = self.server.write().await;
+                if server.pub_session_set_password(&session_id, password) {
+                    S2CMessage::ResponseSuccess
+                } else {
+                    S2CMessage::ResponseSessionInvalidId
+                }
+            }
+            C2SMessage::SessionKickSubscribers => {
+                let Some(session_id) = self.published_session_id().await else {
+                    return S2CMessage::ResponseInvalidClientState;
+                };
+
+                let mut server = self.server.write().await;
+                if server.pub_session_find(&session_id).is_none() {
+                    return S2CMessage::ResponseSessionInvalidId;
+                }
+
+                server.pub_session_kick_subscribers(&session_id).await;
+                S2CMessage::ResponseSuccess
+            }
             C2SMessage::Disconnect { .. } => {
                 /* command is already handled within the connection code */
                 S2CMessage::ResponseSuccess
@@ -5,3 +5,6 @@ mod server;
 pub use server::*;
 
 mod handler;
+
+#[cfg(feature = "static-bundle")]
+mod bundle;
@@ -1,11 +1,19 @@
 use std::{
     collections::BTreeMap,
-    net::SocketAddr,
+    mem,
+    net::{
+        IpAddr,
+        SocketAddr,
+    },
     path::PathBuf,
     sync::{
         Arc,
         Weak,
     },
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
 use anyhow::anyhow;
@@ -32,6 +40,7 @@ use tokio::{
         RwLock,
     },
     task::JoinHandle,
+    time,
 };
 use warp::{
     filters::ws::Message,
@@ -44,13 +53,102 @@ use crate::{
     ClientState,
 };
 
+/// Interval in which expired sessions will be closed
+const SESSION_EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Failed password attempts after which an address will be locked out
+pub const SESSION_MAX_AUTH_FAILURES: u32 = 5;
+
+/// Initial lockout duration, doubled with every further failed attempt
+const SESSION_AUTH_LOCKOUT: Duration = Duration::from_secs(30);
+
+/// Upper bound for the lockout duration
+const SESSION_AUTH_LOCKOUT_MAX: Duration = Duration::from_secs(60 * 60);
+
+/// Time without failed attempts after which the failures of an address are forgotten
+const SESSION_AUTH_FAILURE_RESET: Duration = Duration::from_secs(60 * 60);
+
+/// Failed session password attempts of a remote address.
+/// Tracked per address as clients can simply reconnect.
+struct AuthFailures {
+    count: u32,
+    last_failure: Instant,
+    locked_until: Option<Instant>,
+}
+
+impl AuthFailures {
+    fn new(now: Instant) -> Self {
+        Self {
+            count: 0,
+            last_failure: now,
+            locked_until: None,
+        }
+    }
+
+    fn is_locked(&self, now: Instant) -> bool {
+        self.locked_until
+            .is_some_and(|locked_until| locked_until > now)
+    }
+
+    fn is_stale(&self, now: Instant) -> bool {
+        !self.is_locked(now) && now.duration_since(self.last_failure) >= SESSION_AUTH_FAILURE_RESET
+    }
+
+    fn record_failure(&mut self, now: Instant) {
+        if self.is_stale(now) {
+            self.count = 0;
+        }
+
+        self.count += 1;
+        self.last_failure = now;
+        if self.count >= SESSION_MAX_AUTH_FAILURES {
+            let exponent = (self.count - SESSION_MAX_AUTH_FAILURES).min(16);
+            let lockout = SESSION_AUTH_LOCKOUT
+                .saturating_mul(1 << exponent)
+                .min(SESSION_AUTH_LOCKOUT_MAX);
+            self.locked_until = Some(now + lockout);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct PubSessionOptions {
+    /// Password subscribers have to provide
+    pub password: Option<String>,
+
+    /// Lifetime after which the session will be closed
+    pub max_lifetime: Option<Duration>,
+}
+
 pub struct PubSession {
     pub owner_id: u32,
     pub session_id: String,
+    pub expires_at: Option<Instant>,
+
+    password: Option<String>,
     subscriber: BTreeMap<u32, mpsc::Sender<S2CMessage>>,
 }
 
+/// Compare all bytes to not leak the length of the matching prefix through timing
+fn password_matches(expected: &str, password: &str) -> bool {
+    expected.len() == password.len()
+        && expected
+            .bytes()
+            .zip(password.bytes())
+            .fold(0, |result, (a, b)| result | (a ^ b))
+            == 0
+}
+
 impl PubSession {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Instant::now())
+    }
+
+    pub fn requires_password(&self) -> bool {
+        self.password.is_some()
+    }
+
     pub fn broadcast(&self, message: &S2CMessage) {
         for subscriber in self.subscriber.values() {
             let _ = subscriber.try_send(message.clone());
@@ -73,16 +171,16 @@ pub enum HttpServeDirectory {
     Bundled,
 }
 
-impl HttpServeDirectory {}
-
 pub struct RadarServer {
     ref_self: Weak<RwLock<RadarServer>>,
     client_id_counter: u32,
 
     clients: BTreeMap<u32, Arc<RwLock<PubClient>>>,
     pub_sessions: BTreeMap<String, PubSession>,
+    auth_failures: BTreeMap<IpAddr, AuthFailures>,
 
     www_acceptor: Option<JoinHandle<()>>,
+    expiry_checker: Option<JoinHandle<()>>,
 }
 
 impl RadarServer {
@@ -93,8 +191,10 @@ impl RadarServer {
 
             clients: Default::default(),
             pub_sessions: Default::default(),
+            auth_failures: Default::default(),
 
             www_acceptor: None,
+            expiry_checker: None,
         };
 
         Arc::new_cyclic(|weak| {
@@ -142,10 +242,7 @@ impl RadarServer {
 
                         let mut server = server.write().await;
                         let client_fut = server
-                            .register_client(
-                                PubClient::new(message_tx, address.clone()),
-                                message_rx,
-                            )
+                            .register_client(PubClient::new(message_tx, address), message_rx)
                             .await;
 
                         tokio::spawn(client_fut);
@@ -239,8 +336,14 @@ impl RadarServer {
                 .or(warp::fs::file(path.join("index.html")))
                 .map(|reply| Box::new(reply) as Box<dyn warp::Reply>)
                 .boxed(),
+            #[cfg(feature = "static-bundle")]
+            HttpServeDirectory::Bundled => ws_route
+                .or(crate::bundle::bundled_files())
+                .map(|reply| Box::new(reply) as Box<dyn warp::Reply>)
+                .boxed(),
+            #[cfg(not(feature = "static-bundle"))]
             HttpServeDirectory::Bundled => {
-                anyhow::bail!("bundled is currently not supported");
+                anyhow::bail!("the server has been built without the static-bundle feature");
             }
             HttpServeDirectory::None => ws_route
                 .map(|reply| Box::new(reply) as Box<dyn warp::Reply>)
@@ -249,12 +352,34 @@ impl RadarServer {
 
         let (address, future) = warp::serve(routes).try_bind_ephemeral(addr)?;
         self.www_acceptor = Some(tokio::spawn(future));
+        self.start_expiry_checker();
 
         log::info!("Started server on {}", address);
 
         Ok(())
     }
 
+    fn start_expiry_checker(&mut self) {
+        if self.expiry_checker.is_some() {
+            return;
+        }
+
+        let server = self.ref_self.clone();
+        self.expiry_checker = Some(tokio::spawn(async move {
+            let mut interval = time::interval(SESSION_EXPIRY_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+
+                let Some(server) = server.upgrade() else {
+                    break;
+                };
+                let mut server = server.write().await;
+                server.pub_session_close_expired().await;
+                server.prune_auth_failures();
+            }
+        }));
+    }
+
     pub async fn unregister_client(&mut self, client_id: u32) {
         let client = match self.clients.remove(&client_id) {
             Some(client) => client,
@@ -335,7 +460,11 @@ impl RadarServer {
         }
     }
 
-    pub async fn pub_session_create(&mut self, owner_id: u32) -> Option<&PubSession> {
+    pub async fn pub_session_create(
+        &mut self,
+        owner_id: u32,
+        options: PubSessionOptions,
+    ) -> Option<&PubSession> {
         let owner = match self.clients.get(&owner_id) {
             Some(client) => client,
             None => return None,
@@ -357,6 +486,11 @@ impl RadarServer {
             PubSession {
                 owner_id,
                 session_id: session_id.clone(),
+                expires_at: options
+                    .max_lifetime
+                    .map(|lifetime| Instant::now() + lifetime),
+
+                password: options.password,
                 subscriber: Default::default(),
             },
         );
@@ -388,10 +522,95 @@ impl RadarServer {
         }
     }
 
+    /// Close all expired sessions and notify the publishers and subscribers
+    pub async fn pub_session_close_expired(&mut self) {
+        let expired_sessions = self
+            .pub_sessions
+            .values()
+            .filter(|session| session.is_expired())
+            .map(|session| (session.session_id.clone(), session.owner_id))
+            .collect::<Vec<_>>();
+
+        for (session_id, owner_id) in expired_sessions {
+            log::info!("Session {} expired", session_id);
+            if let Some(session) = self.pub_sessions.get(&session_id) {
+                session.broadcast(&S2CMessage::NotifySessionExpired);
+            }
+
+            if let Some(owner) = self.clients.get(&owner_id) {
+                let mut owner = owner.write().await;
+                owner.send_command(S2CMessage::NotifySessionExpired);
+                owner.state = ClientState::Uninitialized;
+            }
+
+            self.pub_session_close(&session_id).await;
+        }
+    }
+
+    /// Forget the failed password attempts of addresses which are no longer locked out
+    fn prune_auth_failures(&mut self) {
+        let now = Instant::now();
+        self.auth_failures
+            .retain(|_, failures| !failures.is_stale(now));
+    }
+
     pub fn pub_session_find(&self, session_id: &str) -> Option<&PubSession> {
         self.pub_sessions.get(session_id)
     }
 
+    /// Change the session password. Already subscribed clients are not affected.
+    pub fn pub_session_set_password(&mut self, session_id: &str, password: Option<String>) -> bool {
+        let Some(session) = self.pub_sessions.get_mut(session_id) else {
+            return false;
+        };
+
+        log::debug!(
+            "Session {} password {}",
+            session_id,
+            if password.is_some() {
+                "changed"
+            } else {
+                "removed"
+            }
+        );
+        session.password = password;
+        true
+    }
+
+    /// Remove all subscribers from the session and return the amount of removed subscribers
+    pub async fn pub_session_kick_subscribers(&mut self, session_id: &str) -> usize {
+        let Some(session) = self.pub_sessions.get_mut(session_id) else {
+            return 0;
+        };
+
+        let subscriber = mem::take(&mut session.subscriber);
+        for (client_id, tx) in subscriber.iter() {
+            let _ = tx.try_send(S2CMessage::NotifySessionKicked);
+
+            if let Some(client) = self.clients.get(client_id) {
+                let mut client = client.write().await;
+                client.state = ClientState::Uninitialized;
+            }
+        }
+
+        /* all subscribers are gone, only the publisher is left to be notified */
+        if let Some(owner) = self.clients.get(&session.owner_id) {
+            owner
+                .read()
+                .await
+                .send_command(S2CMessage::NotifyViewCount {
+                    viewers: session.subscriber_count(),
+                });
+        }
+
+        log::debug!(
+            "Kicked {} subscribers from session {}",
+            subscriber.len(),
+            session_id
+        );
+        subscriber.len()
+    }
+
     pub async fn pub_session_unsubscribe(&mut self, session_id: &String, client_id: u32) {
         if let Some(session) = self.pub_sessions.get_mut(session_id) {
             session.subscriber.remove(&client_id);
@@ -421,6 +640,7 @@ impl RadarServer {
         &mut self,
         session_id: &String,
         client_id: u32,
+        password: Option<&str>,
     ) -> PubSessionSubscribeResult {
         let client = match self.clients.get(&client_id) {
             Some(client) => client,
@@ -433,10 +653,43 @@ impl RadarServer {
         }
 
         let session = match self.pub_sessions.get_mut(session_id) {
-            Some(session) => session,
-            None => return PubSessionSubscribeResult::InvalidSessionId,
+            Some(session) if !session.is_expired() => session,
+            _ => return PubSessionSubscribeResult::InvalidSessionId,
         };
 
+        if let Some(expected_password) = &session.password {
+            let now = Instant::now();
+            let address = client.address.ip();
+            if self
+                .auth_failures
+                .get(&address)
+                .is_some_and(|failures| failures.is_locked(now))
+            {
+                return PubSessionSubscribeResult::TooManyAuthFailures;
+            }
+
+            match password {
+                None => return PubSessionSubscribeResult::PasswordRequired,
+                Some(password) if !password_matches(expected_password, password) => {
+                    self.auth_failures
+                        .entry(address)
+                        .or_insert_with(|| AuthFailures::new(now))
+                        .record_failure(now);
+
+                    log::debug!(
+                        "Client {} ({}) provided an invalid password for session {}",
+                        client_id,
+                        address,
+                        session_id
+                    );
+                    return PubSessionSubscribeResult::InvalidPassword;
+                }
+                Some(_) => {
+                    self.auth_failures.remove(&address);
+                }
+            }
+        }
+
         session
             .subscriber
             .insert(client.client_id, client.tx.clone());
@@ -457,4 +710,262 @@ pub enum PubSessionSubscribeResult {
     InvalidClientState,
     InvalidSessionId,
     InvalidClientId,
+    PasswordRequired,
+    InvalidPassword,
+    TooManyAuthFailures,
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::Arc,
+        time::{
+            Duration,
+            Instant,
+        },
+    };
+
+    use radar_shared::protocol::S2CMessage;
+    use tokio::sync::{
+        mpsc,
+        RwLock,
+    };
+
+    use super::{
+        password_matches,
+        AuthFailures,
+        PubSessionOptions,
+        PubSessionSubscribeResult,
+        RadarServer,
+        SESSION_AUTH_FAILURE_RESET,
+        SESSION_AUTH_LOCKOUT,
+        SESSION_AUTH_LOCKOUT_MAX,
+        SESSION_MAX_AUTH_FAILURES,
+    };
+    use crate::client::PubClient;
+
+    async fn register_client(
+        server: &Arc<RwLock<RadarServer>>,
+        address: &str,
+    ) -> (u32, mpsc::Receiver<S2CMessage>) {
+        let (tx, rx) = mpsc::channel(16);
+        let (_event_tx, event_rx) = mpsc::channel(16);
+
+        let mut server = server.write().await;
+        /* the client event loop is not needed as no events will be sent */
+        let _client_loop = server
+            .register_client(PubClient::new(tx, address.parse().unwrap()), event_rx)
+            .await;
+        (server.client_id_counter, rx)
+    }
+
+    async fn create_session(
+        server: &Arc<RwLock<RadarServer>>,
+        options: PubSessionOptions,
+    ) -> (String, mpsc::Receiver<S2CMessage>) {
+        let (owner_id, owner_rx) = register_client(server, "10.0.0.1:1000").await;
+        let session_id = server
+            .write()
+            .await
+            .pub_session_create(owner_id, options)
+            .await
+            .unwrap()
+            .session_id
+            .clone();
+        (session_id, owner_rx)
+    }
+
+    async fn subscribe(
+        server: &Arc<RwLock<RadarServer>>,
+        session_id: &String,
+        address: &str,
+        password: Option<&str>,
+    ) -> PubSessionSubscribeResult {
+        let (client_id, _) = register_client(server, address).await;
+        server
+            .write()
+            .await
+            .pub_session_subscribe(session_id, client_id, password)
+            .await
+    }
+
+    #[test]
+    fn test_password_matches() {
+        assert!(password_matches("secret", "secret"));
+        assert!(password_matches("", ""));
+
+        assert!(!password_matches("secret", "Secret"));
+        assert!(!password_matches("secret", "secre"));
+        assert!(!password_matches("secret", "secrets"));
+        assert!(!password_matches("secret", ""));
+    }
+
+    #[test]
+    fn test_auth_failures_backoff() {
+        let now = Instant::now();
+        let mut failures = AuthFailures::new(now);
+        for _ in 1..SESSION_MAX_AUTH_FAILURES {
+            failures.record_failure(now);
+            assert!(!failures.is_locked(now));
+        }
+
+        failures.record_failure(now);
+        assert!(failures.is_locked(now));
+        assert!(failures.is_locked(now + SESSION_AUTH_LOCKOUT - Duration::from_secs(1)));
+        assert!(!failures.is_locked(now + SESSION_AUTH_LOCKOUT));
+
+        /* every further failure doubles the lockout */
+        let now = now + SESSION_AUTH_LOCKOUT;
+        failures.record_failure(now);
+        assert!(failures.is_locked(now + SESSION_AUTH_LOCKOUT));
+        assert!(!failures.is_locked(now + SESSION_AUTH_LOCKOUT * 2));
+
+        for _ in 0..32 {
+            failures.record_failure(now);
+        }
+        assert!(failures.is_locked(now + SESSION_AUTH_LOCKOUT_MAX - Duration::from_secs(1)));
+        assert!(!failures.is_locked(now + SESSION_AUTH_LOCKOUT_MAX));
+
+        /* failures will be forgotten after some time */
+        assert!(!failures.is_stale(now));
+        let now = now + SESSION_AUTH_LOCKOUT_MAX.max(SESSION_AUTH_FAILURE_RESET);
+        assert!(failures.is_stale(now));
+        failures.record_failure(now);
+        assert_eq!(failures.count, 1);
+        assert!(!failures.is_locked(now));
+    }
+
+    #[tokio::test]
+    async fn test_session_password() {
+        let server = RadarServer::new();
+        let (session_id, _owner_rx) = create_session(
+            &server,
+            PubSessionOptions {
+                password: Some("secret".to_string()),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        assert!(matches!(
+            subscribe(&server, &session_id, "10.0.0.2:2000", None).await,
+            PubSessionSubscribeResult::PasswordRequired
+        ));
+        assert!(matches!(
+            subscribe(&server, &session_id, "10.0.0.2:2000", Some("secret")).await,
+            PubSessionSubscribeResult::Success
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_session_lockout() {
+        let server = RadarServer::new();
+        let (session_id, _owner_rx) = create_session(
+            &server,
+            PubSessionOptions {
+                password: Some("secret".to_string()),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        /* every attempt uses a new connection, like a reconnecting client */
+        for port in 0..SESSION_MAX_AUTH_FAILURES {
+            let address = format!("10.0.0.2:{}", 2000 + port);
+            assert!(matches!(
+                subscribe(&server, &session_id, &address, Some("wrong")).await,
+                PubSessionSubscribeResult::InvalidPassword
+            ));
+        }
+
+        assert!(matches!(
+            subscribe(&server, &session_id, "10.0.0.2:3000", Some("secret")).await,
+            PubSessionSubscribeResult::TooManyAuthFailures
+        ));
+
+        /* other addresses are not affected */
+        assert!(matches!(
+            subscribe(&server, &session_id, "10.0.0.3:2000", Some("secret")).await,
+            PubSessionSubscribeResult::Success
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_session_expiry() {
+        let server = RadarServer::new();
+        let (session_id, mut owner_rx) = create_session(
+            &server,
+            PubSessionOptions {
+                max_lifetime: Some(Duration::ZERO),
+                ..Default::default()
+            },
+        )
+        .await;
+        let (persistent_session_id, _) = create_session(&server, Default::default()).await;
+
+        {
+            let server = server.read().await;
+            assert!(server.pub_session_find(&session_id).unwrap().is_expired());
+            assert!(!server
+                .pub_session_find(&persistent_session_id)
+                .unwrap()
+                .is_expired());
+        }
+
+        assert!(matches!(
+            subscribe(&server, &session_id, "10.0.0.2:2000", None).await,
+            PubSessionSubscribeResult::InvalidSessionId
+        ));
+
+        server.write().await.pub_session_close_expired().await;
+        assert!(matches!(
+            owner_rx.try_recv(),
+            Ok(S2CMessage::NotifySessionExpired)
+        ));
+
+        let server = server.read().await;
+        assert!(server.pub_session_find(&session_id).is_none());
+        assert!(server.pub_session_find(&persistent_session_id).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_session_kick_subscribers() {
+        let server = RadarServer::new();
+        let (session_id, mut owner_rx) = create_session(&server, Default::default()).await;
+
+        let (client_id, mut client_rx) = register_client(&server, "10.0.0.2:2000").await;
+        assert!(matches!(
+            server
+                .write()
+                .await
+                .pub_session_subscribe(&session_id, client_id, None)
+                .await,
+            PubSessionSubscribeResult::Success
+        ));
+        assert!(matches!(
+            client_rx.try_recv(),
+            Ok(S2CMessage::NotifyViewCount { viewers: 1 })
+        ));
+
+        assert_eq!(
+            server
+                .write()
+                .await
+                .pub_session_kick_subscribers(&session_id)
+                .await,
+            1
+        );
+        assert!(matches!(
+            client_rx.try_recv(),
+            Ok(S2CMessage::NotifySessionKicked)
+        ));
+        assert!(matches!(
+            owner_rx.try_recv(),
+            Ok(S2CMessage::NotifyViewCount { viewers: 0 })
+        ));
+
+        let server = server.read().await;
+        let session = server.pub_session_find(&session_id).unwrap();
+        assert_eq!(session.subscriber_count(), 0);
+    }
 }
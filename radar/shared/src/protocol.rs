@@ -18,33 +18,82 @@ pub enum RadarUpdate {
 pub enum SubscribeResult {
     Success,
     SessionDoesNotExists,
-    // SessionRequiresPassword,
+    SessionRequiresPassword,
+    SessionInvalidPassword,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum S2CMessage {
     // Generic responses
     ResponseSuccess,
-    ResponseError { error: String },
+    ResponseError {
+        error: String,
+    },
 
     ResponseInvalidClientState,
-    ResponseInitializePublish { session_id: String, version: u32 },
+    ResponseInitializePublish {
+        session_id: String,
+        version: u32,
+
+        /// Seconds until the session expires
+        #[serde(default)]
+        expires_in: Option<u64>,
+    },
     ResponseSubscribeSuccess,
     ResponseSessionInvalidId,
+    ResponseSessionPasswordRequired,
+    ResponseSessionPasswordInvalid,
 
-    NotifyRadarUpdate { update: RadarUpdate },
-    NotifyViewCount { viewers: usize },
+    NotifyRadarUpdate {
+        update: RadarUpdate,
+    },
+    NotifyViewCount {
+        viewers: usize,
+    },
     NotifySessionClosed,
+    NotifySessionExpired,
+
+    /// The subscriber has been removed from the session by the publisher
+    NotifySessionKicked,
 }
 
 #[derive(Serialize, Deserialize)]
 pub enum C2SMessage {
-    InitializePublish { version: u32 },
-    InitializeSubscribe { version: u32, session_id: String },
+    InitializePublish {
+        version: u32,
+
+        /// Password subscribers have to provide
+        #[serde(default)]
+        password: Option<String>,
+
+        /// Max lifetime of the session in seconds
+        #[serde(default)]
+        max_lifetime: Option<u64>,
+    },
+    InitializeSubscribe {
+        version: u32,
+        session_id: String,
+
+        #[serde(default)]
+        password: Option<String>,
+    },
+
+    RadarUpdate {
+        update: RadarUpdate,
+    },
+
+    /// Change the password of the published session.
+    /// Already subscribed clients stay subscribed.
+    SessionSetPassword {
+        password: Option<String>,
+    },
 
-    RadarUpdate { update: RadarUpdate },
+    /// Remove all subscribers from the published session
+    SessionKickSubscribers,
 
-    Disconnect { message: String },
+    Disconnect {
+        message: String,
+    },
 }
 
 pub enum ClientEvent<T> {
@@ -3,6 +3,9 @@ import { EventEmitter } from "../utils/ee";
 
 export type SubscriberClientState = {
     state: "new" | "connecting" | "initializing" | "connected" | "disconnected",
+} | {
+    state: "password-required",
+    invalidPassword: boolean,
 } | {
     state: "failed",
     reason: string
@@ -39,6 +42,16 @@ export class SubscriberClient {
             this.closeSocket();
         };
 
+        this.commandHandler["ResponseSessionPasswordRequired"] = () => {
+            this.updateState({ state: "password-required", invalidPassword: false });
+            this.closeSocket();
+        };
+
+        this.commandHandler["ResponseSessionPasswordInvalid"] = () => {
+            this.updateState({ state: "password-required", invalidPassword: true });
+            this.closeSocket();
+        };
+
         this.commandHandler["ResponseSubscribeSuccess"] = () => {
             this.updateState({ state: "connected" });
         };
@@ -50,6 +63,16 @@ export class SubscriberClient {
         this.commandHandler["NotifySessionClosed"] = () => {
             this.updateState({ state: "disconnected" });
         };
+
+        this.commandHandler["NotifySessionExpired"] = () => {
+            this.updateState({ state: "failed", reason: "session expired" });
+            this.closeSocket();
+        };
+
+        this.commandHandler["NotifySessionKicked"] = () => {
+            this.updateState({ state: "failed", reason: "you have been removed from the session" });
+            this.closeSocket();
+        };
     }
 
     public getState(): Readonly<SubscriberClientState> {
@@ -80,8 +103,8 @@ export class SubscriberClient {
         this.connection = null;
     }
 
-    public connect(sessionId: string) {
-        if (this.currentState.state != "new") {
+    public connect(sessionId: string, password?: string) {
+        if (this.currentState.state != "new" && this.currentState.state != "password-required") {
             throw new Error(`invalid session state`);
         }
 
@@ -91,7 +114,8 @@ export class SubscriberClient {
             this.updateState({ state: "initializing" });
             this.sendCommand("InitializeSubscribe", {
                 version: 1,
-                session_id: sessionId
+                session_id: sessionId,
+                password: password ?? null,
             });
         };
 
@@ -130,21 +154,25 @@ export class SubscriberClient {
 }
 
 export type C2SMessage = {
-    "InitializeSubscribe": { version: number, session_id: string },
+    "InitializeSubscribe": { version: number, session_id: string, password: string | null },
 }
 
 export type S2CMessage = {
     "ResponseSuccess": void,
     "ResponseError": { error: string },
     "ResponseInvalidClientState": void,
-    "ResponseInitializePublish": { session_id: string, version: number },
+    "ResponseInitializePublish": { session_id: string, version: number, expires_in: number | null },
     "ResponseSubscribeSuccess": void,
     "ResponseSessionInvalidId": void,
+    "ResponseSessionPasswordRequired": void,
+    "ResponseSessionPasswordInvalid": void,
 
     "NotifyRadarUpdate": {
         update: RadarUpdate
     },
-    "NotifySessionClosed": void
+    "NotifySessionClosed": void,
+    "NotifySessionExpired": void,
+    "NotifySessionKicked": void,
 }
 
 
@@ -1,4 +1,4 @@
-import { Box, Typography, CircularProgress, Alert } from "@mui/material";
+import { Box, Typography, CircularProgress, Alert, TextField, Button } from "@mui/material";
 import * as React from "react";
 import { SubscriberClientProvider, useSubscriberClient } from "../../../components/connection";
 import { useParams } from "react-router-dom";
@@ -39,6 +39,7 @@ export default React.memo(() => {
             <SubscriberClientProvider address={targetUrl}>
                 <ClientStateNew />
                 <ClientStateConnecting />
+                <ClientStatePasswordRequired />
                 <ClientStateFailed />
                 <ClientStateConnected />
                 <ClientStateDisconnected />
@@ -96,6 +97,45 @@ const ClientStateConnecting = React.memo(() => {
     );
 });
 
+const ClientStatePasswordRequired = React.memo(() => {
+    const client = useSubscriberClient();
+    const state = useSubscriberClientState();
+    const { sessionId } = useParams() as any;
+    const [password, setPassword] = React.useState("");
+
+    if (state.state !== "password-required") {
+        return;
+    }
+
+    return (
+        <Box
+            component={"form"}
+            sx={{ alignSelf: "center", display: "flex", flexDirection: "column", gap: 1 }}
+            onSubmit={event => {
+                event.preventDefault();
+                client.connect(sessionId, password);
+            }}
+        >
+            <Typography>This session is password protected</Typography>
+            {state.invalidPassword && (
+                <Alert severity={"error"}>
+                    Invalid password
+                </Alert>
+            )}
+            <TextField
+                type={"password"}
+                label={"Password"}
+                value={password}
+                onChange={event => setPassword(event.target.value)}
+                autoFocus
+            />
+            <Button type={"submit"} variant={"contained"} disabled={!password}>
+                Join
+            </Button>
+        </Box>
+    );
+});
+
 const ClientStateFailed = React.memo(() => {
     const state = useSubscriberClientState();
     if (state.state !== "failed") {
@@ -0,0 +1,193 @@
+use std::{
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::Mutex,
+};
+
+use imgui::{
+    Context,
+    FontSource,
+    Ui,
+};
+
+use crate::{
+    ReferenceRenderer,
+    RgbaImage,
+};
+
+/// Set this environment variable to write the rendered images as new baselines
+pub const UPDATE_BASELINES_ENV: &str = "UPDATE_GOLDEN_IMAGES";
+
+/// imgui only supports one active context at a time
+static CONTEXT_LOCK: Mutex<()> = Mutex::new(());
+
+/// Called once with the context before the first frame (e.g. to add fonts or apply a style)
+pub type FrameSetup = Box<dyn FnOnce(&mut Context)>;
+
+/// Renders imgui frames without a window or GPU
+pub struct FrameHarness {
+    pub display_size: [f32; 2],
+
+    /// Frames to render before capturing the image.
+    /// imgui needs one frame to calculate the size of auto resizing windows.
+    pub warmup_frames: usize,
+
+    pub setup: Option<FrameSetup>,
+}
+
+impl FrameHarness {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            display_size: [width as f32, height as f32],
+            warmup_frames: 1,
+            setup: None,
+        }
+    }
+
+    pub fn with_setup(mut self, setup: impl FnOnce(&mut Context) + 'static) -> Self {
+        self.setup = Some(Box::new(setup));
+        self
+    }
+
+    /// Run the render closure and capture the result of the last frame
+    pub fn render(self, mut render: impl FnMut(&Ui)) -> anyhow::Result<RgbaImage> {
+        let _context_guard = CONTEXT_LOCK
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+
+        let mut imgui = Context::create();
+        imgui.set_ini_filename(None);
+        imgui.set_log_filename(None);
+        {
+            let io = imgui.io_mut();
+            io.display_size = self.display_size;
+            io.display_framebuffer_scale = [1.0, 1.0];
+            io.delta_time = 1.0 / 60.0;
+        }
+
+        imgui
+            .fonts()
+            .add_font(&[FontSource::DefaultFontData { config: None }]);
+        if let Some(setup) = self.setup {
+            setup(&mut imgui);
+        }
+
+        let renderer = ReferenceRenderer::new(&mut imgui)?;
+        for _ in 0..self.warmup_frames {
+            let ui = imgui.frame();
+            render(ui);
+            imgui.render();
+        }
+
+        let ui = imgui.frame();
+        render(ui);
+        renderer.render(imgui.render())
+    }
+}
+
+/// Difference between two images
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageDifference {
+    pub mismatched_pixels: usize,
+    pub max_channel_difference: u8,
+}
+
+/// Compare two images. Channels which differ at most by `tolerance` are considered equal.
+pub fn compare_images(
+    actual: &RgbaImage,
+    expected: &RgbaImage,
+    tolerance: u8,
+) -> anyhow::Result<ImageDifference> {
+    if actual.width != expected.width || actual.height != expected.height {
+        anyhow::bail!(
+            "image size differs (actual {}x{}, expected {}x{})",
+            actual.width,
+            actual.height,
+            expected.width,
+            expected.height
+        );
+    }
+
+    let mut difference = ImageDifference {
+        mismatched_pixels: 0,
+        max_channel_difference: 0,
+    };
+    for (actual, expected) in actual
+        .data
+        .chunks_exact(4)
+        .zip(expected.data.chunks_exact(4))
+    {
+        let pixel_difference = actual
+            .iter()
+            .zip(expected.iter())
+            .map(|(actual, expected)| actual.abs_diff(*expected))
+            .max()
+            .unwrap_or(0);
+
+        difference.max_channel_difference = difference.max_channel_difference.max(pixel_difference);
+        if pixel_difference > tolerance {
+            difference.mismatched_pixels += 1;
+        }
+    }
+
+    Ok(difference)
+}
+
+fn actual_image_path(baseline: &Path) -> PathBuf {
+    let file_stem = baseline
+        .file_stem()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    baseline.with_file_name(format!("{}.actual.png", file_stem))
+}
+
+/// Compare the image against a PNG baseline.
+/// On mismatch the rendered image will be written next to the baseline (<name>.actual.png).
+/// Missing baselines will only be created if `UPDATE_GOLDEN_IMAGES` is set.
+pub fn assert_matches_baseline(
+    image: &RgbaImage,
+    baseline: &Path,
+    tolerance: u8,
+) -> anyhow::Result<()> {
+    if std::env::var_os(UPDATE_BASELINES_ENV).is_some() {
+        if let Some(directory) = baseline.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+
+        image.save_png(baseline)?;
+        return Ok(());
+    }
+
+    if !baseline.is_file() {
+        image.save_png(&actual_image_path(baseline))?;
+        anyhow::bail!(
+            "missing baseline {} (set {} to create it)",
+            baseline.display(),
+            UPDATE_BASELINES_ENV
+        );
+    }
+
+    let expected = RgbaImage::load_png(baseline)?;
+    let difference = compare_images(image, &expected, tolerance);
+    match difference {
+        Ok(difference) if difference.mismatched_pixels == 0 => Ok(()),
+        Ok(difference) => {
+            let actual_path = actual_image_path(baseline);
+            image.save_png(&actual_path)?;
+            anyhow::bail!(
+                "{} pixels differ from {} (max channel difference {}, actual image: {})",
+                difference.mismatched_pixels,
+                baseline.display(),
+                difference.max_channel_difference,
+                actual_path.display()
+            );
+        }
+        Err(error) => {
+            let actual_path = actual_image_path(baseline);
+            image.save_png(&actual_path)?;
+            Err(error.context(format!("compare with {}", baseline.display())))
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::{
+    fs::File,
+    io::{
+        BufReader,
+        BufWriter,
+    },
+    path::Path,
+};
+
+use anyhow::Context;
+
+/// RGBA8 image (non premultiplied alpha, rows top to bottom)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl RgbaImage {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![0u8; width as usize * height as usize * 4],
+        }
+    }
+
+    pub fn from_rgba8(width: u32, height: u32, data: Vec<u8>) -> anyhow::Result<Self> {
+        let expected_size = width as usize * height as usize * 4;
+        if data.len() != expected_size {
+            anyhow::bail!(
+                "invalid image data size (expected {} bytes, got {})",
+                expected_size,
+                data.len()
+            );
+        }
+
+        Ok(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    pub fn fill(&mut self, color: [u8; 4]) {
+        for pixel in self.data.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&color);
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let offset = (y as usize * self.width as usize + x as usize) * 4;
+        self.data[offset..offset + 4].try_into().unwrap()
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: [u8; 4]) {
+        let offset = (y as usize * self.width as usize + x as usize) * 4;
+        self.data[offset..offset + 4].copy_from_slice(&color);
+    }
+
+    pub fn load_png(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+
+        let mut decoder = png::Decoder::new(BufReader::new(file));
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder.read_info()?;
+
+        let mut buffer = vec![0u8; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        buffer.truncate(info.buffer_size());
+
+        let data = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer
+                .chunks_exact(3)
+                .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 0xFF])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .flat_map(|pixel| [pixel[0], pixel[0], pixel[0], pixel[1]])
+                .collect(),
+            png::ColorType::Grayscale => buffer
+                .iter()
+                .flat_map(|value| [*value, *value, *value, 0xFF])
+                .collect(),
+            color_type => anyhow::bail!("unsupported png color type {:?}", color_type),
+        };
+
+        Self::from_rgba8(info.width, info.height, data)
+    }
+
+    pub fn save_png(&self, path: &Path) -> anyhow::Result<()> {
+        let file = File::create(path).with_context(|| format!("create {}", path.display()))?;
+
+        let mut encoder = png::Encoder::new(BufWriter::new(file), self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.data)?;
+        writer.finish()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+//! Software rasterizer for imgui draw data.
+//! Allows testing overlay UI code against PNG baselines without a GPU.
+
+mod image;
+pub use image::*;
+
+mod raster;
+pub use raster::*;
+
+mod renderer;
+pub use renderer::*;
+
+mod harness;
+pub use harness::*;
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use crate::{
+        assert_matches_baseline,
+        compare_images,
+        rasterize_triangle,
+        FrameHarness,
+        RasterVertex,
+        RgbaImage,
+    };
+
+    fn vertex(x: f32, y: f32, color: [u8; 4]) -> RasterVertex {
+        RasterVertex {
+            position: [x, y],
+            uv: [0.0, 0.0],
+            color,
+        }
+    }
+
+    #[test]
+    fn test_shared_edges_drawn_once() {
+        let mut target = RgbaImage::new(8, 8);
+        let clip_rect = [0.0, 0.0, 8.0, 8.0];
+        let color = [0xFF, 0x00, 0x00, 0x80];
+
+        /* quad made out of two triangles (with different windings) */
+        rasterize_triangle(
+            &mut target,
+            clip_rect,
+            [
+                vertex(0.0, 0.0, color),
+                vertex(8.0, 0.0, color),
+                vertex(8.0, 8.0, color),
+            ],
+            None,
+        );
+        rasterize_triangle(
+            &mut target,
+            clip_rect,
+            [
+                vertex(0.0, 0.0, color),
+                vertex(0.0, 8.0, color),
+                vertex(8.0, 8.0, color),
+            ],
+            None,
+        );
+
+        let expected = target.pixel(0, 0);
+        assert_eq!(expected, [0x80, 0x00, 0x00, 0x80]);
+        for y in 0..8 {
+            for x in 0..8 {
+                assert_eq!(target.pixel(x, y), expected, "pixel {}x{}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_clip_rect_and_texture() {
+        let mut texture = RgbaImage::new(2, 1);
+        texture.set_pixel(0, 0, [0xFF, 0xFF, 0xFF, 0xFF]);
+        texture.set_pixel(1, 0, [0x00, 0xFF, 0x00, 0xFF]);
+
+        let mut target = RgbaImage::new(4, 4);
+        let white = [0xFF, 0xFF, 0xFF, 0xFF];
+        let mut vertices = [
+            vertex(0.0, 0.0, white),
+            vertex(4.0, 0.0, white),
+            vertex(4.0, 4.0, white),
+        ];
+        vertices[1].uv = [1.0, 0.0];
+        vertices[2].uv = [1.0, 0.0];
+
+        rasterize_triangle(&mut target, [0.0, 0.0, 4.0, 2.0], vertices, Some(&texture));
+
+        assert_eq!(target.pixel(0, 0), [0xFF, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(target.pixel(3, 0), [0x00, 0xFF, 0x00, 0xFF]);
+        assert_eq!(target.pixel(3, 1), [0x00, 0xFF, 0x00, 0xFF]);
+
+        /* outside of the clip rect */
+        assert_eq!(target.pixel(3, 3), [0x00, 0x00, 0x00, 0x00]);
+        /* outside of the triangle */
+        assert_eq!(target.pixel(0, 3), [0x00, 0x00, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_png_roundtrip() {
+        let mut image = RgbaImage::new(3, 2);
+        image.set_pixel(1, 1, [0x10, 0x20, 0x30, 0x40]);
+
+        let path = std::env::temp_dir().join(format!(
+            "reference-renderer-roundtrip-{}.png",
+            std::process::id()
+        ));
+        image.save_png(&path).unwrap();
+        let loaded = RgbaImage::load_png(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, image);
+        assert_eq!(
+            compare_images(&loaded, &image, 0)
+                .unwrap()
+                .mismatched_pixels,
+            0
+        );
+    }
+
+    #[test]
+    fn test_render_window() {
+        let image = FrameHarness::new(200, 100)
+            .render(|ui| {
+                ui.window("Reference")
+                    .position([10.0, 10.0], imgui::Condition::Always)
+                    .size([180.0, 80.0], imgui::Condition::Always)
+                    .build(|| {
+                        ui.text("Hello World");
+                        ui.button("Button");
+                    });
+            })
+            .unwrap();
+
+        assert_eq!(image.width, 200);
+        assert_eq!(image.height, 100);
+        assert_eq!(image.pixel(0, 0), [0x00, 0x00, 0x00, 0x00]);
+        assert_ne!(image.pixel(50, 50)[3], 0x00);
+    }
+
+    #[test]
+    fn test_render_empty_frame() {
+        let image = FrameHarness::new(16, 16).render(|_ui| {}).unwrap();
+        assert_eq!(image.pixel(8, 8), [0x00, 0x00, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_bomb_panel_baseline() {
+        let image = FrameHarness::new(240, 130)
+            .render(|ui| {
+                ui.window("Bomb")
+                    .position([10.0, 10.0], imgui::Condition::Always)
+                    .size([220.0, 110.0], imgui::Condition::Always)
+                    .collapsible(false)
+                    .build(|| {
+                        ui.text("Bomb planted A");
+                        ui.text("Time: 24.150");
+                        ui.text_colored([0.11, 0.79, 0.26, 1.0], "Defused in 4.320");
+                        imgui::ProgressBar::new(0.4)
+                            .size([-1.0, 0.0])
+                            .overlay_text("40%")
+                            .build(ui);
+                    });
+            })
+            .unwrap();
+
+        let baseline = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("baselines")
+            .join("bomb_panel.png");
+        assert_matches_baseline(&image, &baseline, 2).unwrap();
+    }
+}
@@ -0,0 +1,150 @@
+use crate::RgbaImage;
+
+/// A vertex in framebuffer coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RasterVertex {
+    pub position: [f32; 2],
+    pub uv: [f32; 2],
+    pub color: [u8; 4],
+}
+
+/// Clip rect in framebuffer coordinates (min x, min y, max x, max y)
+pub type ClipRect = [f32; 4];
+
+fn edge(a: [f32; 2], b: [f32; 2], p: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+/// Top left fill rule, so pixels on edges shared by two triangles will only be drawn once
+fn is_top_left(a: [f32; 2], b: [f32; 2]) -> bool {
+    (a[1] == b[1] && b[0] > a[0]) || b[1] < a[1]
+}
+
+/// Sample the texture using the nearest texel (clamped to the texture bounds)
+fn sample(texture: &RgbaImage, uv: [f32; 2]) -> [f32; 4] {
+    let x = (uv[0] * texture.width as f32).floor() as i64;
+    let y = (uv[1] * texture.height as f32).floor() as i64;
+    let x = x.clamp(0, texture.width as i64 - 1) as u32;
+    let y = y.clamp(0, texture.height as i64 - 1) as u32;
+
+    texture.pixel(x, y).map(|value| value as f32 / 255.0)
+}
+
+/// Blend a non premultiplied color onto the target (same blend state as the Vulkan renderer)
+fn blend(target: &mut [u8], color: [f32; 4]) {
+    let alpha = color[3];
+    for index in 0..3 {
+        let destination = target[index] as f32 / 255.0;
+        let value = color[index] * alpha + destination * (1.0 - alpha);
+        target[index] = (value * 255.0).round().clamp(0.0, 255.0) as u8;
+    }
+
+    let destination = target[3] as f32 / 255.0;
+    let value = alpha + destination * (1.0 - alpha);
+    target[3] = (value * 255.0).round().clamp(0.0, 255.0) as u8;
+}
+
+/// Rasterize a single triangle. Pixels are sampled at their centers.
+pub fn rasterize_triangle(
+    target: &mut RgbaImage,
+    clip_rect: ClipRect,
+    vertices: [RasterVertex; 3],
+    texture: Option<&RgbaImage>,
+) {
+    let [v0, mut v1, mut v2] = vertices;
+    let mut area = edge(v0.position, v1.position, v2.position);
+    if area == 0.0 {
+        return;
+    }
+
+    if area < 0.0 {
+        std::mem::swap(&mut v1, &mut v2);
+        area = -area;
+    }
+
+    let min_x = v0.position[0]
+        .min(v1.position[0])
+        .min(v2.position[0])
+        .max(clip_rect[0])
+        .max(0.0);
+    let min_y = v0.position[1]
+        .min(v1.position[1])
+        .min(v2.position[1])
+        .max(clip_rect[1])
+        .max(0.0);
+    let max_x = v0.position[0]
+        .max(v1.position[0])
+        .max(v2.position[0])
+        .min(clip_rect[2])
+        .min(target.width as f32);
+    let max_y = v0.position[1]
+        .max(v1.position[1])
+        .max(v2.position[1])
+        .min(clip_rect[3])
+        .min(target.height as f32);
+    if min_x >= max_x || min_y >= max_y {
+        return;
+    }
+
+    let top_left = [
+        is_top_left(v1.position, v2.position),
+        is_top_left(v2.position, v0.position),
+        is_top_left(v0.position, v1.position),
+    ];
+    let colors =
+        [v0.color, v1.color, v2.color].map(|color| color.map(|value| value as f32 / 255.0));
+
+    let target_width = target.width as usize;
+    for y in (min_y.floor() as u32)..(max_y.ceil() as u32) {
+        let pixel_y = y as f32 + 0.5;
+        if pixel_y < clip_rect[1] || pixel_y >= clip_rect[3] {
+            continue;
+        }
+
+        for x in (min_x.floor() as u32)..(max_x.ceil() as u32) {
+            let pixel_x = x as f32 + 0.5;
+            if pixel_x < clip_rect[0] || pixel_x >= clip_rect[2] {
+                continue;
+            }
+
+            let point = [pixel_x, pixel_y];
+            let weights = [
+                edge(v1.position, v2.position, point),
+                edge(v2.position, v0.position, point),
+                edge(v0.position, v1.position, point),
+            ];
+
+            let inside = weights
+                .iter()
+                .zip(top_left.iter())
+                .all(|(weight, top_left)| *weight > 0.0 || (*weight == 0.0 && *top_left));
+            if !inside {
+                continue;
+            }
+
+            let weights = weights.map(|weight| weight / area);
+            let interpolate = |values: [f32; 3]| {
+                values[0] * weights[0] + values[1] * weights[1] + values[2] * weights[2]
+            };
+
+            let mut color = [0.0f32; 4];
+            for (index, value) in color.iter_mut().enumerate() {
+                *value = interpolate([colors[0][index], colors[1][index], colors[2][index]]);
+            }
+
+            if let Some(texture) = texture {
+                let uv = [
+                    interpolate([v0.uv[0], v1.uv[0], v2.uv[0]]),
+                    interpolate([v0.uv[1], v1.uv[1], v2.uv[1]]),
+                ];
+                let texel = sample(texture, uv);
+                for index in 0..4 {
+                    color[index] *= texel[index];
+                }
+            }
+
+            let offset = (y as usize * target_width + x as usize) * 4;
+            blend(&mut target.data[offset..offset + 4], color);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use imgui::{
+    internal::RawWrapper,
+    Context,
+    DrawCmd,
+    DrawCmdParams,
+    DrawData,
+    TextureId,
+    Textures,
+};
+
+use crate::{
+    rasterize_triangle,
+    RasterVertex,
+    RgbaImage,
+};
+
+/// Texture id of the font atlas (same id as used by the Vulkan renderer)
+const FONT_TEXTURE_ID: usize = usize::MAX;
+
+/// Software implementation of the overlay renderer.
+/// Rasterizes imgui draw data into an RGBA image, intended for golden image tests.
+pub struct ReferenceRenderer {
+    font_texture: RgbaImage,
+    textures: Textures<RgbaImage>,
+
+    /// Color the target will be cleared with (the overlay clears to transparent black)
+    pub clear_color: [u8; 4],
+}
+
+impl ReferenceRenderer {
+    /// Build the font atlas of the context and create the renderer
+    pub fn new(imgui: &mut Context) -> anyhow::Result<Self> {
+        let font_texture = Self::build_font_texture(imgui)?;
+        Ok(Self {
+            font_texture,
+            textures: Textures::new(),
+
+            clear_color: [0, 0, 0, 0],
+        })
+    }
+
+    fn build_font_texture(imgui: &mut Context) -> anyhow::Result<RgbaImage> {
+        let fonts = imgui.fonts();
+        let atlas = fonts.build_rgba32_texture();
+        let texture = RgbaImage::from_rgba8(atlas.width, atlas.height, atlas.data.to_vec())?;
+        fonts.tex_id = TextureId::new(FONT_TEXTURE_ID);
+        Ok(texture)
+    }
+
+    /// Rebuild the font texture after the font atlas has been changed
+    pub fn update_fonts_texture(&mut self, imgui: &mut Context) -> anyhow::Result<()> {
+        self.font_texture = Self::build_font_texture(imgui)?;
+        Ok(())
+    }
+
+    /// User textures which can be referenced by the draw data
+    pub fn textures(&mut self) -> &mut Textures<RgbaImage> {
+        &mut self.textures
+    }
+
+    fn lookup_texture(&self, texture_id: TextureId) -> anyhow::Result<&RgbaImage> {
+        if texture_id.id() == FONT_TEXTURE_ID {
+            return Ok(&self.font_texture);
+        }
+
+        self.textures
+            .get(texture_id)
+            .ok_or_else(|| anyhow::anyhow!("unknown texture {}", texture_id.id()))
+    }
+
+    /// Render the draw data into a new image with the size of the framebuffer
+    pub fn render(&self, draw_data: &DrawData) -> anyhow::Result<RgbaImage> {
+        let width = (draw_data.display_size[0] * draw_data.framebuffer_scale[0]).round();
+        let height = (draw_data.display_size[1] * draw_data.framebuffer_scale[1]).round();
+        if width <= 0.0 || height <= 0.0 {
+            anyhow::bail!("invalid framebuffer size {}x{}", width, height);
+        }
+
+        let mut target = RgbaImage::new(width as u32, height as u32);
+        target.fill(self.clear_color);
+        self.render_into(draw_data, &mut target)?;
+        Ok(target)
+    }
+
+    pub fn render_into(&self, draw_data: &DrawData, target: &mut RgbaImage) -> anyhow::Result<()> {
+        if draw_data.draw_lists_count() == 0 {
+            /* the draw list pointer may be null, which is invalid even for an empty slice */
+            return Ok(());
+        }
+
+        let display_pos = draw_data.display_pos;
+        let scale = draw_data.framebuffer_scale;
+        let to_framebuffer = |position: [f32; 2]| -> [f32; 2] {
+            [
+                (position[0] - display_pos[0]) * scale[0],
+                (position[1] - display_pos[1]) * scale[1],
+            ]
+        };
+
+        for draw_list in draw_data.draw_lists() {
+            let vertices = draw_list.vtx_buffer();
+            let indices = draw_list.idx_buffer();
+
+            for command in draw_list.commands() {
+                match command {
+                    DrawCmd::Elements {
+                        count,
+                        cmd_params:
+                            DrawCmdParams {
+                                clip_rect,
+                                texture_id,
+                                vtx_offset,
+                                idx_offset,
+                            },
+                    } => {
+                        let clip_min = to_framebuffer([clip_rect[0], clip_rect[1]]);
+                        let clip_max = to_framebuffer([clip_rect[2], clip_rect[3]]);
+                        let clip_rect = [clip_min[0], clip_min[1], clip_max[0], clip_max[1]];
+
+                        let texture = self.lookup_texture(texture_id)?;
+                        for triangle in indices[idx_offset..idx_offset + count].chunks_exact(3) {
+                            let triangle = [triangle[0], triangle[1], triangle[2]].map(|index| {
+                                let vertex = &vertices[vtx_offset + index as usize];
+                                RasterVertex {
+                                    position: to_framebuffer(vertex.pos),
+                                    uv: vertex.uv,
+                                    color: vertex.col,
+                                }
+                            });
+
+                            rasterize_triangle(target, clip_rect, triangle, Some(texture));
+                        }
+                    }
+                    DrawCmd::ResetRenderState => {}
+                    DrawCmd::RawCallback { callback, raw_cmd } => unsafe {
+                        callback(draw_list.raw(), raw_cmd)
+                    },
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
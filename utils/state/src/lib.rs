@@ -1,3 +1,5 @@
+#![cfg_attr(feature = "shared", feature(mapped_lock_guards))]
+
 use std::{
     self,
     any::{
@@ -13,13 +15,14 @@ use std::{
     collections::{
         hash_map::Entry,
         HashMap,
+        HashSet,
     },
+    fmt::Write,
     hash::{
         DefaultHasher,
         Hash,
         Hasher,
     },
-    ops::DerefMut,
     time::{
         Duration,
         Instant,
@@ -31,6 +34,14 @@ use anyhow::{
     Context,
 };
 
+#[cfg(feature = "shared")]
+mod shared;
+#[cfg(feature = "shared")]
+pub use shared::*;
+
+mod stats;
+pub use stats::*;
+
 pub enum StateCacheType {
     /// The state will be cached and never removed
     Persistent,
@@ -70,20 +81,47 @@ fn value_update_proxy<T: State>(
     value.update(states)
 }
 
-struct InternalState {
-    value: Box<dyn Any + Send>,
-    value_update: fn(&mut Box<dyn Any + Send>, states: &StateRegistry) -> anyhow::Result<()>,
+type StateKey = (TypeId, u64);
 
-    cache_key: (TypeId, u64),
+struct InternalState<V: ?Sized = dyn Any + Send> {
+    value: Box<V>,
+    value_update: fn(&mut Box<V>, states: &StateRegistry) -> anyhow::Result<()>,
+    type_name: &'static str,
+
+    cache_key: StateKey,
     cache_type: StateCacheType,
 
     dirty: bool,
     last_access: Instant,
 }
 
+impl<V: ?Sized> InternalState<V> {
+    /// Mark the state as dirty.
+    /// Returns true if the state expired.
+    fn mark_dirty(&mut self, now: Instant) -> bool {
+        if !self.dirty {
+            /* State has been accessed. */
+            self.last_access = now;
+            self.dirty = true;
+        }
+
+        match self.cache_type {
+            StateCacheType::Persistent => false,
+            StateCacheType::Volatile => true,
+            StateCacheType::Timed(timeout) => self.last_access.elapsed() > timeout,
+        }
+    }
+}
+
 struct StateAllocator {
     index_lookup: HashMap<(TypeId, u64), usize>,
     free_list: Vec<usize>,
+
+    capacity: usize,
+    /// Failed allocations since the last capacity change
+    allocation_failures: usize,
+    /// Total failed allocations
+    total_allocation_failures: usize,
 }
 
 impl StateAllocator {
@@ -96,19 +134,70 @@ impl StateAllocator {
         Self {
             index_lookup: Default::default(),
             free_list,
+
+            capacity,
+            allocation_failures: 0,
+            total_allocation_failures: 0,
         }
     }
 
+    /// Increase the capacity to the new capacity.
+    /// The caller must provide storage for the new entries.
+    fn grow(&mut self, new_capacity: usize) {
+        for index in (self.capacity..new_capacity).rev() {
+            self.free_list.push(index);
+        }
+
+        self.capacity = self.capacity.max(new_capacity);
+        self.allocation_failures = 0;
+    }
+
+    fn state_key<T: State>(params: &T::Parameter) -> StateKey {
+        let mut hasher = DefaultHasher::new();
+        params.hash(&mut hasher);
+        let params_hash = hasher.finish();
+
+        (TypeId::of::<T>(), params_hash)
+    }
+
     fn calculate_state_index<T: State>(
         &mut self,
         params: &T::Parameter,
         create_if_not_exists: bool,
     ) -> Option<((TypeId, u64), usize)> {
-        let mut hasher = DefaultHasher::new();
-        params.hash(&mut hasher);
-        let params_hash = hasher.finish();
+        let cache_key = Self::state_key::<T>(params);
+        let index = self.entry_index(cache_key, create_if_not_exists)?;
+        Some((cache_key, index))
+    }
+
+    fn allocate_state<T: State>(
+        &mut self,
+        params: &T::Parameter,
+    ) -> Result<(StateKey, usize), StateCapacityExceeded> {
+        let cache_key = Self::state_key::<T>(params);
+        let index = self.allocate_entry(cache_key, any::type_name::<T>())?;
+        Ok((cache_key, index))
+    }
+
+    fn allocate_entry(
+        &mut self,
+        cache_key: StateKey,
+        type_name: &'static str,
+    ) -> Result<usize, StateCapacityExceeded> {
+        match self.entry_index(cache_key, true) {
+            Some(index) => Ok(index),
+            None => {
+                self.allocation_failures += 1;
+                self.total_allocation_failures += 1;
+                Err(StateCapacityExceeded {
+                    capacity: self.capacity,
+                    type_name,
+                })
+            }
+        }
+    }
 
-        let cache_key = (TypeId::of::<T>(), params_hash);
+    fn entry_index(&mut self, cache_key: StateKey, create_if_not_exists: bool) -> Option<usize> {
         let index = match self.index_lookup.entry(cache_key) {
             Entry::Occupied(entry) => *entry.get(),
             Entry::Vacant(entry) => {
@@ -122,7 +211,7 @@ impl StateAllocator {
             }
         };
 
-        Some((cache_key, index))
+        Some(index)
     }
 
     fn free_entry(&mut self, cache_key: &(TypeId, u64)) {
@@ -134,6 +223,111 @@ impl StateAllocator {
     }
 }
 
+/// Dependencies between states which have been recorded while creating or updating a state.
+#[derive(Default)]
+struct StateDependencies {
+    /// States which have been created or updated using the key state
+    dependents: HashMap<StateKey, HashSet<StateKey>>,
+
+    /// States which have been used to create or update the key state
+    dependencies: HashMap<StateKey, HashSet<StateKey>>,
+}
+
+impl StateDependencies {
+    fn add(&mut self, dependent: StateKey, dependency: StateKey) {
+        if dependent == dependency {
+            return;
+        }
+
+        self.dependents
+            .entry(dependency)
+            .or_default()
+            .insert(dependent);
+
+        self.dependencies
+            .entry(dependent)
+            .or_default()
+            .insert(dependency);
+    }
+
+    fn dependents_of(&self, state: &StateKey) -> Vec<StateKey> {
+        self.dependents
+            .get(state)
+            .map(|dependents| dependents.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Remove the edges of the given states and all their (transitive) dependents.
+    /// Returns all states which need to be removed.
+    fn remove_with_dependents(&mut self, mut pending: Vec<StateKey>) -> Vec<StateKey> {
+        let mut result = Vec::with_capacity(pending.len());
+        while let Some(state) = pending.pop() {
+            pending.extend(self.dependents_of(&state));
+            self.remove_state(&state);
+            result.push(state);
+        }
+
+        result
+    }
+
+    /// Remove all edges of the target state
+    fn remove_state(&mut self, state: &StateKey) {
+        if let Some(dependencies) = self.dependencies.remove(state) {
+            for dependency in dependencies {
+                if let Entry::Occupied(mut entry) = self.dependents.entry(dependency) {
+                    entry.get_mut().remove(state);
+                    if entry.get().is_empty() {
+                        entry.remove();
+                    }
+                }
+            }
+        }
+
+        if let Some(dependents) = self.dependents.remove(state) {
+            for dependent in dependents {
+                if let Some(dependencies) = self.dependencies.get_mut(&dependent) {
+                    dependencies.remove(state);
+                }
+            }
+        }
+    }
+}
+
+fn format_dependency_graph(
+    nodes: &[(StateKey, &'static str)],
+    dependencies: &StateDependencies,
+) -> String {
+    let mut node_names = HashMap::new();
+    let mut output = String::new();
+    let _ = writeln!(&mut output, "digraph states {{");
+    for (index, (cache_key, type_name)) in nodes.iter().enumerate() {
+        let node_name = format!("state{}", index);
+        let _ = writeln!(
+            &mut output,
+            "  {} [label=\"{}\\n{:016X}\"];",
+            node_name, type_name, cache_key.1
+        );
+        node_names.insert(*cache_key, node_name);
+    }
+
+    for (dependent, dependencies) in dependencies.dependencies.iter() {
+        let Some(dependent) = node_names.get(dependent) else {
+            continue;
+        };
+
+        for dependency in dependencies {
+            let Some(dependency) = node_names.get(dependency) else {
+                continue;
+            };
+
+            let _ = writeln!(&mut output, "  {} -> {};", dependent, dependency);
+        }
+    }
+
+    let _ = writeln!(&mut output, "}}");
+    output
+}
+
 fn transpose_ref_opt<T>(x: Ref<'_, Option<T>>) -> Option<Ref<'_, T>> {
     if x.is_none() {
         None
@@ -150,9 +344,26 @@ fn transpose_ref_mut_opt<T>(x: RefMut<'_, Option<T>>) -> Option<RefMut<'_, T>> {
     }
 }
 
+/// Registry for all states.
+///
+/// The registry itself is single threaded. States which should be resolved on
+/// multiple threads can be stored within a `SharedStateRegistry` (feature "shared").
 pub struct StateRegistry {
     allocator: RefCell<StateAllocator>,
     states: Vec<RefCell<Option<InternalState>>>,
+
+    /// Registry used by `resolve_shared`
+    #[cfg(feature = "shared")]
+    shared_states: Option<std::sync::Arc<SharedStateRegistry>>,
+
+    /// States which are currently being created
+    creation_stack: RefCell<Vec<StateKey>>,
+    dependencies: RefCell<StateDependencies>,
+
+    invalidation_hooks: Vec<Box<dyn FnMut() + Send>>,
+
+    capacity_policy: StateCapacityPolicy,
+    instrumentation: RefCell<Option<StateInstrumentation>>,
 }
 
 impl StateRegistry {
@@ -162,52 +373,212 @@ impl StateRegistry {
         Self {
             allocator: RefCell::new(StateAllocator::new(capacity)),
             states,
+
+            #[cfg(feature = "shared")]
+            shared_states: None,
+
+            creation_stack: Default::default(),
+            dependencies: Default::default(),
+
+            invalidation_hooks: Default::default(),
+
+            capacity_policy: Default::default(),
+            instrumentation: Default::default(),
         }
     }
 
+    /// Set the policy for when all state entries are in use.
+    pub fn set_capacity_policy(&mut self, policy: StateCapacityPolicy) {
+        self.capacity_policy = policy;
+    }
+
+    /// Enable or disable collecting per state statistics.
+    /// Enabling the instrumentation resets all collected statistics.
+    pub fn set_instrumentation_enabled(&self, enabled: bool) {
+        *self.instrumentation.borrow_mut() = enabled.then(Default::default);
+    }
+
+    pub fn instrumentation_enabled(&self) -> bool {
+        self.instrumentation.borrow().is_some()
+    }
+
+    fn instrument(&self, callback: impl FnOnce(&mut StateInstrumentation)) {
+        if let Some(instrumentation) = self.instrumentation.borrow_mut().as_mut() {
+            callback(instrumentation);
+        }
+    }
+
+    /// Create a snapshot of the current registry statistics
+    pub fn stats(&self) -> StateRegistryStats {
+        let allocator = self.allocator.borrow();
+        let mut states = Vec::with_capacity(allocator.index_lookup.len());
+        for state in self.states.iter() {
+            let Ok(state) = state.try_borrow() else {
+                /* state is currently being created or updated */
+                continue;
+            };
+
+            if let Some(state) = state.as_ref() {
+                states.push((state.cache_key.0, state.type_name));
+            }
+        }
+
+        build_stats(
+            allocator.capacity,
+            allocator.total_allocation_failures,
+            states.into_iter(),
+            self.instrumentation.borrow().as_ref(),
+        )
+    }
+
+    fn apply_capacity_policy(&mut self) {
+        let StateCapacityPolicy::Grow { max_capacity } = self.capacity_policy else {
+            return;
+        };
+
+        let allocator = self.allocator.get_mut();
+        if allocator.allocation_failures == 0 || allocator.capacity >= max_capacity {
+            return;
+        }
+
+        let new_capacity = (allocator.capacity * 2).clamp(1, max_capacity);
+        allocator.grow(new_capacity);
+        self.states.resize_with(new_capacity, Default::default);
+    }
+
+    /// Register a callback which will be invoked every time the states get invalidated.
+    /// This can be used to invalidate caches which are not managed by the registry.
+    pub fn add_invalidation_hook(&mut self, hook: impl FnMut() + Send + 'static) {
+        self.invalidation_hooks.push(Box::new(hook));
+    }
+
     pub fn invalidate_states(&mut self) {
-        /* As we're mutable there should be no more references to the underlying state */
-        let mut allocator = self.allocator.borrow_mut();
+        for hook in self.invalidation_hooks.iter_mut() {
+            hook();
+        }
 
+        /* As we're mutable there should be no more references to the underlying state */
         let now = Instant::now();
+        let mut expired_states = Vec::new();
         for state in self.states.iter_mut() {
-            let mut state_ref = state.borrow_mut();
-            let state = if let Some(state) = state_ref.deref_mut() {
-                state
-            } else {
-                continue;
-            };
+            if let Some(state) = state.get_mut() {
+                if state.mark_dirty(now) {
+                    expired_states.push(state.cache_key);
+                }
+            }
+        }
+
+        self.expire_states(expired_states);
+        self.apply_capacity_policy();
+    }
+
+    /// Remove the given states and all states which have been created using them.
+    fn remove_states(&mut self, states: Vec<StateKey>) {
+        let states = self.dependencies.get_mut().remove_with_dependents(states);
+        self.free_states(states);
+    }
+
+    /// Remove expired states without removing their dependents.
+    /// The dependents will be marked as dirty and resolve the expired states again when updating.
+    fn expire_states(&mut self, states: Vec<StateKey>) {
+        let allocator = self.allocator.get_mut();
+        let dependencies = self.dependencies.get_mut();
+        for cache_key in states.iter() {
+            for dependent in dependencies.dependents_of(cache_key) {
+                let Some(index) = allocator.index_lookup.get(&dependent) else {
+                    continue;
+                };
+
+                if let Some(state) = self.states[*index].get_mut() {
+                    state.dirty = true;
+                }
+            }
+
+            dependencies.remove_state(cache_key);
+        }
+
+        self.free_states(states);
+    }
 
-            if !state.dirty {
-                /* State has been accessed. */
-                state.last_access = now;
-                state.dirty = true;
+    fn free_states(&mut self, states: Vec<StateKey>) {
+        let allocator = self.allocator.get_mut();
+        let instrumentation = self.instrumentation.get_mut();
+        for cache_key in states {
+            if let Some(index) = allocator.index_lookup.get(&cache_key).cloned() {
+                let state = self.states[index].get_mut().take();
+                if let Some((state, instrumentation)) = state.zip(instrumentation.as_mut()) {
+                    instrumentation.record_eviction(&state);
+                }
+
+                allocator.free_entry(&cache_key);
             }
+        }
+    }
+
+    /// Mark a state as dirty so it will be updated on the next access.
+    /// All states which have been created using this state will be removed.
+    pub fn invalidate<T: State>(&mut self, params: T::Parameter) {
+        let Some((cache_key, index)) = self
+            .allocator
+            .get_mut()
+            .calculate_state_index::<T>(&params, false)
+        else {
+            return;
+        };
+
+        if let Some(state) = self.states[index].get_mut() {
+            state.dirty = true;
+        }
+
+        let dependents = self.dependencies.get_mut().dependents_of(&cache_key);
+        self.remove_states(dependents);
+    }
+
+    /// Remove a state and all states which have been created using this state.
+    /// Returns true if the state has been present.
+    pub fn remove<T: State>(&mut self, params: T::Parameter) -> bool {
+        let Some((cache_key, index)) = self
+            .allocator
+            .get_mut()
+            .calculate_state_index::<T>(&params, false)
+        else {
+            return false;
+        };
 
-            let state_expired = match state.cache_type {
-                StateCacheType::Persistent => false,
-                StateCacheType::Volatile => true,
-                StateCacheType::Timed(timeout) => state.last_access.elapsed() > timeout,
+        let present = self.states[index].get_mut().is_some();
+        self.remove_states(vec![cache_key]);
+        present
+    }
+
+    /// Dump the dependency graph of all current states in the DOT format.
+    /// Edges point from a state to the states it has been created from.
+    pub fn dependency_graph_dot(&self) -> String {
+        let mut nodes = Vec::new();
+        for state in self.states.iter() {
+            let Ok(state) = state.try_borrow() else {
+                continue;
             };
-            if state_expired {
-                allocator.free_entry(&state.cache_key);
-                *state_ref = None;
+
+            if let Some(state) = state.as_ref() {
+                nodes.push((state.cache_key, state.type_name));
             }
         }
+
+        format_dependency_graph(&nodes, &self.dependencies.borrow())
     }
 
-    /// Preset a specific state
+    /// Preset a specific state.
+    /// States which have been created using the previous value will be removed.
     pub fn set<T: State>(&mut self, value: T, params: T::Parameter) -> anyhow::Result<()> {
-        let (cache_key, index) = self
-            .allocator
-            .borrow_mut()
-            .calculate_state_index::<T>(&params, true)
-            .context("state capacity exceeded")?;
+        let (cache_key, index) = self.allocator.get_mut().allocate_state::<T>(&params)?;
 
-        let mut state_ref = self.states[index].borrow_mut();
-        *state_ref = Some(InternalState {
+        let dependents = self.dependencies.get_mut().dependents_of(&cache_key);
+        self.remove_states(dependents);
+
+        *self.states[index].get_mut() = Some(InternalState {
             value: Box::new(value),
             value_update: value_update_proxy::<T>,
+            type_name: any::type_name::<T>(),
 
             cache_key,
             cache_type: T::cache_type(),
@@ -258,55 +629,96 @@ impl StateRegistry {
 
     fn initialize_value<T: State>(
         &self,
-        cache_key: (TypeId, u64),
-        value: &mut RefMut<'_, Option<InternalState>>,
-        params: T::Parameter,
+        cache_key: StateKey,
+        value: &mut Option<InternalState>,
+        params: &mut Option<T::Parameter>,
     ) -> anyhow::Result<()> {
-        let value = match value.as_mut() {
+        let type_name = any::type_name::<T>();
+        let instrumented = self.instrumentation_enabled();
+        if instrumented {
+            let hit = value.as_ref().is_some_and(|value| !value.dirty);
+            self.instrument(|stats| stats.record_access(cache_key.0, type_name, hit));
+        }
+
+        let value = match value {
             Some(value) => value,
             None => {
-                /* create a new value */
-                let state = Box::new(
-                    T::create(self, params)
-                        .with_context(|| format!("create {}", any::type_name::<T>()))?,
-                );
-                **value = Some(InternalState {
+                let params = params.take().context("state parameters already consumed")?;
+
+                /* create a new value and track which states are used to create it */
+                let create_start = instrumented.then(Instant::now);
+                self.creation_stack.borrow_mut().push(cache_key);
+                let state = T::create(self, params);
+                self.creation_stack.borrow_mut().pop();
+
+                if let Some(create_start) = create_start {
+                    let duration = create_start.elapsed();
+                    self.instrument(|stats| stats.record_create(cache_key.0, type_name, duration));
+                }
+
+                let state = match state {
+                    Ok(state) => Box::new(state),
+                    Err(err) => {
+                        self.forget_dependencies(&cache_key);
+                        return Err(err.context(format!("create {}", type_name)));
+                    }
+                };
+
+                value.insert(InternalState {
                     value: state,
                     value_update: value_update_proxy::<T>,
+                    type_name,
 
                     cache_key,
                     cache_type: T::cache_type(),
 
                     dirty: false,
                     last_access: Instant::now(),
-                });
-
-                value.as_mut().unwrap()
+                })
             }
         };
 
         if value.dirty {
-            (value.value_update)(&mut value.value, self)
-                .with_context(|| format!("update {}", any::type_name::<T>()))?;
+            /* states resolved while updating are dependencies as well */
+            let update_start = instrumented.then(Instant::now);
+            self.creation_stack.borrow_mut().push(cache_key);
+            let result = (value.value_update)(&mut value.value, self);
+            self.creation_stack.borrow_mut().pop();
+            if let Some(update_start) = update_start {
+                let duration = update_start.elapsed();
+                self.instrument(|stats| stats.record_update(cache_key.0, type_name, duration));
+            }
+
+            result.with_context(|| format!("update {}", type_name))?;
             value.dirty = false;
         }
 
         Ok(())
     }
 
+    /// Record the target state as dependency of the state which is currently being created or updated.
+    fn record_dependency(&self, cache_key: StateKey) {
+        let Some(dependent) = self.creation_stack.borrow().last().cloned() else {
+            return;
+        };
+
+        self.dependencies.borrow_mut().add(dependent, cache_key);
+    }
+
+    fn forget_dependencies(&self, cache_key: &StateKey) {
+        self.dependencies.borrow_mut().remove_state(cache_key);
+    }
+
     pub fn resolve_mut<T: State>(&self, params: T::Parameter) -> anyhow::Result<RefMut<'_, T>> {
-        let (cache_key, index) = self
-            .allocator
-            .borrow_mut()
-            .calculate_state_index::<T>(&params, true)
-            .context("state capacity exceeded")?;
+        let (cache_key, index) = self.allocator.borrow_mut().allocate_state::<T>(&params)?;
 
         let mut value = self.states[index]
             .try_borrow_mut()
             .context("value already borrowed")?;
 
-        self.initialize_value::<T>(cache_key, &mut value, params)?;
+        self.initialize_value::<T>(cache_key, &mut value, &mut Some(params))?;
         let value = transpose_ref_mut_opt(value).context("expected a valid value")?;
+        self.record_dependency(cache_key);
 
         Ok(RefMut::map(value, |value| {
             value.value.downcast_mut::<T>().expect("to be of type T")
@@ -314,14 +726,10 @@ impl StateRegistry {
     }
 
     pub fn resolve<T: State>(&self, params: T::Parameter) -> anyhow::Result<Ref<'_, T>> {
-        let (cache_key, index) = self
-            .allocator
-            .borrow_mut()
-            .calculate_state_index::<T>(&params, true)
-            .context("state capacity exceeded")?;
+        let (cache_key, index) = self.allocator.borrow_mut().allocate_state::<T>(&params)?;
 
         if let Ok(mut value) = self.states[index].try_borrow_mut() {
-            self.initialize_value::<T>(cache_key, &mut value, params)?;
+            self.initialize_value::<T>(cache_key, &mut value, &mut Some(params))?;
         } else {
             /* We already borrowed that state, hence it must be initialized & not dirty */
         }
@@ -337,15 +745,26 @@ impl StateRegistry {
             let value = value.as_ref().expect("to be present");
             value.value.downcast_ref::<T>().expect("to be of type T")
         });
+        self.record_dependency(cache_key);
         Ok(value)
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+    };
+
     use super::{
         State,
         StateCacheType,
+        StateCapacityExceeded,
+        StateCapacityPolicy,
         StateRegistry,
     };
 
@@ -410,6 +829,184 @@ mod test {
         assert!(states.resolve::<StateC>(0).is_ok());
     }
 
+    #[test]
+    fn test_invalidation_hook() {
+        let invalidations = Arc::new(AtomicUsize::new(0));
+
+        let mut states = StateRegistry::new(2);
+        states.add_invalidation_hook({
+            let invalidations = invalidations.clone();
+            move || {
+                invalidations.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+
+        states.invalidate_states();
+        states.invalidate_states();
+        assert_eq!(invalidations.load(Ordering::Relaxed), 2);
+    }
+
+    struct StateD;
+    impl State for StateD {
+        type Parameter = ();
+
+        fn create(states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            states.resolve::<StateB>(())?;
+            Ok(Self)
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+    }
+
+    struct StateE;
+    impl State for StateE {
+        type Parameter = ();
+
+        fn create(states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            states.resolve::<StateD>(())?;
+            Ok(Self)
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+    }
+
+    #[test]
+    fn test_dependency_cascade() {
+        let mut states = StateRegistry::new(4);
+        assert!(states.resolve::<StateE>(()).is_ok());
+        assert!(states.get::<StateD>(()).is_some());
+
+        assert!(states.remove::<StateB>(()));
+        assert!(states.get::<StateB>(()).is_none());
+        assert!(states.get::<StateD>(()).is_none());
+        assert!(states.get::<StateE>(()).is_none());
+
+        assert!(states.resolve::<StateE>(()).is_ok());
+        states.set(StateB, ()).unwrap();
+        assert!(states.get::<StateB>(()).is_some());
+        assert!(states.get::<StateE>(()).is_none());
+
+        assert!(states.resolve::<StateE>(()).is_ok());
+        states.invalidate::<StateD>(());
+        assert!(states.get::<StateD>(()).is_some());
+        assert!(states.get::<StateE>(()).is_none());
+    }
+
+    /// Resolves its dependency only while updating
+    struct StateF;
+    impl State for StateF {
+        type Parameter = ();
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self)
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+
+        fn update(&mut self, states: &StateRegistry) -> anyhow::Result<()> {
+            states.resolve::<StateB>(())?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_dependency_update_cascade() {
+        let mut states = StateRegistry::new(4);
+        assert!(states.resolve::<StateF>(()).is_ok());
+        assert!(states.get::<StateB>(()).is_none());
+
+        /* the next resolve updates StateF which resolves StateB */
+        states.invalidate_states();
+        assert!(states.resolve::<StateF>(()).is_ok());
+        assert!(states.get::<StateB>(()).is_some());
+
+        states.invalidate::<StateB>(());
+        assert!(states.get::<StateB>(()).is_some());
+        assert!(states.get::<StateF>(()).is_none());
+
+        assert!(states.resolve::<StateF>(()).is_ok());
+        states.invalidate_states();
+        assert!(states.resolve::<StateF>(()).is_ok());
+        assert!(states.remove::<StateB>(()));
+        assert!(states.get::<StateF>(()).is_none());
+    }
+
+    #[test]
+    fn test_dependency_volatile_expire() {
+        let mut states = StateRegistry::new(4);
+        assert!(states.resolve::<StateC>(0).is_ok());
+        states.invalidate_states();
+
+        /* expiring the volatile StateA does not remove states created using it */
+        assert!(states.get::<StateA>(()).is_none());
+        assert!(states.get::<StateB>(()).is_some());
+        assert!(states.get::<StateC>(0).is_some());
+
+        /* the remaining dependencies are still tracked */
+        assert!(states.remove::<StateB>(()));
+        assert!(states.get::<StateC>(0).is_none());
+    }
+
+    /// Persistent state which resolves the volatile StateA while updating
+    struct StateG {
+        updates: usize,
+    }
+
+    impl State for StateG {
+        type Parameter = ();
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self { updates: 0 })
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+
+        fn update(&mut self, states: &StateRegistry) -> anyhow::Result<()> {
+            states.resolve::<StateA>(())?;
+            self.updates += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_dependency_volatile_update() {
+        let mut states = StateRegistry::new(4);
+        assert!(states.resolve::<StateG>(()).is_ok());
+
+        for frame in 1..=5 {
+            states.invalidate_states();
+            assert!(states.get::<StateA>(()).is_none());
+            assert!(states.get::<StateG>(()).is_some());
+
+            /* the persistent state will be updated instead of being recreated */
+            assert_eq!(states.resolve::<StateG>(()).unwrap().updates, frame);
+            assert!(states.get::<StateA>(()).is_some());
+        }
+
+        /* explicit invalidations of the dependency still cascade */
+        states.invalidate::<StateA>(());
+        assert!(states.get::<StateG>(()).is_none());
+    }
+
+    #[test]
+    fn test_dependency_graph_dot() {
+        let states = StateRegistry::new(4);
+        assert!(states.resolve::<StateD>(()).is_ok());
+
+        let graph = states.dependency_graph_dot();
+        assert!(graph.starts_with("digraph states {"));
+        assert!(graph.contains("StateD"));
+        assert_eq!(graph.matches(" -> ").count(), 1);
+    }
+
     #[test]
     fn test_expire() {
         let mut states = StateRegistry::new(2);
@@ -423,4 +1020,49 @@ mod test {
         assert!(states.get::<StateA>(()).is_some());
         assert!(states.get::<StateB>(()).is_some());
     }
+
+    #[test]
+    fn test_stats() {
+        let mut states = StateRegistry::new(4);
+        states.set_instrumentation_enabled(true);
+
+        assert!(states.resolve::<StateD>(()).is_ok());
+        assert!(states.resolve::<StateD>(()).is_ok());
+        assert!(states.resolve::<StateA>(()).is_ok());
+        states.invalidate_states();
+
+        let stats = states.stats();
+        assert_eq!(stats.capacity, 4);
+        assert_eq!(stats.used, 2);
+        assert_eq!(stats.evictions.volatile, 1);
+
+        let state_d = stats
+            .states
+            .iter()
+            .find(|stats| stats.type_name.ends_with("StateD"))
+            .unwrap();
+        assert_eq!(state_d.entries, 1);
+        assert_eq!(state_d.create_count, 1);
+        assert_eq!(state_d.hits, 1);
+        assert_eq!(state_d.misses, 1);
+    }
+
+    #[test]
+    fn test_capacity_policy() {
+        let mut states = StateRegistry::new(1);
+        assert!(states.resolve::<StateB>(()).is_ok());
+
+        let error = states.resolve::<StateD>(()).err().unwrap();
+        assert!(error.downcast_ref::<StateCapacityExceeded>().is_some());
+        assert_eq!(states.stats().capacity_exceeded, 1);
+
+        /* fixed capacity does not grow */
+        states.invalidate_states();
+        assert!(states.resolve::<StateD>(()).is_err());
+
+        states.set_capacity_policy(StateCapacityPolicy::Grow { max_capacity: 2 });
+        states.invalidate_states();
+        assert!(states.resolve::<StateD>(()).is_ok());
+        assert_eq!(states.stats().capacity, 2);
+    }
 }
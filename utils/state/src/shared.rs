@@ -0,0 +1,747 @@
+use std::{
+    any::{
+        self,
+        Any,
+    },
+    cell::Ref,
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    fmt,
+    ops::Deref,
+    sync::{
+        Arc,
+        MappedRwLockReadGuard,
+        Mutex,
+        MutexGuard,
+        PoisonError,
+        RwLock,
+        RwLockReadGuard,
+        TryLockError,
+    },
+    thread::{
+        self,
+        ThreadId,
+    },
+    time::Instant,
+};
+
+use anyhow::Context;
+
+use crate::{
+    build_stats,
+    InternalState,
+    State,
+    StateAllocator,
+    StateKey,
+    StateRegistry,
+    StateRegistryStats,
+};
+
+type SharedValue = dyn Any + Send + Sync;
+
+fn shared_value_update_proxy<T: State + Sync>(
+    value: &mut Box<SharedValue>,
+    states: &StateRegistry,
+) -> anyhow::Result<()> {
+    let value = value.downcast_mut::<T>().expect("to be of type T");
+    value.update(states)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    /* bookkeeping stays consistent even if a state panicked */
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Error returned when resolving a shared state would deadlock.
+/// This happens if the state is already locked by the resolving thread (re-entrant resolve)
+/// or the thread holding the state lock is (transitively) waiting for the resolving thread.
+#[derive(Debug, Clone)]
+pub struct StateLockCycle {
+    pub type_name: &'static str,
+}
+
+impl fmt::Display for StateLockCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "resolving {} would deadlock (re-entrant resolve or lock cycle)",
+            self.type_name
+        )
+    }
+}
+
+impl std::error::Error for StateLockCycle {}
+
+/// Threads holding or waiting for entry locks
+#[derive(Default)]
+struct LockGraph {
+    /// Threads currently holding the lock of an entry
+    holders: HashMap<usize, Vec<ThreadId>>,
+
+    /// Entry a thread is currently waiting for
+    waiting: HashMap<ThreadId, usize>,
+}
+
+impl LockGraph {
+    /// Check if waiting for the entry would (transitively) wait for the given thread
+    fn would_deadlock(&self, thread: ThreadId, index: usize) -> bool {
+        let mut pending = vec![index];
+        let mut visited = HashSet::new();
+        while let Some(index) = pending.pop() {
+            if !visited.insert(index) {
+                continue;
+            }
+
+            for holder in self.holders.get(&index).into_iter().flatten() {
+                if *holder == thread {
+                    return true;
+                }
+
+                if let Some(index) = self.waiting.get(holder) {
+                    pending.push(*index);
+                }
+            }
+        }
+
+        false
+    }
+}
+
+/// Registration of a thread holding an entry lock.
+/// The registration will be removed when dropped.
+struct EntryHolder<'a> {
+    lock_graph: &'a Mutex<LockGraph>,
+    index: usize,
+    thread: ThreadId,
+}
+
+impl Drop for EntryHolder<'_> {
+    fn drop(&mut self) {
+        let mut lock_graph = lock(self.lock_graph);
+        if let Some(holders) = lock_graph.holders.get_mut(&self.index) {
+            if let Some(position) = holders.iter().position(|holder| *holder == self.thread) {
+                holders.swap_remove(position);
+            }
+
+            if holders.is_empty() {
+                lock_graph.holders.remove(&self.index);
+            }
+        }
+    }
+}
+
+/// Reference to a state within a `SharedStateRegistry`
+pub struct SharedStateRef<'a, T> {
+    /* the value must be released before the holder registration */
+    value: MappedRwLockReadGuard<'a, T>,
+    _holder: EntryHolder<'a>,
+}
+
+impl<T> Deref for SharedStateRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+/// Reference to a state resolved by `StateRegistry::resolve_shared`
+pub enum StateRef<'a, T> {
+    Local(Ref<'a, T>),
+    Shared(SharedStateRef<'a, T>),
+}
+
+impl<T> Deref for StateRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Local(value) => value,
+            Self::Shared(value) => value,
+        }
+    }
+}
+
+impl StateRegistry {
+    /// Resolve states using `resolve_shared` from the given shared registry
+    /// instead of creating a local copy within this registry.
+    pub fn set_shared_registry(&mut self, shared_states: Arc<SharedStateRegistry>) {
+        self.shared_states = Some(shared_states);
+    }
+
+    /// Resolve a state which can be shared between multiple registries (e.g. worker registries).
+    /// The state will be resolved locally if no shared registry has been set.
+    ///
+    /// Note: Dependencies on shared states are not tracked.
+    pub fn resolve_shared<T: State + Sync>(
+        &self,
+        params: T::Parameter,
+    ) -> anyhow::Result<StateRef<'_, T>> {
+        match &self.shared_states {
+            Some(shared_states) => Ok(StateRef::Shared(shared_states.resolve::<T>(self, params)?)),
+            None => Ok(StateRef::Local(self.resolve::<T>(params)?)),
+        }
+    }
+}
+
+type SharedEntry = RwLock<Option<InternalState<SharedValue>>>;
+type SharedEntryReadGuard<'a> = RwLockReadGuard<'a, Option<InternalState<SharedValue>>>;
+
+/// Thread safe storage for states which should be resolved on multiple threads
+/// (e.g. one state per player).
+///
+/// States will be created and updated using the `StateRegistry` of the resolving thread,
+/// therefore their dependencies are resolved from that registry.
+/// Every entry is locked individually, so independent states can be resolved in parallel.
+///
+/// Resolving a state which is already locked by the current thread, or would wait
+/// for a thread which is waiting for the current thread, fails with `StateLockCycle`.
+///
+/// Note: Dependencies are not tracked across registries.
+/// States expire according to their cache type when calling `invalidate_states`.
+pub struct SharedStateRegistry {
+    allocator: Mutex<StateAllocator>,
+    states: Vec<SharedEntry>,
+    lock_graph: Mutex<LockGraph>,
+}
+
+impl SharedStateRegistry {
+    pub fn new(capacity: usize) -> Self {
+        let mut states = Vec::with_capacity(capacity);
+        states.resize_with(capacity, Default::default);
+
+        Self {
+            allocator: Mutex::new(StateAllocator::new(capacity)),
+            states,
+            lock_graph: Default::default(),
+        }
+    }
+
+    /// Create a snapshot of the current registry statistics.
+    /// States which are currently locked for writing will not be counted.
+    pub fn stats(&self) -> StateRegistryStats {
+        let mut states = Vec::new();
+        for state in self.states.iter() {
+            let Ok(state) = state.try_read() else {
+                continue;
+            };
+
+            if let Some(state) = state.as_ref() {
+                states.push((state.cache_key.0, state.type_name));
+            }
+        }
+
+        let allocator = lock(&self.allocator);
+        build_stats(
+            allocator.capacity,
+            allocator.total_allocation_failures,
+            states.into_iter(),
+            None,
+        )
+    }
+
+    /// Mark all states as dirty and remove expired states.
+    /// States which are currently in use will be skipped.
+    pub fn invalidate_states(&self) {
+        let now = Instant::now();
+        let mut allocator = lock(&self.allocator);
+
+        let mut expired_states = Vec::new();
+        for (cache_key, index) in allocator.index_lookup.iter() {
+            let mut state = match self.states[*index].try_write() {
+                Ok(state) => state,
+                Err(TryLockError::Poisoned(error)) => error.into_inner(),
+                Err(TryLockError::WouldBlock) => continue,
+            };
+
+            /* entries of states which failed to create will be released as well */
+            let expired = state.as_mut().is_none_or(|state| state.mark_dirty(now));
+            if expired {
+                *state = None;
+                expired_states.push(*cache_key);
+            }
+        }
+
+        for cache_key in expired_states {
+            allocator.free_entry(&cache_key);
+        }
+    }
+
+    /// Preset a specific state.
+    pub fn set<T: State + Sync>(&self, value: T, params: T::Parameter) -> anyhow::Result<()> {
+        let type_name = any::type_name::<T>();
+        let (cache_key, index) = lock(&self.allocator).allocate_state::<T>(&params)?;
+
+        let (mut state, _holder) = self.lock_entry(index, type_name, |entry| {
+            entry.write().unwrap_or_else(PoisonError::into_inner)
+        })?;
+        *state = Some(InternalState {
+            value: Box::new(value),
+            value_update: shared_value_update_proxy::<T>,
+            type_name,
+
+            cache_key,
+            cache_type: T::cache_type(),
+
+            dirty: false,
+            last_access: Instant::now(),
+        });
+        Ok(())
+    }
+
+    fn initialize_value<T: State + Sync>(
+        states: &StateRegistry,
+        cache_key: StateKey,
+        value: &mut Option<InternalState<SharedValue>>,
+        params: T::Parameter,
+    ) -> anyhow::Result<()> {
+        let type_name = any::type_name::<T>();
+        let value = match value {
+            Some(value) => value,
+            None => {
+                let state =
+                    T::create(states, params).with_context(|| format!("create {}", type_name))?;
+
+                value.insert(InternalState {
+                    value: Box::new(state),
+                    value_update: shared_value_update_proxy::<T>,
+                    type_name,
+
+                    cache_key,
+                    cache_type: T::cache_type(),
+
+                    dirty: false,
+                    last_access: Instant::now(),
+                })
+            }
+        };
+
+        if value.dirty {
+            (value.value_update)(&mut value.value, states)
+                .with_context(|| format!("update {}", type_name))?;
+            value.dirty = false;
+        }
+
+        Ok(())
+    }
+
+    /// Lock an entry while tracking the lock holders.
+    /// Fails instead of blocking if acquiring the lock would deadlock.
+    fn lock_entry<'a, G>(
+        &'a self,
+        index: usize,
+        type_name: &'static str,
+        acquire: impl FnOnce(&'a SharedEntry) -> G,
+    ) -> Result<(G, EntryHolder<'a>), StateLockCycle> {
+        let thread = thread::current().id();
+        {
+            let mut lock_graph = lock(&self.lock_graph);
+            if lock_graph.would_deadlock(thread, index) {
+                return Err(StateLockCycle { type_name });
+            }
+
+            lock_graph.waiting.insert(thread, index);
+        }
+
+        let guard = acquire(&self.states[index]);
+
+        let mut lock_graph = lock(&self.lock_graph);
+        lock_graph.waiting.remove(&thread);
+        lock_graph.holders.entry(index).or_default().push(thread);
+
+        Ok((
+            guard,
+            EntryHolder {
+                lock_graph: &self.lock_graph,
+                index,
+                thread,
+            },
+        ))
+    }
+
+    fn read_entry<'a>(
+        &'a self,
+        index: usize,
+        type_name: &'static str,
+    ) -> Result<(SharedEntryReadGuard<'a>, EntryHolder<'a>), StateLockCycle> {
+        self.lock_entry(index, type_name, |entry| {
+            entry.read().unwrap_or_else(PoisonError::into_inner)
+        })
+    }
+
+    /// Resolve a state using the given registry to create or update it.
+    pub fn resolve<T: State + Sync>(
+        &self,
+        states: &StateRegistry,
+        params: T::Parameter,
+    ) -> anyhow::Result<SharedStateRef<'_, T>> {
+        let type_name = any::type_name::<T>();
+        let (cache_key, index) = lock(&self.allocator).allocate_state::<T>(&params)?;
+
+        let (state, holder) = self.read_entry(index, type_name)?;
+        if state.as_ref().is_some_and(|state| !state.dirty) {
+            return Ok(Self::map_value(state, holder));
+        }
+        drop(state);
+        drop(holder);
+
+        {
+            /* another thread might have initialized the state while waiting for the lock */
+            let (mut state, _holder) = self.lock_entry(index, type_name, |entry| {
+                entry.write().unwrap_or_else(PoisonError::into_inner)
+            })?;
+            Self::initialize_value::<T>(states, cache_key, &mut state, params)?;
+        }
+
+        let (state, holder) = self.read_entry(index, type_name)?;
+        Ok(Self::map_value(state, holder))
+    }
+
+    fn map_value<'a, T: State + Sync>(
+        state: SharedEntryReadGuard<'a>,
+        holder: EntryHolder<'a>,
+    ) -> SharedStateRef<'a, T> {
+        let value = RwLockReadGuard::map(state, |state| {
+            let state = state.as_ref().expect("to be present");
+            state.value.downcast_ref::<T>().expect("to be of type T")
+        });
+
+        SharedStateRef {
+            value,
+            _holder: holder,
+        }
+    }
+
+    /// Get a state without creating or updating it.
+    /// Returns None if the state is not present or locked by the current thread.
+    pub fn get<T: State + Sync>(&self, params: T::Parameter) -> Option<SharedStateRef<'_, T>> {
+        let (_cache_key, index) =
+            lock(&self.allocator).calculate_state_index::<T>(&params, false)?;
+
+        let (state, holder) = self.read_entry(index, any::type_name::<T>()).ok()?;
+        let value =
+            RwLockReadGuard::filter_map(state, |state| state.as_ref()?.value.downcast_ref::<T>())
+                .ok()?;
+
+        Some(SharedStateRef {
+            value,
+            _holder: holder,
+        })
+    }
+
+    /// Resolve the states for all given parameters.
+    /// Every worker registry will be used by its own thread.
+    /// The results are in the same order as the parameters.
+    pub fn resolve_parallel<T: State + Sync>(
+        &self,
+        workers: &mut [StateRegistry],
+        params: Vec<T::Parameter>,
+    ) -> Vec<anyhow::Result<()>>
+    where
+        T::Parameter: Send,
+    {
+        if workers.len() <= 1 || params.len() <= 1 {
+            let Some(worker) = workers.first() else {
+                return params
+                    .iter()
+                    .map(|_| Err(anyhow::anyhow!("missing a worker registry")))
+                    .collect();
+            };
+
+            return params
+                .into_iter()
+                .map(|params| self.resolve::<T>(worker, params).map(|_| ()))
+                .collect();
+        }
+
+        let param_count = params.len();
+        let queue = Mutex::new(params.into_iter().enumerate().collect::<Vec<_>>());
+        let results = Mutex::new(
+            (0..param_count)
+                .map(|_| None)
+                .collect::<Vec<Option<anyhow::Result<()>>>>(),
+        );
+
+        thread::scope(|scope| {
+            for worker in workers.iter_mut().take(param_count) {
+                let queue = &queue;
+                let results = &results;
+                scope.spawn(move || loop {
+                    let Some((index, params)) = lock(queue).pop() else {
+                        break;
+                    };
+
+                    let result = self.resolve::<T>(worker, params).map(|_| ());
+                    lock(results)[index] = Some(result);
+                });
+            }
+        });
+
+        results
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+            .into_iter()
+            .map(|result| result.unwrap_or_else(|| Err(anyhow::anyhow!("worker panicked"))))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{
+            atomic::{
+                AtomicBool,
+                Ordering,
+            },
+            Arc,
+            Barrier,
+            LazyLock,
+        },
+        thread,
+    };
+
+    use super::{
+        SharedStateRegistry,
+        StateLockCycle,
+    };
+    use crate::{
+        State,
+        StateCacheType,
+        StateRegistry,
+    };
+
+    struct StateBase(usize);
+    impl State for StateBase {
+        type Parameter = ();
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            Ok(Self(42))
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+    }
+
+    struct StatePlayer(usize);
+    impl State for StatePlayer {
+        type Parameter = usize;
+
+        fn create(states: &StateRegistry, params: Self::Parameter) -> anyhow::Result<Self> {
+            let base = states.resolve::<StateBase>(())?;
+            Ok(Self(base.0 + params))
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Volatile
+        }
+    }
+
+    #[test]
+    fn test_resolve() {
+        let registry = SharedStateRegistry::new(4);
+        let states = StateRegistry::new(4);
+
+        assert_eq!(registry.resolve::<StatePlayer>(&states, 1).unwrap().0, 43);
+        assert_eq!(registry.get::<StatePlayer>(1).unwrap().0, 43);
+        assert!(registry.get::<StatePlayer>(2).is_none());
+
+        /* the dependency has been resolved within the local registry */
+        assert!(states.get::<StateBase>(()).is_some());
+        assert!(registry.get::<StateBase>(()).is_none());
+    }
+
+    #[test]
+    fn test_parallel() {
+        let registry = SharedStateRegistry::new(32);
+        let mut workers = (0..4).map(|_| StateRegistry::new(4)).collect::<Vec<_>>();
+
+        let results = registry.resolve_parallel::<StatePlayer>(&mut workers, (0..16).collect());
+        assert_eq!(results.len(), 16);
+        assert!(results.iter().all(|result| result.is_ok()));
+
+        for index in 0..16 {
+            assert_eq!(registry.get::<StatePlayer>(index).unwrap().0, 42 + index);
+        }
+        assert_eq!(registry.stats().used, 16);
+
+        /* dependencies are resolved within the worker registries */
+        assert!(workers
+            .iter()
+            .any(|worker| worker.get::<StateBase>(()).is_some()));
+        assert!(registry.get::<StateBase>(()).is_none());
+
+        /* volatile states expire */
+        registry.invalidate_states();
+        assert!(registry.get::<StatePlayer>(3).is_none());
+        assert_eq!(registry.stats().used, 0);
+    }
+
+    #[test]
+    fn test_missing_worker() {
+        let registry = SharedStateRegistry::new(4);
+        let results = registry.resolve_parallel::<StatePlayer>(&mut [], vec![1]);
+        assert!(results[0].is_err());
+    }
+
+    /// Resolves its dependency from the shared registry
+    struct StateSharedPlayer(usize);
+    impl State for StateSharedPlayer {
+        type Parameter = usize;
+
+        fn create(states: &StateRegistry, params: Self::Parameter) -> anyhow::Result<Self> {
+            let base = states.resolve_shared::<StateBase>(())?;
+            Ok(Self(base.0 + params))
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Volatile
+        }
+    }
+
+    #[test]
+    fn test_shared_dependencies() {
+        let registry = Arc::new(SharedStateRegistry::new(32));
+        registry.set(StateBase(10), ()).unwrap();
+
+        let mut workers = (0..4)
+            .map(|_| {
+                let mut states = StateRegistry::new(4);
+                states.set_shared_registry(registry.clone());
+                states
+            })
+            .collect::<Vec<_>>();
+
+        let results =
+            registry.resolve_parallel::<StateSharedPlayer>(&mut workers, (0..16).collect());
+        assert!(results.iter().all(|result| result.is_ok()));
+        for index in 0..16 {
+            assert_eq!(
+                registry.get::<StateSharedPlayer>(index).unwrap().0,
+                10 + index
+            );
+        }
+
+        /* the dependency has been resolved from the shared registry only */
+        assert!(workers
+            .iter()
+            .all(|worker| worker.get::<StateBase>(()).is_none()));
+
+        /* without a shared registry the state will be resolved locally */
+        let states = StateRegistry::new(4);
+        assert_eq!(states.resolve_shared::<StateBase>(()).unwrap().0, 42);
+        assert!(states.get::<StateBase>(()).is_some());
+    }
+
+    #[test]
+    fn test_invalidate_skips_locked() {
+        let registry = SharedStateRegistry::new(4);
+        let states = StateRegistry::new(4);
+        assert!(registry.resolve::<StatePlayer>(&states, 1).is_ok());
+        assert!(registry.resolve::<StatePlayer>(&states, 2).is_ok());
+
+        let player = registry.get::<StatePlayer>(1).unwrap();
+        registry.invalidate_states();
+        assert_eq!(player.0, 43);
+        drop(player);
+
+        assert!(registry.get::<StatePlayer>(1).is_some());
+        assert!(registry.get::<StatePlayer>(2).is_none());
+    }
+
+    fn is_lock_cycle(error: &anyhow::Error) -> bool {
+        error.chain().any(|error| error.is::<StateLockCycle>())
+    }
+
+    #[test]
+    fn test_reentrant_resolve() {
+        let registry = SharedStateRegistry::new(4);
+        let states = StateRegistry::new(4);
+
+        let player = registry.resolve::<StatePlayer>(&states, 1).unwrap();
+        let error = registry.resolve::<StatePlayer>(&states, 1).err().unwrap();
+        assert!(is_lock_cycle(&error));
+        assert!(registry.get::<StatePlayer>(1).is_none());
+
+        /* other states are not affected */
+        assert!(registry.resolve::<StatePlayer>(&states, 2).is_ok());
+
+        drop(player);
+        assert!(registry.resolve::<StatePlayer>(&states, 1).is_ok());
+        assert!(registry.get::<StatePlayer>(1).is_some());
+    }
+
+    static CYCLE_REGISTRY: LazyLock<SharedStateRegistry> =
+        LazyLock::new(|| SharedStateRegistry::new(4));
+    static CYCLE_BARRIER: LazyLock<Barrier> = LazyLock::new(|| Barrier::new(2));
+
+    /// Resolves StateCycleB while being created
+    struct StateCycleA;
+    static CYCLE_A_CREATED: AtomicBool = AtomicBool::new(false);
+
+    impl State for StateCycleA {
+        type Parameter = ();
+
+        fn create(states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            if !CYCLE_A_CREATED.swap(true, Ordering::SeqCst) {
+                /* both threads hold the lock of their state */
+                CYCLE_BARRIER.wait();
+            }
+
+            CYCLE_REGISTRY.resolve::<StateCycleB>(states, ())?;
+            Ok(Self)
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Volatile
+        }
+    }
+
+    /// Resolves StateCycleA while being created
+    struct StateCycleB;
+    static CYCLE_B_CREATED: AtomicBool = AtomicBool::new(false);
+
+    impl State for StateCycleB {
+        type Parameter = ();
+
+        fn create(states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            if !CYCLE_B_CREATED.swap(true, Ordering::SeqCst) {
+                CYCLE_BARRIER.wait();
+            }
+
+            CYCLE_REGISTRY.resolve::<StateCycleA>(states, ())?;
+            Ok(Self)
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Volatile
+        }
+    }
+
+    #[test]
+    fn test_lock_cycle() {
+        let (result_a, result_b) = thread::scope(|scope| {
+            let thread_a = scope.spawn(|| {
+                let states = StateRegistry::new(4);
+                CYCLE_REGISTRY
+                    .resolve::<StateCycleA>(&states, ())
+                    .map(|_| ())
+            });
+            let thread_b = scope.spawn(|| {
+                let states = StateRegistry::new(4);
+                CYCLE_REGISTRY
+                    .resolve::<StateCycleB>(&states, ())
+                    .map(|_| ())
+            });
+
+            (thread_a.join().unwrap(), thread_b.join().unwrap())
+        });
+
+        /* the states depend on each other, hence neither can be created */
+        assert!(is_lock_cycle(&result_a.err().unwrap()));
+        assert!(is_lock_cycle(&result_b.err().unwrap()));
+    }
+}
@@ -0,0 +1,194 @@
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    fmt,
+    time::Duration,
+};
+
+use crate::{
+    InternalState,
+    StateCacheType,
+};
+
+/// Error returned when no more state entries can be allocated.
+#[derive(Debug, Clone)]
+pub struct StateCapacityExceeded {
+    pub capacity: usize,
+    pub type_name: &'static str,
+}
+
+impl fmt::Display for StateCapacityExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "state capacity of {} exceeded while allocating {} (increase the registry capacity or use StateCapacityPolicy::Grow)",
+            self.capacity, self.type_name
+        )
+    }
+}
+
+impl std::error::Error for StateCapacityExceeded {}
+
+/// How the registry should behave when all state entries are in use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StateCapacityPolicy {
+    /// Allocating a new state fails with `StateCapacityExceeded`
+    #[default]
+    Fixed,
+
+    /// Allocating a new state fails with `StateCapacityExceeded`
+    /// but the capacity will be doubled (up to `max_capacity`) on the next `invalidate_states` call.
+    Grow { max_capacity: usize },
+}
+
+/// Number of evicted states by their cache type
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StateEvictions {
+    pub persistent: usize,
+    pub timed: usize,
+    pub volatile: usize,
+}
+
+impl StateEvictions {
+    fn record(&mut self, cache_type: &StateCacheType) {
+        match cache_type {
+            StateCacheType::Persistent => self.persistent += 1,
+            StateCacheType::Timed(_) => self.timed += 1,
+            StateCacheType::Volatile => self.volatile += 1,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.persistent + self.timed + self.volatile
+    }
+}
+
+/// Statistics of all states of one type
+#[derive(Debug, Default, Clone)]
+pub struct StateTypeStats {
+    pub type_name: &'static str,
+
+    /// Currently allocated entries
+    pub entries: usize,
+
+    pub create_count: usize,
+    pub create_duration: Duration,
+
+    pub update_count: usize,
+    pub update_duration: Duration,
+
+    /// Resolves which did not require a create or update
+    pub hits: usize,
+    /// Resolves which required a create or update
+    pub misses: usize,
+
+    pub evictions: StateEvictions,
+}
+
+impl StateTypeStats {
+    /// Total time spend creating and updating the states
+    pub fn total_duration(&self) -> Duration {
+        self.create_duration + self.update_duration
+    }
+}
+
+/// Snapshot of the registry statistics.
+/// Per type counters are only collected while instrumentation is enabled.
+#[derive(Debug, Default, Clone)]
+pub struct StateRegistryStats {
+    pub capacity: usize,
+    pub used: usize,
+
+    /// Number of failed allocations because of the capacity limit
+    pub capacity_exceeded: usize,
+    pub evictions: StateEvictions,
+
+    /// Per type statistics ordered by their total duration (descending)
+    pub states: Vec<StateTypeStats>,
+}
+
+#[derive(Default)]
+pub(crate) struct StateInstrumentation {
+    types: HashMap<TypeId, StateTypeStats>,
+    evictions: StateEvictions,
+}
+
+impl StateInstrumentation {
+    fn type_stats(&mut self, type_id: TypeId, type_name: &'static str) -> &mut StateTypeStats {
+        self.types.entry(type_id).or_insert_with(|| StateTypeStats {
+            type_name,
+            ..Default::default()
+        })
+    }
+
+    pub fn record_create(&mut self, type_id: TypeId, type_name: &'static str, duration: Duration) {
+        let stats = self.type_stats(type_id, type_name);
+        stats.create_count += 1;
+        stats.create_duration += duration;
+    }
+
+    pub fn record_update(&mut self, type_id: TypeId, type_name: &'static str, duration: Duration) {
+        let stats = self.type_stats(type_id, type_name);
+        stats.update_count += 1;
+        stats.update_duration += duration;
+    }
+
+    pub fn record_access(&mut self, type_id: TypeId, type_name: &'static str, hit: bool) {
+        let stats = self.type_stats(type_id, type_name);
+        if hit {
+            stats.hits += 1;
+        } else {
+            stats.misses += 1;
+        }
+    }
+
+    pub fn record_eviction<V: ?Sized>(&mut self, state: &InternalState<V>) {
+        self.evictions.record(&state.cache_type);
+        self.type_stats(state.cache_key.0, state.type_name)
+            .evictions
+            .record(&state.cache_type);
+    }
+}
+
+/// Build a stats snapshot out of the currently allocated states
+pub(crate) fn build_stats(
+    capacity: usize,
+    capacity_exceeded: usize,
+    states: impl Iterator<Item = (TypeId, &'static str)>,
+    instrumentation: Option<&StateInstrumentation>,
+) -> StateRegistryStats {
+    let mut types = instrumentation
+        .map(|instrumentation| instrumentation.types.clone())
+        .unwrap_or_default();
+
+    let mut used = 0;
+    for (type_id, type_name) in states {
+        used += 1;
+        types
+            .entry(type_id)
+            .or_insert_with(|| StateTypeStats {
+                type_name,
+                ..Default::default()
+            })
+            .entries += 1;
+    }
+
+    let mut states = types.into_values().collect::<Vec<_>>();
+    states.sort_by(|a, b| {
+        b.total_duration()
+            .cmp(&a.total_duration())
+            .then_with(|| b.entries.cmp(&a.entries))
+    });
+
+    StateRegistryStats {
+        capacity,
+        used,
+
+        capacity_exceeded,
+        evictions: instrumentation
+            .map(|instrumentation| instrumentation.evictions)
+            .unwrap_or_default(),
+
+        states,
+    }
+}